@@ -0,0 +1,19 @@
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct CommentData {
+    value: String,
+}
+
+impl CommentData {
+    pub(crate) fn new(value: String) -> CommentData {
+        CommentData { value }
+    }
+
+    /// The text between `<!--` and `-->`.
+    pub fn text(&self) -> &str {
+        &self.value
+    }
+
+    pub(crate) fn set_value(&mut self, value: String) {
+        self.value = value;
+    }
+}
@@ -0,0 +1,33 @@
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct DoctypeData {
+    name: String,
+    public_id: Option<String>,
+    system_id: Option<String>,
+}
+
+impl DoctypeData {
+    pub(crate) fn new(
+        name: String,
+        public_id: Option<String>,
+        system_id: Option<String>,
+    ) -> DoctypeData {
+        DoctypeData {
+            name,
+            public_id,
+            system_id,
+        }
+    }
+
+    /// The doctype name, `html` for every modern document.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn public_id(&self) -> Option<&str> {
+        self.public_id.as_deref()
+    }
+
+    pub fn system_id(&self) -> Option<&str> {
+        self.system_id.as_deref()
+    }
+}
@@ -1,31 +1,154 @@
-use crate::node::Node;
-use serde::Serialize;
-use std::{cell::RefCell, ops::Deref, rc::Rc};
+use crate::comment::CommentData;
+use crate::doctype::DoctypeData;
+use crate::element::{Attribute, ElementData, Namespace};
+use crate::node::{Descendants, Node, NodeData, NodeId, TreeNode};
+use crate::span::Span;
+use crate::text::TextData;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
-pub struct Document(Rc<RefCell<DocumentData>>);
+/// An HTML document, owning every node created for it.
+///
+/// Nodes live in a single arena and refer to each other through [`NodeId`]s,
+/// so building and walking the tree does not allocate per link. Nodes that
+/// are created but never attached stay in the arena until the document is
+/// dropped.
+///
+/// Documents hold no shared or interior-mutable state, so they are `Send`
+/// and `Sync`: a parsed document can be put in an `Arc` and read from many
+/// threads at once.
+#[derive(Debug, Clone)]
+pub struct Document {
+    nodes: Vec<TreeNode>,
+    quirks_mode: QuirksMode,
+}
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
-pub struct DocumentData {
-    children: Vec<Node>,
+/// How closely a browser would follow the standards when rendering the
+/// document, decided by its doctype.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum QuirksMode {
+    #[default]
+    NoQuirks,
+    LimitedQuirks,
+    Quirks,
 }
 
 impl Document {
-    pub fn new(children: Vec<Node>) -> Document {
-        Document(Rc::new(RefCell::new(DocumentData { children })))
+    /// The id of the document node itself, which is the root of the tree.
+    pub const ROOT: NodeId = NodeId(0);
+
+    pub fn new() -> Document {
+        Document {
+            nodes: vec![TreeNode::new(NodeData::Document)],
+            quirks_mode: QuirksMode::NoQuirks,
+        }
+    }
+
+    pub fn quirks_mode(&self) -> QuirksMode {
+        self.quirks_mode
+    }
+
+    pub fn set_quirks_mode(&mut self, quirks_mode: QuirksMode) {
+        self.quirks_mode = quirks_mode;
+    }
+
+    pub fn root(&self) -> Node<'_> {
+        self.node(Document::ROOT)
     }
-}
 
-impl Deref for Document {
-    type Target = RefCell<DocumentData>;
+    /// Returns a view of the node with the given id.
+    ///
+    /// Panics if the id was not created by this document.
+    pub fn node(&self, id: NodeId) -> Node<'_> {
+        assert!(id.0 < self.nodes.len(), "node id out of bounds");
+        Node::new(self, id)
+    }
+
+    pub fn descendants(&self) -> Descendants<'_> {
+        self.root().descendants()
+    }
+
+    /// The number of nodes in the arena, including detached nodes but not
+    /// the document node, which every document has. Node ids index an arena
+    /// of `len() + 1` nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    /// Whether the document has no nodes besides the document node, i.e.
+    /// `len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates a detached HTML element.
+    pub fn create_element(&mut self, name: String, attributes: Vec<Attribute>) -> NodeId {
+        self.create_element_ns(name, Namespace::Html, attributes)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Creates a detached element in the given namespace.
+    pub fn create_element_ns(
+        &mut self,
+        name: String,
+        namespace: Namespace,
+        attributes: Vec<Attribute>,
+    ) -> NodeId {
+        self.push(NodeData::Element(ElementData::new(
+            name, namespace, attributes,
+        )))
+    }
+
+    /// Creates a detached text node.
+    pub fn create_text(&mut self, value: String) -> NodeId {
+        self.push(NodeData::Text(TextData::new(value)))
+    }
+
+    /// Creates a detached comment.
+    pub fn create_comment(&mut self, value: String) -> NodeId {
+        self.push(NodeData::Comment(CommentData::new(value)))
+    }
+
+    /// Creates a detached doctype.
+    pub fn create_doctype(
+        &mut self,
+        name: String,
+        public_id: Option<String>,
+        system_id: Option<String>,
+    ) -> NodeId {
+        self.push(NodeData::Doctype(DoctypeData::new(
+            name, public_id, system_id,
+        )))
+    }
+
+    /// Returns the element data of `id` for modification, or `None` if the
+    /// node is not an element.
+    pub fn element_mut(&mut self, id: NodeId) -> Option<&mut ElementData> {
+        match &mut self.nodes[id.0].data {
+            NodeData::Element(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn set_span(&mut self, id: NodeId, span: Option<Span>) {
+        self.nodes[id.0].span = span;
+    }
+
+    pub(crate) fn tree_node(&self, id: NodeId) -> &TreeNode {
+        &self.nodes[id.0]
+    }
+
+    pub(crate) fn tree_node_mut(&mut self, id: NodeId) -> &mut TreeNode {
+        &mut self.nodes[id.0]
+    }
+
+    pub(crate) fn push(&mut self, data: NodeData) -> NodeId {
+        self.nodes.push(TreeNode::new(data));
+        NodeId(self.nodes.len() - 1)
     }
 }
 
-impl Document {
-    pub fn append_child(&mut self, child: Node) {
-        self.0.deref().borrow_mut().children.push(child);
+impl Default for Document {
+    fn default() -> Document {
+        Document::new()
     }
 }
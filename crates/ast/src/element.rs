@@ -1,40 +1,193 @@
 use crate::node::Node;
-use serde::Serialize;
-use std::{cell::RefCell, ops::Deref, rc::Rc};
+use std::ops::Deref;
+use std::str::SplitAsciiWhitespace;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
-pub struct Element(Rc<RefCell<ElementData>>);
-
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct ElementData {
     name: String,
+    namespace: Namespace,
     attributes: Vec<Attribute>,
-    children: Vec<Node>,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
+/// The namespace an element is in. HTML documents can hold SVG and MathML
+/// elements, whose names and attributes follow different rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Namespace {
+    #[default]
+    Html,
+    Svg,
+    MathMl,
+}
+
+impl Namespace {
+    /// The namespace URI, as used in XML.
+    pub fn uri(self) -> &'static str {
+        match self {
+            Namespace::Html => "http://www.w3.org/1999/xhtml",
+            Namespace::Svg => "http://www.w3.org/2000/svg",
+            Namespace::MathMl => "http://www.w3.org/1998/Math/MathML",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attribute {
     name: String,
     value: String,
 }
 
-impl Element {
-    pub fn new(name: String, attributes: Vec<Attribute>, children: Vec<Node>) -> Element {
-        Element(Rc::new(RefCell::new(ElementData {
+impl ElementData {
+    pub(crate) fn new(
+        name: String,
+        namespace: Namespace,
+        attributes: Vec<Attribute>,
+    ) -> ElementData {
+        ElementData {
             name,
+            namespace,
             attributes,
-            children,
-        })))
+        }
+    }
+
+    pub fn tag_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn namespace(&self) -> Namespace {
+        self.namespace
+    }
+
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    /// The value of the `id` attribute, if any.
+    pub fn id(&self) -> Option<&str> {
+        self.get_attribute("id")
+    }
+
+    /// Iterates over the whitespace separated names in the `class` attribute.
+    pub fn class_list(&self) -> SplitAsciiWhitespace<'_> {
+        self.get_attribute("class")
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+    }
+
+    /// Whether `attribute` is called `name`. HTML attribute names are
+    /// ASCII case-insensitive, while SVG and MathML ones such as `viewBox`
+    /// have to match exactly.
+    fn is_named(&self, attribute: &Attribute, name: &str) -> bool {
+        match self.namespace {
+            Namespace::Html => attribute.name.eq_ignore_ascii_case(name),
+            Namespace::Svg | Namespace::MathMl => attribute.name == name,
+        }
+    }
+
+    /// Returns the value of the attribute with the given name. Names are
+    /// compared ASCII case-insensitively on HTML elements and exactly on
+    /// SVG and MathML ones.
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| self.is_named(attribute, name))
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Sets the value of an attribute, adding it if it doesn't exist yet.
+    /// New attribute names are lowercased on HTML elements and kept as they
+    /// are on SVG and MathML ones.
+    pub fn set_attribute(&mut self, name: &str, value: &str) {
+        match self
+            .attributes
+            .iter()
+            .position(|attribute| self.is_named(attribute, name))
+        {
+            Some(index) => self.attributes[index].value = value.to_string(),
+            None => {
+                let name = match self.namespace {
+                    Namespace::Html => name.to_ascii_lowercase(),
+                    Namespace::Svg | Namespace::MathMl => name.to_string(),
+                };
+                self.attributes
+                    .push(Attribute::new(name, value.to_string()))
+            }
+        }
+    }
+
+    /// Removes an attribute, returning its value if it was present. Names
+    /// are compared as in [`get_attribute`](Self::get_attribute).
+    pub fn remove_attribute(&mut self, name: &str) -> Option<String> {
+        let index = self
+            .attributes
+            .iter()
+            .position(|attribute| self.is_named(attribute, name))?;
+        Some(self.attributes.remove(index).value)
     }
 }
 
-impl Element {
-    pub fn append_attribute(&mut self, attribute: Attribute) {
-        self.0.deref().borrow_mut().attributes.push(attribute);
+/// A borrowed view of an element node.
+///
+/// Dereferences to [`Node`] for navigation.
+#[derive(Debug, Clone, Copy)]
+pub struct Element<'a> {
+    node: Node<'a>,
+    data: &'a ElementData,
+}
+
+impl<'a> Element<'a> {
+    pub(crate) fn new(node: Node<'a>, data: &'a ElementData) -> Element<'a> {
+        Element { node, data }
+    }
+
+    pub fn as_node(&self) -> Node<'a> {
+        self.node
+    }
+
+    pub fn data(&self) -> &'a ElementData {
+        self.data
+    }
+
+    pub fn tag_name(&self) -> &'a str {
+        self.data.tag_name()
+    }
+
+    pub fn namespace(&self) -> Namespace {
+        self.data.namespace()
+    }
+
+    pub fn attributes(&self) -> &'a [Attribute] {
+        self.data.attributes()
     }
 
-    pub fn append_child(&mut self, child: Node) {
-        self.0.deref().borrow_mut().children.push(child);
+    pub fn get_attribute(&self, name: &str) -> Option<&'a str> {
+        self.data.get_attribute(name)
+    }
+
+    pub fn id(&self) -> Option<&'a str> {
+        self.data.id()
+    }
+
+    pub fn class_list(&self) -> SplitAsciiWhitespace<'a> {
+        self.data.class_list()
+    }
+}
+
+impl PartialEq for Element<'_> {
+    fn eq(&self, other: &Element) -> bool {
+        self.node == other.node
+    }
+}
+
+impl Eq for Element<'_> {}
+
+impl<'a> Deref for Element<'a> {
+    type Target = Node<'a>;
+
+    fn deref(&self) -> &Node<'a> {
+        &self.node
     }
 }
 
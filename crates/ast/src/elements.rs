@@ -0,0 +1,156 @@
+//! Element categories from the HTML specification.
+
+/// Elements that never have children or an end tag.
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param",
+    "source", "track", "wbr",
+];
+
+/// Elements whose contents are raw text: no tags, no character references.
+const RAW_TEXT: &[&str] = &["iframe", "noembed", "noframes", "script", "style", "xmp"];
+
+/// Elements whose contents are text with character references, but no tags.
+const ESCAPABLE_RAW_TEXT: &[&str] = &["textarea", "title"];
+
+pub fn is_void(name: &str) -> bool {
+    VOID.contains(&name)
+}
+
+pub fn is_raw_text(name: &str) -> bool {
+    RAW_TEXT.contains(&name)
+}
+
+pub fn is_escapable_raw_text(name: &str) -> bool {
+    ESCAPABLE_RAW_TEXT.contains(&name)
+}
+
+/// Elements whose start tag closes an open `p` element.
+const CLOSES_PARAGRAPH: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "center",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "listing",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "summary",
+    "table",
+    "ul",
+];
+
+/// Elements that don't take part in inline layout: they are blocks, list
+/// items or table parts, or aren't rendered at all. Whitespace around them
+/// doesn't affect rendering.
+const BLOCK: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "caption",
+    "center",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "li",
+    "link",
+    "listing",
+    "main",
+    "menu",
+    "meta",
+    "nav",
+    "noscript",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "pre",
+    "script",
+    "search",
+    "section",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+];
+
+pub fn closes_paragraph(name: &str) -> bool {
+    CLOSES_PARAGRAPH.contains(&name)
+}
+
+pub fn is_block(name: &str) -> bool {
+    BLOCK.contains(&name)
+}
+
+/// Elements that are never rendered: document metadata, scripts, templates
+/// and `noscript`, which is hidden when scripting is enabled.
+const HIDDEN: &[&str] = &[
+    "base", "head", "link", "meta", "noscript", "script", "style", "template", "title",
+];
+
+pub fn is_hidden(name: &str) -> bool {
+    HIDDEN.contains(&name)
+}
+
+/// Whether an element is laid out as a block, list item or table part, as
+/// opposed to being inline or not rendered at all.
+pub fn is_layout_block(name: &str) -> bool {
+    is_block(name) && !is_hidden(name)
+}
@@ -0,0 +1,186 @@
+//! An approximation of the `innerText` getter from the HTML specification.
+//!
+//! Browsers compute `innerText` from the rendered layout. Without one, the
+//! default display of each element stands in for its computed style: what
+//! browsers hide by default is skipped, block elements start new lines,
+//! paragraphs are separated by blank lines, table cells are separated by tabs
+//! and whitespace is collapsed outside preformatted elements.
+
+use crate::document::Document;
+use crate::element::Element;
+use crate::elements;
+use crate::node::Node;
+use crate::text::Text;
+use crate::visit::{walk, Visit, Visitor};
+
+/// Elements that browsers don't render by default.
+const HIDDEN: &[&str] = &[
+    "area", "base", "basefont", "datalist", "head", "iframe", "link", "meta", "noembed",
+    "noframes", "object", "param", "rp", "script", "source", "style", "template", "title", "track",
+];
+
+/// Elements whose whitespace is rendered as written.
+const PREFORMATTED: &[&str] = &["listing", "plaintext", "pre", "textarea", "xmp"];
+
+/// Parts of tables, which are laid out in rows and cells rather than as
+/// blocks.
+const TABLE_PARTS: &[&str] = &[
+    "col", "colgroup", "tbody", "td", "tfoot", "th", "thead", "tr",
+];
+
+fn is_hidden(element: Element) -> bool {
+    HIDDEN.contains(&element.tag_name()) || element.get_attribute("hidden").is_some()
+}
+
+impl Document {
+    /// The text of the document as a browser would render it. See
+    /// [`Node::inner_text`].
+    pub fn inner_text(&self) -> String {
+        self.root().inner_text()
+    }
+}
+
+impl Node<'_> {
+    /// The text of this node as a browser would render it, approximating
+    /// `innerText`. Unlike [`text_content`](Node::text_content), it leaves
+    /// out scripts, styles and other elements that aren't displayed, puts
+    /// line breaks around blocks and for `br`, blank lines around
+    /// paragraphs, tabs between table cells, and collapses whitespace.
+    ///
+    /// Like `innerText`, an element that isn't displayed gives its text
+    /// content instead.
+    pub fn inner_text(&self) -> String {
+        if self.as_element().is_some_and(is_hidden) {
+            return self.text_content();
+        }
+        let mut renderer = Renderer::default();
+        walk(*self, &mut renderer);
+        renderer.output
+    }
+}
+
+#[derive(Default)]
+struct Renderer {
+    output: String,
+    /// Line breaks required before the next text. Runs of required breaks
+    /// collapse to the largest, and none are written at the start or end.
+    breaks: usize,
+    /// Whether collapsed whitespace is waiting to be written as a space.
+    space: bool,
+    /// How many of the open elements are preformatted.
+    preformatted: usize,
+}
+
+impl Renderer {
+    fn require_breaks(&mut self, count: usize) {
+        self.breaks = self.breaks.max(count);
+        self.space = false;
+    }
+
+    fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if !self.output.is_empty() {
+            if self.breaks > 0 {
+                self.output.extend(std::iter::repeat_n('\n', self.breaks));
+            } else if self.space && !self.output.ends_with(['\n', '\t']) {
+                self.output.push(' ');
+            }
+        }
+        self.breaks = 0;
+        self.space = false;
+        self.output.push_str(text);
+    }
+
+    fn push_collapsed(&mut self, text: &str) {
+        for (index, word) in text.split(|ch: char| ch.is_ascii_whitespace()).enumerate() {
+            if index > 0 {
+                self.space = true;
+            }
+            self.push(word);
+        }
+    }
+}
+
+fn following_siblings<'a>(node: Node<'a>) -> impl Iterator<Item = Node<'a>> {
+    std::iter::successors(node.next_sibling(), Node::next_sibling)
+}
+
+/// Whether a cell or row is followed by another one in the same row or
+/// table.
+fn has_following(element: Element, names: &[&str]) -> bool {
+    let is_match = |node: Node| {
+        node.as_element()
+            .is_some_and(|element| names.contains(&element.tag_name()))
+    };
+    if following_siblings(element.as_node()).any(is_match) {
+        return true;
+    }
+    // Rows in the row groups after this one are in the same table.
+    let group = element.parent().filter(|parent| {
+        parent
+            .as_element()
+            .is_some_and(|parent| matches!(parent.tag_name(), "tbody" | "thead" | "tfoot"))
+    });
+    element.tag_name() == "tr"
+        && group.is_some_and(|group| {
+            following_siblings(group).any(|group| group.children().any(is_match))
+        })
+}
+
+impl<'a> Visitor<'a> for Renderer {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        if is_hidden(element) {
+            return Visit::SkipChildren;
+        }
+        let name = element.tag_name();
+        if PREFORMATTED.contains(&name) {
+            self.preformatted += 1;
+        }
+        if name == "br" {
+            self.space = false;
+            self.push("\n");
+        } else if name == "p" {
+            self.require_breaks(2);
+        } else if elements::is_block(name) && !TABLE_PARTS.contains(&name) {
+            self.require_breaks(1);
+        }
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        if is_hidden(element) {
+            return Visit::Continue;
+        }
+        let name = element.tag_name();
+        if PREFORMATTED.contains(&name) {
+            self.preformatted -= 1;
+        }
+        match name {
+            "p" => self.require_breaks(2),
+            "td" | "th" if has_following(element, &["td", "th"]) => {
+                self.space = false;
+                self.push("\t");
+            }
+            "tr" if has_following(element, &["tr"]) => {
+                self.space = false;
+                self.push("\n");
+            }
+            name if elements::is_block(name) && !TABLE_PARTS.contains(&name) => {
+                self.require_breaks(1)
+            }
+            _ => {}
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        if self.preformatted > 0 {
+            self.push(text.text());
+        } else {
+            self.push_collapsed(text.text());
+        }
+        Visit::Continue
+    }
+}
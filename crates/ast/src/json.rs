@@ -0,0 +1,289 @@
+//! The serde representation of a document.
+//!
+//! Documents serialize to a stable, versioned format. In JSON it looks like
+//! this:
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "type": "document",
+//!   "nodes": [
+//!     {
+//!       "type": "element",
+//!       "name": "p",
+//!       "attributes": [{ "name": "class", "value": "intro" }]
+//!     },
+//!     { "type": "text", "value": "Hello", "parent": 0 }
+//!   ]
+//! }
+//! ```
+//!
+//! Every node is an object with a `type` of `document`, `element`, `text`,
+//! `comment` or `doctype`. Elements have a `name` and an ordered `attributes`
+//! array of `name`/`value` pairs, text and comment nodes have a `value`, and
+//! doctypes have a `name` and a `publicId` and `systemId` that may be `null`.
+//! SVG and MathML elements also have a `namespace` of `svg` or `mathml`. Only
+//! the document carries a `version`, which is [`VERSION`]; it changes
+//! whenever the format changes in a way that older readers would
+//! misinterpret. The document also has a `quirksMode` of `quirks` or
+//! `limited-quirks` unless it is in no-quirks mode.
+//!
+//! Nodes don't nest. The document lists all the nodes below it in document
+//! order in a flat `nodes` array, and each node there has a `parent` with the
+//! index of its parent element in that array, left out for the document's
+//! own children. This keeps the nesting the same however deep the tree is,
+//! so that deep trees take no more stack space to write or read than shallow
+//! ones, and stay within the nesting limits of formats such as JSON.
+//!
+//! When deserializing, `attributes` and `nodes` may be left out and default
+//! to empty arrays, and a missing `namespace` means HTML. Documents with any
+//! other version are rejected, as are parents that are not elements earlier
+//! in the array.
+//! Serializing a single [`Node`] produces the same object as it has inside a
+//! document, without a version or `parent`, and with the nodes below it in
+//! `nodes` if it is an element.
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::document::{Document, QuirksMode};
+use crate::element::{Attribute, Element, Namespace};
+use crate::node::{Node, NodeData, NodeId};
+use crate::text::Text;
+
+/// The version of the format written by this crate.
+pub const VERSION: u32 = 2;
+
+impl Serialize for Document {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let quirks = self.quirks_mode() != QuirksMode::NoQuirks;
+        let mut state = serializer.serialize_struct("Document", 3 + quirks as usize)?;
+        state.serialize_field("version", &VERSION)?;
+        state.serialize_field("type", "document")?;
+        if quirks {
+            state.serialize_field("quirksMode", &self.quirks_mode())?;
+        }
+        state.serialize_field("nodes", &Nodes(self.root()))?;
+        state.end()
+    }
+}
+
+impl Serialize for Node<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Flat {
+            node: *self,
+            parent: None,
+            nodes: true,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for Element<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_node().serialize(serializer)
+    }
+}
+
+/// A node on its own, with the index of its parent in the `nodes` array it
+/// is in and, if `nodes` is set, the nodes below it.
+struct Flat<'a> {
+    node: Node<'a>,
+    parent: Option<usize>,
+    nodes: bool,
+}
+
+impl Serialize for Flat<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self.node;
+        let (parent, nodes) = (self.parent.is_some() as usize, self.nodes as usize);
+        match node.data() {
+            NodeData::Document => {
+                let mut state = serializer.serialize_struct("Document", 1 + nodes)?;
+                state.serialize_field("type", "document")?;
+                if self.nodes {
+                    state.serialize_field("nodes", &Nodes(node))?;
+                }
+                state.end()
+            }
+            NodeData::Element(_) => {
+                let element = node.as_element().unwrap();
+                let foreign = element.namespace() != Namespace::Html;
+                let mut state = serializer
+                    .serialize_struct("Element", 3 + foreign as usize + parent + nodes)?;
+                state.serialize_field("type", "element")?;
+                state.serialize_field("name", element.tag_name())?;
+                if foreign {
+                    state.serialize_field("namespace", &element.namespace())?;
+                }
+                state.serialize_field("attributes", element.attributes())?;
+                if let Some(parent) = self.parent {
+                    state.serialize_field("parent", &parent)?;
+                }
+                if self.nodes {
+                    state.serialize_field("nodes", &Nodes(node))?;
+                }
+                state.end()
+            }
+            NodeData::Text(text) => {
+                let mut state = serializer.serialize_struct("Text", 2 + parent)?;
+                state.serialize_field("type", "text")?;
+                state.serialize_field("value", text.text())?;
+                if let Some(parent) = self.parent {
+                    state.serialize_field("parent", &parent)?;
+                }
+                state.end()
+            }
+            NodeData::Comment(comment) => {
+                let mut state = serializer.serialize_struct("Comment", 2 + parent)?;
+                state.serialize_field("type", "comment")?;
+                state.serialize_field("value", comment.text())?;
+                if let Some(parent) = self.parent {
+                    state.serialize_field("parent", &parent)?;
+                }
+                state.end()
+            }
+            NodeData::Doctype(doctype) => {
+                let mut state = serializer.serialize_struct("Doctype", 4 + parent)?;
+                state.serialize_field("type", "doctype")?;
+                state.serialize_field("name", doctype.name())?;
+                state.serialize_field("publicId", &doctype.public_id())?;
+                state.serialize_field("systemId", &doctype.system_id())?;
+                if let Some(parent) = self.parent {
+                    state.serialize_field("parent", &parent)?;
+                }
+                state.end()
+            }
+        }
+    }
+}
+
+impl Serialize for Text<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_node().serialize(serializer)
+    }
+}
+
+/// Serializes the nodes below a node as a flat sequence in document order.
+struct Nodes<'a>(Node<'a>);
+
+impl Serialize for Nodes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The open elements with their indices, from the outermost.
+        let mut ancestors: Vec<(NodeId, usize)> = Vec::new();
+        let root = self.0.id();
+        serializer.collect_seq(self.0.descendants().enumerate().map(|(index, node)| {
+            let parent = node.parent().map(|parent| parent.id()).unwrap_or(root);
+            while ancestors.last().is_some_and(|&(id, _)| id != parent) {
+                ancestors.pop();
+            }
+            let flat = Flat {
+                node,
+                parent: ancestors.last().map(|&(_, index)| index),
+                nodes: false,
+            };
+            if node.is_element() {
+                ancestors.push((node.id(), index));
+            }
+            flat
+        }))
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum DocumentRepr {
+    Document {
+        version: u32,
+        #[serde(default, rename = "quirksMode")]
+        quirks_mode: QuirksMode,
+        #[serde(default)]
+        nodes: Vec<FlatRepr>,
+    },
+}
+
+#[derive(serde::Deserialize)]
+struct FlatRepr {
+    #[serde(default)]
+    parent: Option<usize>,
+    #[serde(flatten)]
+    node: NodeRepr,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum NodeRepr {
+    Element {
+        name: String,
+        #[serde(default)]
+        namespace: Namespace,
+        #[serde(default)]
+        attributes: Vec<Attribute>,
+    },
+    Text {
+        value: String,
+    },
+    Comment {
+        value: String,
+    },
+    Doctype {
+        name: String,
+        #[serde(default, rename = "publicId")]
+        public_id: Option<String>,
+        #[serde(default, rename = "systemId")]
+        system_id: Option<String>,
+    },
+}
+
+impl<'de> Deserialize<'de> for Document {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Document, D::Error> {
+        let DocumentRepr::Document {
+            version,
+            quirks_mode,
+            nodes,
+        } = DocumentRepr::deserialize(deserializer)?;
+        if version != VERSION {
+            return Err(de::Error::custom(format_args!(
+                "unsupported document version {}, expected {}",
+                version, VERSION
+            )));
+        }
+
+        let mut document = Document::new();
+        document.set_quirks_mode(quirks_mode);
+        let mut ids = Vec::with_capacity(nodes.len());
+        for (index, FlatRepr { parent, node }) in nodes.into_iter().enumerate() {
+            let parent = match parent {
+                None => Document::ROOT,
+                Some(parent) => match ids.get(parent) {
+                    Some(&id) if document.node(id).is_element() => id,
+                    _ => {
+                        return Err(de::Error::custom(format_args!(
+                            "node {} has parent {}, which is not an element before it",
+                            index, parent
+                        )))
+                    }
+                },
+            };
+            let id = match node {
+                NodeRepr::Element {
+                    name,
+                    namespace,
+                    attributes,
+                } => document.create_element_ns(name, namespace, attributes),
+                NodeRepr::Text { value } => document.create_text(value),
+                NodeRepr::Comment { value } => document.create_comment(value),
+                NodeRepr::Doctype {
+                    name,
+                    public_id,
+                    system_id,
+                } => document.create_doctype(name, public_id, system_id),
+            };
+            document
+                .append_child(parent, id)
+                .expect("new nodes can be appended to documents and elements");
+            ids.push(id);
+        }
+        Ok(document)
+    }
+}
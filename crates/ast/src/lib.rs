@@ -1,9 +1,25 @@
+mod comment;
+mod doctype;
 mod document;
 mod element;
+pub mod elements;
+mod inner_text;
+#[cfg(feature = "serde")]
+pub mod json;
+mod mutation;
 mod node;
+mod span;
 mod text;
+mod visit;
+mod walker;
 
-pub use document::{Document, DocumentData};
-pub use element::{Attribute, Element, ElementData};
-pub use node::Node;
-pub use text::Text;
+pub use comment::CommentData;
+pub use doctype::DoctypeData;
+pub use document::{Document, QuirksMode};
+pub use element::{Attribute, Element, ElementData, Namespace};
+pub use mutation::HierarchyError;
+pub use node::{Ancestors, Children, Descendants, Node, NodeData, NodeId};
+pub use span::{Location, Span};
+pub use text::{Text, TextData};
+pub use visit::{walk, walk_mut, Visit, Visitor, VisitorMut};
+pub use walker::TreeWalker;
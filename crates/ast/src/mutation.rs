@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::document::Document;
+use crate::node::{NodeData, NodeId};
+
+/// The reason a node could not be inserted or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HierarchyError {
+    /// The parent is a text, comment or doctype node, which cannot have
+    /// children.
+    NotAParent,
+    /// The node is the document, or an inclusive ancestor of the parent it
+    /// was going to be inserted into.
+    InvalidChild,
+    /// The node was expected to be a child of the parent but is not.
+    NotAChild,
+}
+
+impl fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HierarchyError::NotAParent => write!(f, "node cannot have children"),
+            HierarchyError::InvalidChild => write!(f, "node cannot be inserted there"),
+            HierarchyError::NotAChild => write!(f, "node is not a child of the parent"),
+        }
+    }
+}
+
+impl Error for HierarchyError {}
+
+impl Document {
+    /// Appends `child` as the last child of `parent`, first removing it from
+    /// wherever it currently is in the tree.
+    pub fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), HierarchyError> {
+        self.insert_before(parent, child, None)
+    }
+
+    /// Inserts `child` into `parent` right before `reference`, or at the end
+    /// when `reference` is `None`. The child is removed from its current
+    /// position first.
+    pub fn insert_before(
+        &mut self,
+        parent: NodeId,
+        child: NodeId,
+        reference: Option<NodeId>,
+    ) -> Result<(), HierarchyError> {
+        self.validate_insertion(parent, child)?;
+        if let Some(reference) = reference {
+            if self.tree_node(reference).parent != Some(parent) {
+                return Err(HierarchyError::NotAChild);
+            }
+        }
+
+        // Inserting a node before itself keeps it where it is.
+        let reference = if reference == Some(child) {
+            self.tree_node(child).next_sibling
+        } else {
+            reference
+        };
+
+        self.detach(child);
+
+        let previous = match reference {
+            Some(reference) => self.tree_node(reference).previous_sibling,
+            None => self.tree_node(parent).last_child,
+        };
+        match previous {
+            Some(previous) => self.tree_node_mut(previous).next_sibling = Some(child),
+            None => self.tree_node_mut(parent).first_child = Some(child),
+        }
+        match reference {
+            Some(reference) => self.tree_node_mut(reference).previous_sibling = Some(child),
+            None => self.tree_node_mut(parent).last_child = Some(child),
+        }
+
+        let node = self.tree_node_mut(child);
+        node.parent = Some(parent);
+        node.previous_sibling = previous;
+        node.next_sibling = reference;
+        Ok(())
+    }
+
+    /// Removes `child` from `parent`. The child and its subtree stay in the
+    /// document and can be inserted again.
+    pub fn remove_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), HierarchyError> {
+        if self.tree_node(child).parent != Some(parent) {
+            return Err(HierarchyError::NotAChild);
+        }
+        self.detach(child);
+        Ok(())
+    }
+
+    /// Puts `replacement` where `node` is in the tree and detaches `node`.
+    /// Does nothing if `node` has no parent.
+    pub fn replace_with(
+        &mut self,
+        node: NodeId,
+        replacement: NodeId,
+    ) -> Result<(), HierarchyError> {
+        let parent = match self.tree_node(node).parent {
+            Some(parent) => parent,
+            None => return Ok(()),
+        };
+        if node == replacement {
+            return Ok(());
+        }
+        self.insert_before(parent, replacement, Some(node))?;
+        self.detach(node);
+        Ok(())
+    }
+
+    /// Unlinks the node from its parent and siblings, keeping its children.
+    pub fn detach(&mut self, id: NodeId) {
+        let node = self.tree_node_mut(id);
+        let parent = node.parent.take();
+        let previous = node.previous_sibling.take();
+        let next = node.next_sibling.take();
+
+        if let Some(previous) = previous {
+            self.tree_node_mut(previous).next_sibling = next;
+        } else if let Some(parent) = parent {
+            self.tree_node_mut(parent).first_child = next;
+        }
+
+        if let Some(next) = next {
+            self.tree_node_mut(next).previous_sibling = previous;
+        } else if let Some(parent) = parent {
+            self.tree_node_mut(parent).last_child = previous;
+        }
+    }
+
+    /// Sets the value of a text or comment node, or replaces all children of
+    /// a document or element with a single text node (no node at all for an
+    /// empty string). Does nothing for doctypes.
+    pub fn set_text_content(&mut self, id: NodeId, value: &str) {
+        match &mut self.tree_node_mut(id).data {
+            NodeData::Text(text) => return text.set_value(value.to_string()),
+            NodeData::Comment(comment) => return comment.set_value(value.to_string()),
+            NodeData::Doctype(_) => return,
+            NodeData::Document | NodeData::Element(_) => {}
+        }
+
+        while let Some(child) = self.tree_node(id).first_child {
+            self.detach(child);
+        }
+        if !value.is_empty() {
+            let text = self.create_text(value.to_string());
+            self.append_child(id, text)
+                .expect("a new text node can always be appended");
+        }
+    }
+
+    /// Copies a node, and with `deep` its whole subtree, into a new detached
+    /// node in this document.
+    ///
+    /// Panics if `id` is the document node; clone the `Document` instead.
+    pub fn clone_node(&mut self, id: NodeId, deep: bool) -> NodeId {
+        assert!(id != Document::ROOT, "the document node cannot be cloned");
+        let copy = self.push(self.tree_node(id).data.clone());
+        if !deep {
+            return copy;
+        }
+
+        // Pairs of (original, copy) whose children still need to be copied.
+        let mut pending = vec![(id, copy)];
+        while let Some((original, copy)) = pending.pop() {
+            let mut child = self.tree_node(original).first_child;
+            while let Some(original_child) = child {
+                let child_copy = self.push(self.tree_node(original_child).data.clone());
+                self.append_child(copy, child_copy)
+                    .expect("copies mirror a valid tree");
+                pending.push((original_child, child_copy));
+                child = self.tree_node(original_child).next_sibling;
+            }
+        }
+        copy
+    }
+
+    fn validate_insertion(&self, parent: NodeId, child: NodeId) -> Result<(), HierarchyError> {
+        if !matches!(
+            self.tree_node(parent).data,
+            NodeData::Document | NodeData::Element(_)
+        ) {
+            return Err(HierarchyError::NotAParent);
+        }
+        if child == Document::ROOT {
+            return Err(HierarchyError::InvalidChild);
+        }
+        if parent == child {
+            return Err(HierarchyError::InvalidChild);
+        }
+        // Only a node with children can be an ancestor of the parent, which
+        // saves walking up from deep parents for new nodes.
+        if self.tree_node(child).first_child.is_none() {
+            return Ok(());
+        }
+        let mut ancestor = Some(parent);
+        while let Some(id) = ancestor {
+            if id == child {
+                return Err(HierarchyError::InvalidChild);
+            }
+            ancestor = self.tree_node(id).parent;
+        }
+        Ok(())
+    }
+}
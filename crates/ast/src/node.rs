@@ -1,24 +1,263 @@
-use std::borrow::BorrowMut;
-use std::ops::{Deref, DerefMut};
+use std::fmt;
+use std::ptr;
 
+use crate::comment::CommentData;
+use crate::doctype::DoctypeData;
 use crate::document::Document;
-use crate::element::Element;
-use crate::text::Text;
-use serde::Serialize;
-
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
-pub enum Node {
-    Document(Document),
-    Element(Element),
-    Text(Text),
-}
-
-impl Node {
-    pub fn append_child(&mut self, child: Node) {
-        match self {
-            Node::Document(doc) => doc.append_child(child),
-            Node::Element(el) => el.borrow_mut().append_child(child),
-            _ => unreachable!(),
+use crate::element::{Element, ElementData};
+use crate::span::Span;
+use crate::text::{Text, TextData};
+
+/// A handle to a node stored in a [`Document`].
+///
+/// Ids are only meaningful for the document that created them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub(crate) usize);
+
+impl NodeId {
+    /// The position of the node in its document's arena.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The payload of a node, without its links to the rest of the tree.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum NodeData {
+    Document,
+    Element(ElementData),
+    Text(TextData),
+    Comment(CommentData),
+    Doctype(DoctypeData),
+}
+
+/// A node in the arena together with its links to the rest of the tree.
+#[derive(Debug, Clone)]
+pub(crate) struct TreeNode {
+    pub(crate) parent: Option<NodeId>,
+    pub(crate) first_child: Option<NodeId>,
+    pub(crate) last_child: Option<NodeId>,
+    pub(crate) previous_sibling: Option<NodeId>,
+    pub(crate) next_sibling: Option<NodeId>,
+    pub(crate) span: Option<Span>,
+    pub(crate) data: NodeData,
+}
+
+impl TreeNode {
+    pub(crate) fn new(data: NodeData) -> TreeNode {
+        TreeNode {
+            parent: None,
+            first_child: None,
+            last_child: None,
+            previous_sibling: None,
+            next_sibling: None,
+            span: None,
+            data,
+        }
+    }
+}
+
+/// A borrowed view of a node and its position in the document.
+#[derive(Clone, Copy)]
+pub struct Node<'a> {
+    document: &'a Document,
+    id: NodeId,
+}
+
+impl<'a> Node<'a> {
+    pub(crate) fn new(document: &'a Document, id: NodeId) -> Node<'a> {
+        Node { document, id }
+    }
+
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn document(&self) -> &'a Document {
+        self.document
+    }
+
+    pub fn data(&self) -> &'a NodeData {
+        &self.tree_node().data
+    }
+
+    /// Where in the source this node was parsed from. Nodes that were
+    /// created programmatically have no span.
+    pub fn span(&self) -> Option<Span> {
+        self.tree_node().span
+    }
+
+    pub fn is_document(&self) -> bool {
+        matches!(self.data(), NodeData::Document)
+    }
+
+    pub fn is_element(&self) -> bool {
+        matches!(self.data(), NodeData::Element(_))
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self.data(), NodeData::Text(_))
+    }
+
+    pub fn is_comment(&self) -> bool {
+        matches!(self.data(), NodeData::Comment(_))
+    }
+
+    pub fn is_doctype(&self) -> bool {
+        matches!(self.data(), NodeData::Doctype(_))
+    }
+
+    pub fn as_element(&self) -> Option<Element<'a>> {
+        match self.data() {
+            NodeData::Element(data) => Some(Element::new(*self, data)),
+            _ => None,
         }
     }
+
+    pub fn as_text(&self) -> Option<Text<'a>> {
+        match self.data() {
+            NodeData::Text(data) => Some(Text::new(*self, data)),
+            _ => None,
+        }
+    }
+
+    pub fn as_comment(&self) -> Option<&'a CommentData> {
+        match self.data() {
+            NodeData::Comment(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_doctype(&self) -> Option<&'a DoctypeData> {
+        match self.data() {
+            NodeData::Doctype(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn parent(&self) -> Option<Node<'a>> {
+        self.link(self.tree_node().parent)
+    }
+
+    pub fn first_child(&self) -> Option<Node<'a>> {
+        self.link(self.tree_node().first_child)
+    }
+
+    pub fn last_child(&self) -> Option<Node<'a>> {
+        self.link(self.tree_node().last_child)
+    }
+
+    pub fn next_sibling(&self) -> Option<Node<'a>> {
+        self.link(self.tree_node().next_sibling)
+    }
+
+    pub fn previous_sibling(&self) -> Option<Node<'a>> {
+        self.link(self.tree_node().previous_sibling)
+    }
+
+    /// Iterates over the direct children of this node.
+    pub fn children(&self) -> Children<'a> {
+        Children(self.first_child())
+    }
+
+    /// Iterates over the parent, grandparent and so on up to the root.
+    pub fn ancestors(&self) -> Ancestors<'a> {
+        Ancestors(self.parent())
+    }
+
+    /// Iterates over every node below this one in document order.
+    pub fn descendants(&self) -> Descendants<'a> {
+        Descendants {
+            root: *self,
+            next: self.first_child(),
+        }
+    }
+
+    /// Concatenates the values of this node, if it is a text node, or of
+    /// all its text descendants otherwise.
+    pub fn text_content(&self) -> String {
+        if let Some(text) = self.as_text() {
+            return text.text().to_string();
+        }
+        self.descendants()
+            .filter_map(|node| node.as_text())
+            .map(|text| text.text())
+            .collect()
+    }
+
+    fn tree_node(&self) -> &'a TreeNode {
+        self.document.tree_node(self.id)
+    }
+
+    fn link(&self, id: Option<NodeId>) -> Option<Node<'a>> {
+        id.map(|id| Node::new(self.document, id))
+    }
+}
+
+impl PartialEq for Node<'_> {
+    fn eq(&self, other: &Node) -> bool {
+        ptr::eq(self.document, other.document) && self.id == other.id
+    }
+}
+
+impl Eq for Node<'_> {}
+
+impl fmt::Debug for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("id", &self.id)
+            .field("data", self.data())
+            .finish()
+    }
+}
+
+pub struct Children<'a>(Option<Node<'a>>);
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Node<'a>> {
+        let node = self.0.take()?;
+        self.0 = node.next_sibling();
+        Some(node)
+    }
+}
+
+pub struct Ancestors<'a>(Option<Node<'a>>);
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Node<'a>> {
+        let node = self.0.take()?;
+        self.0 = node.parent();
+        Some(node)
+    }
+}
+
+pub struct Descendants<'a> {
+    root: Node<'a>,
+    next: Option<Node<'a>>,
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Node<'a>> {
+        let node = self.next.take()?;
+        self.next = node.first_child().or_else(|| {
+            // Climb until we find an ancestor below the root with a sibling.
+            let mut current = node;
+            loop {
+                if current == self.root {
+                    return None;
+                }
+                if let Some(sibling) = current.next_sibling() {
+                    return Some(sibling);
+                }
+                current = current.parent()?;
+            }
+        });
+        Some(node)
+    }
 }
@@ -0,0 +1,23 @@
+/// A point in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    /// The byte offset from the start of the source.
+    pub offset: usize,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column, counted in characters.
+    pub column: usize,
+}
+
+/// The range of source text a node was parsed from. `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span {
+    pub fn new(start: Location, end: Location) -> Span {
+        Span { start, end }
+    }
+}
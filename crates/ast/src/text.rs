@@ -1,26 +1,64 @@
-use serde::Serialize;
-use std::cell::RefCell;
+use crate::node::Node;
 use std::ops::Deref;
-use std::rc::Rc;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
-pub struct Text(Rc<RefCell<TextData>>);
-
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize)]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct TextData {
     value: String,
 }
 
-impl Text {
-    pub fn new(value: String) -> Self {
-        Text(Rc::new(RefCell::new(TextData { value })))
+impl TextData {
+    pub(crate) fn new(value: String) -> TextData {
+        TextData { value }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.value
+    }
+
+    pub(crate) fn set_value(&mut self, value: String) {
+        self.value = value;
+    }
+}
+
+/// A borrowed view of a text node.
+///
+/// Dereferences to [`Node`] for navigation.
+#[derive(Debug, Clone, Copy)]
+pub struct Text<'a> {
+    node: Node<'a>,
+    data: &'a TextData,
+}
+
+impl<'a> Text<'a> {
+    pub(crate) fn new(node: Node<'a>, data: &'a TextData) -> Text<'a> {
+        Text { node, data }
     }
 
-    pub fn push(&mut self, ch: char) {
-        self.0.deref().borrow_mut().value.push(ch);
+    pub fn as_node(&self) -> Node<'a> {
+        self.node
     }
 
-    pub fn push_str(&mut self, string: &str) {
-        self.0.deref().borrow_mut().value.push_str(string);
+    pub fn data(&self) -> &'a TextData {
+        self.data
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.data.text()
+    }
+}
+
+impl PartialEq for Text<'_> {
+    fn eq(&self, other: &Text) -> bool {
+        self.node == other.node
+    }
+}
+
+impl Eq for Text<'_> {}
+
+impl<'a> Deref for Text<'a> {
+    type Target = Node<'a>;
+
+    fn deref(&self) -> &Node<'a> {
+        &self.node
     }
 }
@@ -0,0 +1,199 @@
+use crate::document::Document;
+use crate::element::Element;
+use crate::node::{Node, NodeData, NodeId};
+use crate::text::Text;
+
+/// What a walk should do after a visitor hook returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    Continue,
+    /// Don't descend into the children of the node just entered. Its leave
+    /// hook is still called. Behaves like `Continue` when returned from any
+    /// other hook.
+    SkipChildren,
+    /// End the walk immediately, without calling any more hooks.
+    Stop,
+}
+
+/// Read-only hooks called by [`walk`] for each node in document order.
+///
+/// Every hook defaults to `Visit::Continue`, so an implementation only needs
+/// the ones for the node kinds it cares about. Text, comment and doctype
+/// nodes have no children, so they get a single `visit_*` hook instead of
+/// enter and leave.
+pub trait Visitor<'a> {
+    fn enter_document(&mut self, _document: Node<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    fn leave_document(&mut self, _document: Node<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    fn enter_element(&mut self, _element: Element<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, _element: Element<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, _text: Text<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, _comment: Node<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    fn visit_doctype(&mut self, _doctype: Node<'a>) -> Visit {
+        Visit::Continue
+    }
+}
+
+/// Hooks called by [`walk_mut`], which may change the document as it is
+/// walked.
+///
+/// A hook may change anything about the node it is called with, including
+/// its children, or detach the node altogether; the walk continues with the
+/// node that followed it. Nodes inserted as children of the current node
+/// during `enter_*` are visited. Moving or removing nodes outside the current
+/// node's subtree can cause them to be skipped or visited twice.
+pub trait VisitorMut {
+    fn enter_document(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+
+    fn leave_document(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+
+    fn enter_element(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+
+    fn visit_doctype(&mut self, _document: &mut Document, _id: NodeId) -> Visit {
+        Visit::Continue
+    }
+}
+
+/// Walks `root` and its descendants in document order.
+///
+/// The walk is iterative, so it works on arbitrarily deep trees.
+pub fn walk<'a, V: Visitor<'a>>(root: Node<'a>, visitor: &mut V) {
+    let mut current = root;
+    loop {
+        let visit = match current.data() {
+            NodeData::Document => visitor.enter_document(current),
+            NodeData::Element(_) => visitor.enter_element(current.as_element().unwrap()),
+            NodeData::Text(_) => visitor.visit_text(current.as_text().unwrap()),
+            NodeData::Comment(_) => visitor.visit_comment(current),
+            NodeData::Doctype(_) => visitor.visit_doctype(current),
+        };
+        if visit == Visit::Stop {
+            return;
+        }
+        if visit == Visit::Continue {
+            if let Some(child) = current.first_child() {
+                current = child;
+                continue;
+            }
+        }
+
+        loop {
+            let visit = match current.data() {
+                NodeData::Document => visitor.leave_document(current),
+                NodeData::Element(_) => visitor.leave_element(current.as_element().unwrap()),
+                _ => Visit::Continue,
+            };
+            if visit == Visit::Stop || current == root {
+                return;
+            }
+            if let Some(sibling) = current.next_sibling() {
+                current = sibling;
+                break;
+            }
+            match current.parent() {
+                Some(parent) => current = parent,
+                None => return,
+            }
+        }
+    }
+}
+
+/// Walks the subtree rooted at `root` in document order, letting the visitor
+/// modify the document along the way. See [`VisitorMut`] for which changes
+/// are safe.
+pub fn walk_mut<V: VisitorMut>(document: &mut Document, root: NodeId, visitor: &mut V) {
+    // The open ancestors of the current node, each with the sibling that
+    // followed it when it was entered.
+    let mut stack: Vec<(NodeId, Option<NodeId>)> = Vec::new();
+    let mut current = root;
+    let mut following = None;
+
+    loop {
+        let visit = match document.node(current).data() {
+            NodeData::Document => visitor.enter_document(document, current),
+            NodeData::Element(_) => visitor.enter_element(document, current),
+            NodeData::Text(_) => visitor.visit_text(document, current),
+            NodeData::Comment(_) => visitor.visit_comment(document, current),
+            NodeData::Doctype(_) => visitor.visit_doctype(document, current),
+        };
+        if visit == Visit::Stop {
+            return;
+        }
+        if visit == Visit::Continue {
+            if let Some(child) = document.tree_node(current).first_child {
+                stack.push((current, following));
+                following = document.tree_node(child).next_sibling;
+                current = child;
+                continue;
+            }
+        }
+
+        loop {
+            let visit = match document.node(current).data() {
+                NodeData::Document => visitor.leave_document(document, current),
+                NodeData::Element(_) => visitor.leave_element(document, current),
+                _ => Visit::Continue,
+            };
+            if visit == Visit::Stop {
+                return;
+            }
+            let (parent, parent_following) = match stack.last() {
+                Some(&frame) => frame,
+                None => return,
+            };
+
+            // Follow the current node's links while it is still in place,
+            // and fall back to the sibling recorded on entry if the visitor
+            // moved it.
+            let next = if document.tree_node(current).parent == Some(parent) {
+                document.tree_node(current).next_sibling
+            } else {
+                following.filter(|&next| document.tree_node(next).parent == Some(parent))
+            };
+            if let Some(next) = next {
+                following = document.tree_node(next).next_sibling;
+                current = next;
+                break;
+            }
+
+            stack.pop();
+            current = parent;
+            following = parent_following;
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::node::Node;
+
+/// A cursor over the subtree rooted at a node.
+///
+/// Every move returns the node moved to, or `None` without moving if there
+/// is no such node inside the subtree.
+#[derive(Debug, Clone)]
+pub struct TreeWalker<'a> {
+    root: Node<'a>,
+    current: Node<'a>,
+}
+
+impl<'a> TreeWalker<'a> {
+    pub fn new(root: Node<'a>) -> TreeWalker<'a> {
+        TreeWalker {
+            root,
+            current: root,
+        }
+    }
+
+    pub fn root(&self) -> Node<'a> {
+        self.root
+    }
+
+    pub fn current(&self) -> Node<'a> {
+        self.current
+    }
+
+    pub fn parent_node(&mut self) -> Option<Node<'a>> {
+        if self.current == self.root {
+            return None;
+        }
+        self.move_to(self.current.parent())
+    }
+
+    pub fn first_child(&mut self) -> Option<Node<'a>> {
+        self.move_to(self.current.first_child())
+    }
+
+    pub fn last_child(&mut self) -> Option<Node<'a>> {
+        self.move_to(self.current.last_child())
+    }
+
+    pub fn next_sibling(&mut self) -> Option<Node<'a>> {
+        if self.current == self.root {
+            return None;
+        }
+        self.move_to(self.current.next_sibling())
+    }
+
+    pub fn previous_sibling(&mut self) -> Option<Node<'a>> {
+        if self.current == self.root {
+            return None;
+        }
+        self.move_to(self.current.previous_sibling())
+    }
+
+    /// Moves to the next node in document order.
+    pub fn next_node(&mut self) -> Option<Node<'a>> {
+        if let Some(child) = self.current.first_child() {
+            return self.move_to(Some(child));
+        }
+        let mut node = self.current;
+        while node != self.root {
+            if let Some(sibling) = node.next_sibling() {
+                return self.move_to(Some(sibling));
+            }
+            node = node.parent()?;
+        }
+        None
+    }
+
+    /// Moves to the previous node in document order.
+    pub fn previous_node(&mut self) -> Option<Node<'a>> {
+        if self.current == self.root {
+            return None;
+        }
+        let mut node = match self.current.previous_sibling() {
+            Some(sibling) => sibling,
+            None => return self.move_to(self.current.parent()),
+        };
+        while let Some(child) = node.last_child() {
+            node = child;
+        }
+        self.move_to(Some(node))
+    }
+
+    fn move_to(&mut self, node: Option<Node<'a>>) -> Option<Node<'a>> {
+        let node = node?;
+        self.current = node;
+        Some(node)
+    }
+}
@@ -0,0 +1,176 @@
+use ast::{Attribute, Document, HierarchyError, Namespace, NodeId};
+
+fn element(document: &mut Document, name: &str) -> NodeId {
+    document.create_element(name.to_string(), Vec::new())
+}
+
+fn children(document: &Document, parent: NodeId) -> Vec<NodeId> {
+    document.node(parent).children().map(|n| n.id()).collect()
+}
+
+#[test]
+fn len_and_is_empty_leave_out_the_document_node() {
+    let mut document = Document::new();
+    assert_eq!(document.len(), 0);
+    assert!(document.is_empty());
+
+    // Detached nodes are counted too.
+    element(&mut document, "p");
+    assert_eq!(document.len(), 1);
+    assert!(!document.is_empty());
+}
+
+#[test]
+fn insert_before_and_remove_child() {
+    let mut document = Document::new();
+    let list = element(&mut document, "ul");
+    let a = element(&mut document, "li");
+    let b = element(&mut document, "li");
+    let c = element(&mut document, "li");
+
+    document.append_child(list, c).unwrap();
+    document.insert_before(list, a, Some(c)).unwrap();
+    document.insert_before(list, b, Some(c)).unwrap();
+    assert_eq!(children(&document, list), vec![a, b, c]);
+    assert_eq!(document.node(b).previous_sibling(), Some(document.node(a)));
+    assert_eq!(document.node(c).previous_sibling(), Some(document.node(b)));
+
+    document.insert_before(list, c, Some(a)).unwrap();
+    assert_eq!(children(&document, list), vec![c, a, b]);
+    assert_eq!(document.node(list).last_child(), Some(document.node(b)));
+
+    document.remove_child(list, a).unwrap();
+    assert_eq!(children(&document, list), vec![c, b]);
+    assert_eq!(document.node(a).parent(), None);
+    assert_eq!(
+        document.remove_child(list, a),
+        Err(HierarchyError::NotAChild)
+    );
+}
+
+#[test]
+fn rejects_invalid_insertions() {
+    let mut document = Document::new();
+    let outer = element(&mut document, "div");
+    let inner = element(&mut document, "div");
+    let text = document.create_text("text".to_string());
+    document.append_child(outer, inner).unwrap();
+
+    assert_eq!(
+        document.append_child(inner, outer),
+        Err(HierarchyError::InvalidChild)
+    );
+    assert_eq!(
+        document.append_child(inner, inner),
+        Err(HierarchyError::InvalidChild)
+    );
+    assert_eq!(
+        document.append_child(outer, Document::ROOT),
+        Err(HierarchyError::InvalidChild)
+    );
+    assert_eq!(
+        document.append_child(text, outer),
+        Err(HierarchyError::NotAParent)
+    );
+    assert_eq!(
+        document.insert_before(Document::ROOT, text, Some(inner)),
+        Err(HierarchyError::NotAChild)
+    );
+    assert_eq!(children(&document, outer), vec![inner]);
+}
+
+#[test]
+fn replace_with_and_detach() {
+    let mut document = Document::new();
+    let body = element(&mut document, "body");
+    let a = element(&mut document, "a");
+    let b = element(&mut document, "b");
+    let c = element(&mut document, "c");
+    let replacement = element(&mut document, "span");
+    for child in [a, b, c] {
+        document.append_child(body, child).unwrap();
+    }
+
+    document.replace_with(b, replacement).unwrap();
+    assert_eq!(children(&document, body), vec![a, replacement, c]);
+    assert_eq!(document.node(b).parent(), None);
+
+    document.detach(a);
+    document.detach(c);
+    assert_eq!(children(&document, body), vec![replacement]);
+    assert_eq!(document.node(replacement).previous_sibling(), None);
+    assert_eq!(document.node(replacement).next_sibling(), None);
+}
+
+#[test]
+fn attributes_are_case_insensitive() {
+    let mut document = Document::new();
+    let link = document.create_element(
+        "a".to_string(),
+        vec![Attribute::new("href".to_string(), "/".to_string())],
+    );
+
+    let data = document.element_mut(link).unwrap();
+    data.set_attribute("HREF", "/home");
+    data.set_attribute("Title", "Home");
+    assert_eq!(data.get_attribute("href"), Some("/home"));
+    assert_eq!(data.remove_attribute("TITLE"), Some("Home".to_string()));
+    assert_eq!(data.remove_attribute("title"), None);
+
+    let element = document.node(link).as_element().unwrap();
+    assert_eq!(element.get_attribute("Href"), Some("/home"));
+    assert!(document.element_mut(Document::ROOT).is_none());
+}
+
+#[test]
+fn foreign_attributes_are_case_sensitive() {
+    let mut document = Document::new();
+    let svg = document.create_element_ns(
+        "svg".to_string(),
+        Namespace::Svg,
+        vec![Attribute::new("viewBox".to_string(), "0 0 1 1".to_string())],
+    );
+
+    let data = document.element_mut(svg).unwrap();
+    assert_eq!(data.get_attribute("viewbox"), None);
+    data.set_attribute("viewbox", "lower");
+    data.set_attribute("viewBox", "0 0 2 2");
+    data.set_attribute("preserveAspectRatio", "none");
+    let names: Vec<_> = data.attributes().iter().map(Attribute::name).collect();
+    assert_eq!(names, ["viewBox", "viewbox", "preserveAspectRatio"]);
+    assert_eq!(data.get_attribute("viewBox"), Some("0 0 2 2"));
+    assert_eq!(data.remove_attribute("PRESERVEASPECTRATIO"), None);
+    assert_eq!(data.remove_attribute("viewbox"), Some("lower".to_string()));
+}
+
+#[test]
+fn set_text_content_and_clone_node() {
+    let mut document = Document::new();
+    let div = element(&mut document, "div");
+    let span = element(&mut document, "span");
+    let text = document.create_text("old".to_string());
+    document.append_child(div, span).unwrap();
+    document.append_child(span, text).unwrap();
+
+    let copy = document.clone_node(div, true);
+    let shallow = document.clone_node(div, false);
+    assert_eq!(document.node(shallow).children().count(), 0);
+    assert_eq!(document.node(copy).parent(), None);
+    assert_eq!(document.node(copy).descendants().count(), 2);
+
+    document.set_text_content(text, "new");
+    document.set_text_content(div, "replaced");
+    assert_eq!(document.node(text).text_content(), "new");
+    let only_child = document.node(div).first_child().unwrap();
+    assert_eq!(only_child.as_text().unwrap().text(), "replaced");
+    assert_eq!(only_child.next_sibling(), None);
+    assert_eq!(document.node(span).parent(), None);
+
+    // The copy was made before the changes and is unaffected by them.
+    let copied_text = document.node(copy).descendants().nth(1).unwrap();
+    assert_eq!(copied_text.as_text().unwrap().text(), "old");
+    assert_ne!(copied_text.id(), text);
+
+    document.set_text_content(div, "");
+    assert_eq!(document.node(div).first_child(), None);
+}
@@ -0,0 +1,76 @@
+use ast::{Document, NodeId};
+
+fn element(document: &mut Document, name: &str) -> NodeId {
+    document.create_element(name.to_string(), Vec::new())
+}
+
+#[test]
+fn parent_and_siblings() {
+    let mut document = Document::new();
+    let body = element(&mut document, "body");
+    let first = element(&mut document, "p");
+    let second = document.create_text("text".to_string());
+    let third = element(&mut document, "div");
+
+    document.append_child(Document::ROOT, body).unwrap();
+    document.append_child(body, first).unwrap();
+    document.append_child(body, second).unwrap();
+    document.append_child(body, third).unwrap();
+
+    let node = |id| document.node(id);
+    assert_eq!(node(second).parent(), Some(node(body)));
+    assert_eq!(node(body).parent(), Some(document.root()));
+    assert_eq!(document.root().parent(), None);
+
+    assert_eq!(node(first).next_sibling(), Some(node(second)));
+    assert_eq!(node(second).next_sibling(), Some(node(third)));
+    assert_eq!(node(third).next_sibling(), None);
+    assert_eq!(node(third).previous_sibling(), Some(node(second)));
+    assert_eq!(node(first).previous_sibling(), None);
+    assert_eq!(node(body).first_child(), Some(node(first)));
+    assert_eq!(node(body).last_child(), Some(node(third)));
+}
+
+#[test]
+fn ancestors_and_descendants() {
+    let mut document = Document::new();
+    let html = element(&mut document, "html");
+    let head = element(&mut document, "head");
+    let body = element(&mut document, "body");
+    let text = document.create_text("hello".to_string());
+
+    document.append_child(body, text).unwrap();
+    document.append_child(html, head).unwrap();
+    document.append_child(html, body).unwrap();
+    document.append_child(Document::ROOT, html).unwrap();
+
+    let ancestors: Vec<NodeId> = document.node(text).ancestors().map(|n| n.id()).collect();
+    assert_eq!(ancestors, vec![body, html, Document::ROOT]);
+
+    let descendants: Vec<NodeId> = document.descendants().map(|n| n.id()).collect();
+    assert_eq!(descendants, vec![html, head, body, text]);
+
+    let descendants: Vec<NodeId> = document.node(head).descendants().map(|n| n.id()).collect();
+    assert!(descendants.is_empty());
+}
+
+#[test]
+fn append_child_moves_node() {
+    let mut document = Document::new();
+    let first = element(&mut document, "div");
+    let second = element(&mut document, "div");
+    let a = element(&mut document, "a");
+    let b = element(&mut document, "b");
+    let c = element(&mut document, "c");
+
+    document.append_child(first, a).unwrap();
+    document.append_child(first, b).unwrap();
+    document.append_child(first, c).unwrap();
+    document.append_child(second, b).unwrap();
+
+    let children: Vec<NodeId> = document.node(first).children().map(|n| n.id()).collect();
+    assert_eq!(children, vec![a, c]);
+    assert_eq!(document.node(c).previous_sibling(), Some(document.node(a)));
+    assert_eq!(document.node(b).parent(), Some(document.node(second)));
+    assert_eq!(document.node(b).previous_sibling(), None);
+}
@@ -0,0 +1,295 @@
+//! The `html` command: parses, formats, minifies, queries and checks HTML
+//! from the command line.
+//!
+//! Every command reads the files named on the command line, or standard
+//! input when there are none or the name is `-`.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use ast::elements;
+use clap::{Parser, Subcommand, ValueEnum};
+use formatter::{FormatOptions, WhitespaceSensitivity, WrapAttributes};
+use lexer::{HtmlLexer, State, Token};
+use minifier::MinifyOptions;
+use parser::{parse_html, parse_html_with_errors, render, Severity};
+use selector::SelectorList;
+
+#[derive(Parser)]
+#[command(name = "html", version, about = "Parse, format, query and check HTML")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the document tree as JSON.
+    Parse {
+        file: Option<String>,
+        /// Print the JSON on a single line.
+        #[arg(long)]
+        compact: bool,
+    },
+    /// Print the tokens the tokenizer produces, one per line.
+    Tokens { file: Option<String> },
+    /// Pretty-print the document.
+    Fmt {
+        file: Option<String>,
+        /// Spaces per level of indentation.
+        #[arg(long, default_value_t = 2)]
+        indent: usize,
+        /// The width lines are wrapped at.
+        #[arg(long, default_value_t = 80)]
+        line_width: usize,
+        /// When to put attributes on lines of their own.
+        #[arg(long, value_enum, default_value_t = Wrap::Auto)]
+        wrap_attributes: Wrap,
+        /// Which whitespace may be added or removed.
+        #[arg(long, value_enum, default_value_t = Whitespace::Css)]
+        whitespace: Whitespace,
+    },
+    /// Print the document minified.
+    Minify { file: Option<String> },
+    /// Print the elements matching a CSS selector, one per line.
+    Select {
+        selector: String,
+        file: Option<String>,
+        /// Print the text of the elements rather than their HTML.
+        #[arg(long)]
+        text: bool,
+    },
+    /// Report parse errors, exiting with a failure status if there are any.
+    Check {
+        files: Vec<String>,
+        /// Fail on warnings, such as exceeded limits, too.
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Wrap {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Whitespace {
+    Css,
+    Strict,
+    Ignore,
+}
+
+/// A failure that ends the command.
+struct Error(String);
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error(error.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(Error(message)) => {
+            eprintln!("html: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs a command, returning whether it succeeded.
+fn run(command: Command) -> Result<bool, Error> {
+    let output = match command {
+        Command::Parse { file, compact } => {
+            let document = parse_html(&read(file.as_deref())?);
+            let json = if compact {
+                serde_json::to_string(&document)
+            } else {
+                serde_json::to_string_pretty(&document)
+            };
+            json.map_err(|error| Error(error.to_string()))? + "\n"
+        }
+        Command::Tokens { file } => tokens(&read(file.as_deref())?),
+        Command::Fmt {
+            file,
+            indent,
+            line_width,
+            wrap_attributes,
+            whitespace,
+        } => {
+            let options = FormatOptions {
+                indent_width: indent,
+                line_width,
+                wrap_attributes: match wrap_attributes {
+                    Wrap::Auto => WrapAttributes::Auto,
+                    Wrap::Always => WrapAttributes::Always,
+                    Wrap::Never => WrapAttributes::Never,
+                },
+                whitespace_sensitivity: match whitespace {
+                    Whitespace::Css => WhitespaceSensitivity::Css,
+                    Whitespace::Strict => WhitespaceSensitivity::Strict,
+                    Whitespace::Ignore => WhitespaceSensitivity::Ignore,
+                },
+            };
+            formatter::format(&read(file.as_deref())?, &options)
+        }
+        Command::Minify { file } => {
+            minifier::minify(&read(file.as_deref())?, &MinifyOptions::default())
+        }
+        Command::Select {
+            selector,
+            file,
+            text,
+        } => {
+            let selectors = SelectorList::parse(&selector)
+                .map_err(|error| Error(format!("invalid selector: {error}")))?;
+            let document = parse_html(&read(file.as_deref())?);
+            let mut output = String::new();
+            for element in selectors.select(document.root()) {
+                if text {
+                    output += &element.text_content();
+                } else {
+                    output += &serializer::outer_html(element.as_node());
+                }
+                output.push('\n');
+            }
+            output
+        }
+        Command::Check {
+            files,
+            deny_warnings,
+        } => return check(&files, deny_warnings),
+    };
+    io::stdout().write_all(output.as_bytes())?;
+    Ok(true)
+}
+
+/// Reads `file`, or standard input for `None` or `-`.
+fn read(file: Option<&str>) -> Result<String, Error> {
+    match file {
+        None | Some("-") => {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .map_err(|error| Error(format!("<stdin>: {error}")))?;
+            Ok(source)
+        }
+        Some(path) => fs::read_to_string(path).map_err(|error| Error(format!("{path}: {error}"))),
+    }
+}
+
+/// Describes each token on a line of its own, after its line and column.
+/// Runs of characters are shown together as a single text token.
+fn tokens(source: &str) -> String {
+    let mut lexer = HtmlLexer::new(source);
+    let mut output = String::new();
+    let mut text: Option<(lexer::Location, String)> = None;
+    loop {
+        let token = lexer.next();
+        let start = lexer.token_start();
+        if let Token::Character(ch) = token {
+            text.get_or_insert_with(|| (start, String::new()))
+                .1
+                .push(ch);
+            continue;
+        }
+        if let Some((start, text)) = text.take() {
+            line(&mut output, start, &format!("text {text:?}"));
+        }
+
+        let description = match &token {
+            Token::Doctype {
+                name,
+                public_id,
+                system_id,
+                ..
+            } => {
+                let mut description = format!("doctype {name}");
+                if let Some(public_id) = public_id {
+                    description += &format!(" public {public_id:?}");
+                }
+                if let Some(system_id) = system_id {
+                    description += &format!(" system {system_id:?}");
+                }
+                description
+            }
+            Token::Tag {
+                tag_name,
+                self_closing,
+                attributes,
+                is_end_tag,
+            } => {
+                let kind = if *is_end_tag { "end-tag" } else { "start-tag" };
+                let mut description = format!("{kind} {tag_name}");
+                for attribute in attributes {
+                    description += &format!(" {}={:?}", attribute.name, attribute.value);
+                }
+                if *self_closing {
+                    description += " /";
+                }
+                description
+            }
+            Token::Comment(comment) => format!("comment {comment:?}"),
+            Token::EOF => "eof".to_string(),
+            Token::Character(_) => unreachable!(),
+        };
+        line(&mut output, start, &description);
+        if token == Token::EOF {
+            return output;
+        }
+
+        // Follow the parser into the elements whose contents are text.
+        if token.is_start_tag() && !token.self_closing() {
+            match token.tag_name().as_str() {
+                "script" => lexer.set_state(State::ScriptData),
+                "plaintext" => lexer.set_state(State::PlainText),
+                name if elements::is_raw_text(name) => lexer.set_state(State::RawText),
+                name if elements::is_escapable_raw_text(name) => lexer.set_state(State::RcData),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn line(output: &mut String, location: lexer::Location, description: &str) {
+    *output += &format!("{}:{}\t{description}\n", location.line, location.column);
+}
+
+/// Prints the parse errors in `files` to standard error, returning whether
+/// there were none that fail the check.
+fn check(files: &[String], deny_warnings: bool) -> Result<bool, Error> {
+    let files: Vec<Option<&str>> = if files.is_empty() {
+        vec![None]
+    } else {
+        files.iter().map(|file| Some(file.as_str())).collect()
+    };
+
+    let mut stderr = io::stderr().lock();
+    let mut failed = 0;
+    for file in files {
+        let source = read(file)?;
+        let name = match file {
+            None | Some("-") => "<stdin>",
+            Some(path) => path,
+        };
+        let (_, errors) = parse_html_with_errors(&source);
+        for error in &errors {
+            writeln!(stderr, "{}", render(&source, name, error))?;
+            if error.severity == Severity::Error || deny_warnings {
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        let plural = if failed == 1 { "" } else { "s" };
+        writeln!(stderr, "html: found {failed} error{plural}")?;
+    }
+    Ok(failed == 0)
+}
@@ -0,0 +1,121 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs `html` with `args`, feeding it `stdin`.
+fn html(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_html"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn parse_prints_json() {
+    let output = html(&["parse", "--compact"], "<p class=a>Hi</p>");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_str(stdout(&output)).unwrap();
+    assert_eq!(json["nodes"][0]["name"], "p");
+    assert_eq!(json["nodes"][1]["value"], "Hi");
+    assert_eq!(json["nodes"][1]["parent"], 0);
+}
+
+#[test]
+fn tokens_are_listed() {
+    let output = html(
+        &["tokens"],
+        "<!DOCTYPE html>\n<p a=1>x<br/></p><script>a<b</script><!--c-->",
+    );
+    assert_eq!(
+        stdout(&output),
+        "1:1\tdoctype html\n\
+         1:16\ttext \"\\n\"\n\
+         2:1\tstart-tag p a=\"1\"\n\
+         2:8\ttext \"x\"\n\
+         2:9\tstart-tag br /\n\
+         2:14\tend-tag p\n\
+         2:18\tstart-tag script\n\
+         2:26\ttext \"a<b\"\n\
+         2:29\tend-tag script\n\
+         2:38\tcomment \"c\"\n\
+         2:46\teof\n"
+    );
+}
+
+#[test]
+fn fmt_and_minify() {
+    let source = "<div><p>Hello   <b>world</b></p>\n\n<!-- note --></div>";
+    let output = html(&["fmt", "--indent", "4"], source);
+    assert_eq!(
+        stdout(&output),
+        "<div>\n    <p>Hello <b>world</b></p>\n    <!-- note -->\n</div>\n"
+    );
+
+    let output = html(&["minify", "-"], source);
+    assert_eq!(stdout(&output), "<div><p>Hello <b>world</b></div>");
+}
+
+#[test]
+fn select_prints_matches() {
+    let source = "<ul><li class=a>One</li><li>Two <b>2</b></li></ul>";
+    let output = html(&["select", "li:not(.a)"], source);
+    assert_eq!(stdout(&output), "<li>Two <b>2</b></li>\n");
+
+    let output = html(&["select", "--text", "li"], source);
+    assert_eq!(stdout(&output), "One\nTwo 2\n");
+
+    let output = html(&["select", "li["], source);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("html: invalid selector:"));
+}
+
+#[test]
+fn check_reports_errors() {
+    let output = html(&["check"], "<!DOCTYPE html><p>fine</p>");
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+
+    let output = html(&["check"], "<!DOCTYPE html>\n<p a=\"1\"b=\"2\">");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "error[missing-whitespace-between-attributes]: missing whitespace between attributes
+ --> <stdin>:2:9
+  |
+2 | <p a=\"1\"b=\"2\">
+  |         ^
+
+html: found 1 error
+"
+    );
+}
+
+#[test]
+fn reads_files() {
+    let path = std::env::temp_dir().join(format!("html-cli-test-{}.html", std::process::id()));
+    std::fs::write(&path, "<p>file</p>").unwrap();
+    let output = html(&["select", "--text", "p", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(stdout(&output), "file\n");
+
+    let output = html(&["check", "missing.html"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("html: missing.html: "));
+}
@@ -0,0 +1,781 @@
+//! Forms and their controls, and the entries a form would submit, built
+//! with the HTML standard's algorithm for constructing the entry list.
+//!
+//! ```
+//! use extract::{extract_forms, urlencode, Method, Url};
+//!
+//! let document = parser::parse_html(
+//!     "<form action=/search method=post>\
+//!      <input name=q value=lamps><input type=checkbox name=new checked>\
+//!      <button name=go value=1>Search</button></form>",
+//! );
+//! let url = Url::parse("https://shop.example/").unwrap();
+//! let form = &extract_forms(&document, &url)[0];
+//! assert_eq!(form.method, Method::Post);
+//! assert_eq!(form.action.as_ref().unwrap().as_str(), "https://shop.example/search");
+//! assert_eq!(urlencode(&form.form_data(None)), "q=lamps&new=on");
+//! assert_eq!(urlencode(&form.form_data(Some(2))), "q=lamps&new=on&go=1");
+//! ```
+
+use std::collections::HashMap;
+
+use ast::{Document, Element, NodeId};
+use url::{form_urlencoded, Url};
+
+use crate::{base_url, collapse_whitespace, html_elements, parse_integer, parse_number};
+
+/// A `form` element and the controls associated with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form {
+    /// The `form` element.
+    pub node: NodeId,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// The URL the form submits to: its `action` resolved against the base
+    /// URL, or the document's URL if it has none. `None` if the action isn't
+    /// a valid URL, in which case browsers don't submit the form.
+    pub action: Option<Url>,
+    pub method: Method,
+    pub enctype: Enctype,
+    /// The controls whose form owner is this form, in document order. They
+    /// include controls outside the form that name it with a `form`
+    /// attribute, and leave out those inside it that name another.
+    pub controls: Vec<Control>,
+}
+
+/// The `method` of a form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Method {
+    #[default]
+    Get,
+    Post,
+    /// Closes the `dialog` the form is in instead of submitting.
+    Dialog,
+}
+
+/// The `enctype` of a form: how its entries are encoded when it is posted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Enctype {
+    /// `application/x-www-form-urlencoded`.
+    #[default]
+    UrlEncoded,
+    /// `multipart/form-data`.
+    Multipart,
+    /// `text/plain`.
+    TextPlain,
+}
+
+impl Enctype {
+    /// The media type of the encoding.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Enctype::UrlEncoded => "application/x-www-form-urlencoded",
+            Enctype::Multipart => "multipart/form-data",
+            Enctype::TextPlain => "text/plain",
+        }
+    }
+}
+
+/// A control of a [`Form`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Control {
+    /// The `input`, `select`, `textarea` or `button` element.
+    pub node: NodeId,
+    pub kind: ControlKind,
+    pub name: Option<String>,
+    /// The value the control starts with. For `input`s it is the `value`
+    /// attribute sanitized for the type, so an invalid `number` is empty and
+    /// a `checkbox` without one is `on`. For `textarea`s it is their text.
+    /// `select`s have theirs in their options.
+    pub value: String,
+    /// Whether a `checkbox` or `radio` starts checked.
+    pub checked: bool,
+    /// Whether the control is disabled, by its `disabled` attribute or by a
+    /// disabled `fieldset` around it.
+    pub disabled: bool,
+    pub required: bool,
+    pub readonly: bool,
+    pub multiple: bool,
+    /// The `dirname` of text fields, under which their direction is
+    /// submitted.
+    pub dirname: Option<String>,
+    /// The direction of the control's text, from the nearest `dir`.
+    pub direction: Direction,
+    /// The options of a `select`, including those in `optgroup`s.
+    pub options: Vec<SelectOption>,
+}
+
+/// What kind of control a [`Control`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlKind {
+    Input(InputType),
+    Select,
+    Textarea,
+    Button(ButtonType),
+}
+
+/// The `type` of an `input`. Missing and unknown types are
+/// [`InputType::Text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputType {
+    Hidden,
+    Text,
+    Search,
+    Tel,
+    Url,
+    Email,
+    Password,
+    Date,
+    Month,
+    Week,
+    Time,
+    DatetimeLocal,
+    Number,
+    Range,
+    Color,
+    Checkbox,
+    Radio,
+    File,
+    Submit,
+    Image,
+    Reset,
+    Button,
+}
+
+impl InputType {
+    /// Reads a `type` attribute.
+    pub fn from_attribute(value: Option<&str>) -> InputType {
+        match value.unwrap_or_default().to_ascii_lowercase().as_str() {
+            "hidden" => InputType::Hidden,
+            "search" => InputType::Search,
+            "tel" => InputType::Tel,
+            "url" => InputType::Url,
+            "email" => InputType::Email,
+            "password" => InputType::Password,
+            "date" => InputType::Date,
+            "month" => InputType::Month,
+            "week" => InputType::Week,
+            "time" => InputType::Time,
+            "datetime-local" => InputType::DatetimeLocal,
+            "number" => InputType::Number,
+            "range" => InputType::Range,
+            "color" => InputType::Color,
+            "checkbox" => InputType::Checkbox,
+            "radio" => InputType::Radio,
+            "file" => InputType::File,
+            "submit" => InputType::Submit,
+            "image" => InputType::Image,
+            "reset" => InputType::Reset,
+            "button" => InputType::Button,
+            _ => InputType::Text,
+        }
+    }
+
+    /// The keyword of the type, such as `datetime-local`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InputType::Hidden => "hidden",
+            InputType::Text => "text",
+            InputType::Search => "search",
+            InputType::Tel => "tel",
+            InputType::Url => "url",
+            InputType::Email => "email",
+            InputType::Password => "password",
+            InputType::Date => "date",
+            InputType::Month => "month",
+            InputType::Week => "week",
+            InputType::Time => "time",
+            InputType::DatetimeLocal => "datetime-local",
+            InputType::Number => "number",
+            InputType::Range => "range",
+            InputType::Color => "color",
+            InputType::Checkbox => "checkbox",
+            InputType::Radio => "radio",
+            InputType::File => "file",
+            InputType::Submit => "submit",
+            InputType::Image => "image",
+            InputType::Reset => "reset",
+            InputType::Button => "button",
+        }
+    }
+}
+
+/// The `type` of a `button`. Missing and unknown types are
+/// [`ButtonType::Submit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ButtonType {
+    Submit,
+    Reset,
+    Button,
+}
+
+/// The direction of a control's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// An `option` of a `select`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectOption {
+    /// The `value` attribute, or the text if there is none.
+    pub value: String,
+    /// The text, with its whitespace collapsed.
+    pub text: String,
+    /// Whether the option starts selected. Without `multiple`, at most one
+    /// is: the last with a `selected` attribute, or else the first that
+    /// isn't disabled when the `select` shows a single row.
+    pub selected: bool,
+    /// Whether the option, or the `optgroup` it is in, is disabled.
+    pub disabled: bool,
+}
+
+/// An entry of the data a form submits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    pub value: EntryValue,
+}
+
+/// The value of an [`Entry`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryValue {
+    Text(String),
+    /// A file from a file `input`. Without a chosen file, browsers submit an
+    /// empty one with an empty name.
+    File {
+        filename: String,
+        content_type: String,
+    },
+}
+
+impl Control {
+    /// Whether the control is a button: a `button` or an `input` of type
+    /// `submit`, `image`, `reset` or `button`.
+    pub fn is_button(&self) -> bool {
+        matches!(
+            self.kind,
+            ControlKind::Button(_)
+                | ControlKind::Input(
+                    InputType::Submit | InputType::Image | InputType::Reset | InputType::Button
+                )
+        )
+    }
+
+    /// Whether the control submits its form when activated.
+    pub fn is_submit_button(&self) -> bool {
+        matches!(
+            self.kind,
+            ControlKind::Button(ButtonType::Submit)
+                | ControlKind::Input(InputType::Submit | InputType::Image)
+        )
+    }
+}
+
+impl Form {
+    /// The entries the form submits with its controls' starting values, as
+    /// the HTML standard constructs them. `submitter` is the index in
+    /// [`Form::controls`] of the submit button that submits the form, if
+    /// one does; other buttons aren't submitted.
+    pub fn form_data(&self, submitter: Option<usize>) -> Vec<Entry> {
+        let submitter = submitter.filter(|&index| {
+            self.controls
+                .get(index)
+                .is_some_and(Control::is_submit_button)
+        });
+        let mut entries = Vec::new();
+        let mut push = |name: &str, value: EntryValue| {
+            entries.push(Entry {
+                name: name.to_string(),
+                value,
+            })
+        };
+        let text = |value: &str| EntryValue::Text(value.to_string());
+        for (index, control) in self.controls.iter().enumerate() {
+            if control.disabled || control.is_button() && submitter != Some(index) {
+                continue;
+            }
+            let name = control.name.as_deref().unwrap_or_default();
+            if control.kind == ControlKind::Input(InputType::Image) {
+                // The coordinates of the click, which there wasn't.
+                let prefix = match name {
+                    "" => String::new(),
+                    name => format!("{name}."),
+                };
+                push(&format!("{prefix}x"), text("0"));
+                push(&format!("{prefix}y"), text("0"));
+                continue;
+            }
+            if name.is_empty() {
+                continue;
+            }
+            match control.kind {
+                ControlKind::Select => {
+                    for option in &control.options {
+                        if option.selected && !option.disabled {
+                            push(name, text(&option.value));
+                        }
+                    }
+                }
+                ControlKind::Input(InputType::Checkbox | InputType::Radio) => {
+                    if control.checked {
+                        push(name, text(&control.value));
+                    }
+                }
+                ControlKind::Input(InputType::File) => push(
+                    name,
+                    EntryValue::File {
+                        filename: String::new(),
+                        content_type: "application/octet-stream".to_string(),
+                    },
+                ),
+                ControlKind::Input(InputType::Hidden) if name.eq_ignore_ascii_case("_charset_") => {
+                    push(name, text("UTF-8"));
+                }
+                _ => push(name, text(&control.value)),
+            }
+            if let Some(dirname) = control
+                .dirname
+                .as_deref()
+                .filter(|dirname| !dirname.is_empty())
+            {
+                let direction = match control.direction {
+                    Direction::Ltr => "ltr",
+                    Direction::Rtl => "rtl",
+                };
+                push(dirname, text(direction));
+            }
+        }
+        entries
+    }
+}
+
+/// Encodes entries as `application/x-www-form-urlencoded`, as forms send
+/// them in query strings and bodies. Line breaks become CRLF, and files are
+/// sent as their names.
+pub fn urlencode(entries: &[Entry]) -> String {
+    let mut serializer = form_urlencoded::Serializer::new(String::new());
+    for entry in entries {
+        let value = match &entry.value {
+            EntryValue::Text(text) => text,
+            EntryValue::File { filename, .. } => filename,
+        };
+        serializer.append_pair(&normalize_newlines(&entry.name), &normalize_newlines(value));
+    }
+    serializer.finish()
+}
+
+/// Replaces each CR and LF that isn't part of a CRLF with one.
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', "\r\n")
+}
+
+/// Extracts the forms of `document`, loaded from `url`, in document order.
+pub fn extract_forms(document: &Document, url: &Url) -> Vec<Form> {
+    let base = base_url(document, url);
+    let mut ids: HashMap<&str, Element> = HashMap::new();
+    for element in document.descendants().filter_map(|node| node.as_element()) {
+        if let Some(id) = element.id() {
+            ids.entry(id).or_insert(element);
+        }
+    }
+
+    let mut forms = Vec::new();
+    let mut indexes: HashMap<NodeId, usize> = HashMap::new();
+    let mut pending = Vec::new();
+    for element in html_elements(document) {
+        match element.tag_name() {
+            "form" => {
+                indexes.insert(element.as_node().id(), forms.len());
+                forms.push(read_form(element, &base, url));
+            }
+            "input" | "select" | "textarea" | "button" => {
+                // Controls in a `datalist` only supply suggestions.
+                if has_ancestor(element, "datalist") {
+                    continue;
+                }
+                // A `form` attribute names the owner, even one that comes
+                // later, so controls are placed once all forms are known.
+                let owner = match element.get_attribute("form") {
+                    Some(id) => ids
+                        .get(id)
+                        .filter(|owner| owner.tag_name() == "form")
+                        .map(|owner| owner.as_node().id()),
+                    None => element
+                        .ancestors()
+                        .filter_map(|node| node.as_element())
+                        .find(|ancestor| ancestor.tag_name() == "form")
+                        .map(|form| form.as_node().id()),
+                };
+                if let Some(owner) = owner {
+                    pending.push((owner, read_control(element)));
+                }
+            }
+            _ => {}
+        }
+    }
+    for (owner, control) in pending {
+        if let Some(&index) = indexes.get(&owner) {
+            forms[index].controls.push(control);
+        }
+    }
+    forms
+}
+
+fn read_form(form: Element, base: &Url, url: &Url) -> Form {
+    let attribute = |name| form.get_attribute(name).map(str::to_string);
+    let action = match form.get_attribute("action").map(str::trim) {
+        Some(action) if !action.is_empty() => base.join(action).ok(),
+        _ => Some(url.clone()),
+    };
+    let keyword = |name| {
+        form.get_attribute(name)
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    };
+    Form {
+        node: form.as_node().id(),
+        id: attribute("id"),
+        name: attribute("name"),
+        action,
+        method: match keyword("method").as_str() {
+            "post" => Method::Post,
+            "dialog" => Method::Dialog,
+            _ => Method::Get,
+        },
+        enctype: match keyword("enctype").as_str() {
+            "multipart/form-data" => Enctype::Multipart,
+            "text/plain" => Enctype::TextPlain,
+            _ => Enctype::UrlEncoded,
+        },
+        controls: Vec::new(),
+    }
+}
+
+fn read_control(element: Element) -> Control {
+    let attribute = |name| element.get_attribute(name);
+    let flag = |name| attribute(name).is_some();
+    let kind = match element.tag_name() {
+        "input" => ControlKind::Input(InputType::from_attribute(attribute("type"))),
+        "select" => ControlKind::Select,
+        "textarea" => ControlKind::Textarea,
+        _ => ControlKind::Button(
+            match attribute("type")
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "reset" => ButtonType::Reset,
+                "button" => ButtonType::Button,
+                _ => ButtonType::Submit,
+            },
+        ),
+    };
+    let value = match kind {
+        ControlKind::Input(input_type) => input_value(element, input_type),
+        ControlKind::Textarea => element
+            .text_content()
+            .replace("\r\n", "\n")
+            .replace('\r', "\n"),
+        ControlKind::Button(_) => attribute("value").unwrap_or_default().to_string(),
+        ControlKind::Select => String::new(),
+    };
+    let options = match kind {
+        ControlKind::Select => select_options(element),
+        _ => Vec::new(),
+    };
+    let has_dirname = matches!(
+        kind,
+        ControlKind::Textarea | ControlKind::Input(InputType::Text | InputType::Search)
+    );
+    Control {
+        node: element.as_node().id(),
+        kind,
+        name: attribute("name").map(str::to_string),
+        value,
+        checked: flag("checked"),
+        disabled: is_disabled(element),
+        required: flag("required"),
+        readonly: flag("readonly"),
+        multiple: flag("multiple"),
+        dirname: attribute("dirname")
+            .filter(|_| has_dirname)
+            .map(str::to_string),
+        direction: direction(element),
+        options,
+    }
+}
+
+/// The value of an `input`, sanitized like browsers sanitize it for its
+/// type.
+fn input_value(input: Element, input_type: InputType) -> String {
+    let attribute = input.get_attribute("value");
+    let value = attribute.unwrap_or_default();
+    let strip_newlines = |value: &str| value.replace(['\r', '\n'], "");
+    let trim = |value: &str| {
+        value
+            .trim_matches(|ch: char| ch.is_ascii_whitespace())
+            .to_string()
+    };
+    match input_type {
+        InputType::Text | InputType::Search | InputType::Tel | InputType::Password => {
+            strip_newlines(value)
+        }
+        InputType::Url => trim(&strip_newlines(value)),
+        InputType::Email if input.get_attribute("multiple").is_some() => strip_newlines(value)
+            .split(',')
+            .map(trim)
+            .collect::<Vec<_>>()
+            .join(","),
+        InputType::Email => trim(&strip_newlines(value)),
+        InputType::Number => match parse_number(value) {
+            Some(_) => value.to_string(),
+            None => String::new(),
+        },
+        InputType::Range => range_value(input, value),
+        InputType::Color => {
+            let is_color = value.len() == 7
+                && value.starts_with('#')
+                && value[1..].bytes().all(|byte| byte.is_ascii_hexdigit());
+            match is_color {
+                true => value.to_ascii_lowercase(),
+                false => "#000000".to_string(),
+            }
+        }
+        InputType::Date if is_date(value) => value.to_string(),
+        InputType::Month if is_month(value) => value.to_string(),
+        InputType::Week if is_week(value) => value.to_string(),
+        InputType::Time if is_time(value) => value.to_string(),
+        InputType::DatetimeLocal => match value.split_once(['T', ' ']) {
+            Some((date, time)) if is_date(date) && is_time(time) => format!("{date}T{time}"),
+            _ => String::new(),
+        },
+        InputType::Date | InputType::Month | InputType::Week | InputType::Time => String::new(),
+        InputType::Checkbox | InputType::Radio => attribute.unwrap_or("on").to_string(),
+        InputType::File => String::new(),
+        InputType::Hidden
+        | InputType::Submit
+        | InputType::Image
+        | InputType::Reset
+        | InputType::Button => value.to_string(),
+    }
+}
+
+/// The value of a range: within `min` and `max`, or halfway between them if
+/// it isn't a number.
+fn range_value(input: Element, value: &str) -> String {
+    let bound = |name, default| {
+        input
+            .get_attribute(name)
+            .and_then(parse_number)
+            .unwrap_or(default)
+    };
+    let min = bound("min", 0.0);
+    let max = bound("max", 100.0).max(min);
+    match parse_number(value) {
+        Some(number) if (min..=max).contains(&number) => value.to_string(),
+        Some(number) => number.clamp(min, max).to_string(),
+        None => (min + (max - min) / 2.0).to_string(),
+    }
+}
+
+fn select_options(select: Element) -> Vec<SelectOption> {
+    let mut options = Vec::new();
+    for child in select.children().filter_map(|node| node.as_element()) {
+        let (group, elements): (Option<Element>, Vec<Element>) = match child.tag_name() {
+            "option" => (None, vec![child]),
+            "optgroup" => (
+                Some(child),
+                child
+                    .children()
+                    .filter_map(|node| node.as_element())
+                    .filter(|option| option.tag_name() == "option")
+                    .collect(),
+            ),
+            _ => continue,
+        };
+        for option in elements {
+            let text = collapse_whitespace(&option.text_content());
+            options.push(SelectOption {
+                value: option
+                    .get_attribute("value")
+                    .map_or_else(|| text.clone(), str::to_string),
+                text,
+                selected: option.get_attribute("selected").is_some(),
+                disabled: option.get_attribute("disabled").is_some()
+                    || group.is_some_and(|group| group.get_attribute("disabled").is_some()),
+            });
+        }
+    }
+
+    if select.get_attribute("multiple").is_none() {
+        // Only the last option marked selected stays selected, and a select
+        // showing one row always has one selected if it can.
+        let last = options.iter().rposition(|option| option.selected);
+        for (index, option) in options.iter_mut().enumerate() {
+            option.selected = Some(index) == last;
+        }
+        let size = select
+            .get_attribute("size")
+            .and_then(|size| parse_integer(size.trim()))
+            .unwrap_or(1);
+        if last.is_none() && size <= 1 {
+            if let Some(option) = options.iter_mut().find(|option| !option.disabled) {
+                option.selected = true;
+            }
+        }
+    }
+    options
+}
+
+/// Whether a control is disabled by its own `disabled` attribute or by a
+/// disabled `fieldset` it is in, other than in that fieldset's first
+/// `legend`.
+fn is_disabled(control: Element) -> bool {
+    if control.get_attribute("disabled").is_some() {
+        return true;
+    }
+    let mut child = control.as_node();
+    for ancestor in control.ancestors() {
+        let Some(element) = ancestor.as_element() else {
+            break;
+        };
+        if element.tag_name() == "fieldset" && element.get_attribute("disabled").is_some() {
+            let first_legend = element
+                .children()
+                .filter_map(|node| node.as_element())
+                .find(|child| child.tag_name() == "legend");
+            if first_legend.is_none_or(|legend| legend.as_node() != child) {
+                return true;
+            }
+        }
+        child = ancestor;
+    }
+    false
+}
+
+fn has_ancestor(element: Element, name: &str) -> bool {
+    element
+        .ancestors()
+        .filter_map(|node| node.as_element())
+        .any(|ancestor| ancestor.tag_name() == name)
+}
+
+/// The direction from the nearest `dir` attribute of `ltr` or `rtl`.
+fn direction(element: Element) -> Direction {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(|node| node.as_element()))
+        .find_map(|element| {
+            match element
+                .get_attribute("dir")?
+                .trim()
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "ltr" => Some(Direction::Ltr),
+                "rtl" => Some(Direction::Rtl),
+                _ => None,
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `value` is a valid date string, such as `2024-02-29`.
+fn is_date(value: &str) -> bool {
+    let mut parts = value.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let Some((year, month)) = year_month(year, month) else {
+        return false;
+    };
+    day.len() == 2
+        && parse_integer(day).is_some_and(|day| day >= 1 && day <= days_in_month(year, month))
+}
+
+/// Whether `value` is a valid month string, such as `2024-02`.
+fn is_month(value: &str) -> bool {
+    value
+        .split_once('-')
+        .is_some_and(|(year, month)| year_month(year, month).is_some())
+}
+
+/// Whether `value` is a valid week string, such as `2024-W09`.
+fn is_week(value: &str) -> bool {
+    let Some((year, week)) = value.split_once("-W") else {
+        return false;
+    };
+    let Some(year) = parse_year(year) else {
+        return false;
+    };
+    // Years starting on a Thursday, and leap years starting on a Wednesday,
+    // have 53 weeks.
+    let weekday = weekday_of_january_first(year);
+    let weeks = if weekday == 4 || weekday == 3 && is_leap_year(year) {
+        53
+    } else {
+        52
+    };
+    week.len() == 2 && parse_integer(week).is_some_and(|week| week >= 1 && week <= weeks)
+}
+
+/// Whether `value` is a valid time string, such as `09:30`, `09:30:15` or
+/// `09:30:15.250`.
+fn is_time(value: &str) -> bool {
+    let two_digits = |part: &str, max: u32| {
+        part.len() == 2 && parse_integer(part).is_some_and(|number| number <= max)
+    };
+    let mut parts = value.splitn(3, ':');
+    let (Some(hour), Some(minute)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    if !two_digits(hour, 23) || !two_digits(minute, 59) {
+        return false;
+    }
+    match parts.next() {
+        None => true,
+        Some(seconds) => match seconds.split_once('.') {
+            None => two_digits(seconds, 59),
+            Some((seconds, fraction)) => {
+                two_digits(seconds, 59)
+                    && (1..=3).contains(&fraction.len())
+                    && fraction.bytes().all(|byte| byte.is_ascii_digit())
+            }
+        },
+    }
+}
+
+fn year_month(year: &str, digits: &str) -> Option<(u32, u32)> {
+    let year = parse_year(year)?;
+    let month = parse_integer(digits).filter(|month| (1..=12).contains(month))?;
+    (digits.len() == 2).then_some((year, month))
+}
+
+/// Years have at least four digits and are after zero.
+fn parse_year(year: &str) -> Option<u32> {
+    parse_integer(year).filter(|&number| year.len() >= 4 && number > 0)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn is_leap_year(year: u32) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+/// The day of the week of January 1st of `year`, from 0 for Sunday.
+fn weekday_of_january_first(year: u32) -> u32 {
+    let year = year - 1;
+    (1 + 5 * (year % 4) + 4 * (year % 100) + 6 * (year % 400)) % 7
+}
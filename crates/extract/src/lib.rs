@@ -0,0 +1,88 @@
+//! Extracts data from parsed documents, such as the metadata used for link
+//! previews, the structured data describing products, the URLs a page
+//! refers to, its tables and its forms.
+//!
+//! URLs in documents are resolved like browsers resolve them: against the
+//! document's first `<base href>`, which is itself resolved against the URL
+//! the document was loaded from.
+
+pub mod forms;
+pub mod links;
+pub mod metadata;
+pub mod structured_data;
+pub mod tables;
+
+mod microdata;
+mod rdfa;
+
+use ast::{Document, Element, Namespace};
+
+pub use forms::{
+    extract_forms, urlencode, ButtonType, Control, ControlKind, Direction, Enctype, Entry,
+    EntryValue, Form, InputType, Method, SelectOption,
+};
+pub use links::{extract_links, parse_srcset, Descriptor, ImageCandidate, Link, LinkKind};
+pub use metadata::{extract_metadata, Alternate, Icon, PageMetadata};
+pub use structured_data::{
+    extract_structured_data, Format, Item, JsonLdError, PropertyValue, StructuredData,
+};
+pub use tables::{extract_table, extract_tables, Cell, Row, Scope, Section, Table};
+pub use url::Url;
+
+/// The URL that relative URLs in `document` are resolved against: the first
+/// `base` element's `href` resolved against `url`, the document's own URL,
+/// or `url` itself if there is no such element.
+pub fn base_url(document: &Document, url: &Url) -> Url {
+    html_elements(document)
+        .find(|element| element.tag_name() == "base" && element.get_attribute("href").is_some())
+        .and_then(|base| url.join(base.get_attribute("href")?.trim()).ok())
+        .unwrap_or_else(|| url.clone())
+}
+
+/// The HTML elements in `document`, in document order. SVG and MathML
+/// elements such as `title` are left out.
+fn html_elements(document: &Document) -> impl Iterator<Item = Element<'_>> {
+    document
+        .descendants()
+        .filter_map(|node| node.as_element())
+        .filter(|element| element.namespace() == Namespace::Html)
+}
+
+/// Strips leading and trailing ASCII whitespace and collapses the runs of it
+/// inside to single spaces.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_ascii_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A valid non-negative integer: ASCII digits only.
+fn parse_integer(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// A valid floating-point number: an optional `-`, digits, a fraction or
+/// both, and an optional exponent. Unlike Rust's, it can't start with `+`
+/// or end with `.`.
+fn parse_number(value: &str) -> Option<f64> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let (mantissa, exponent) = match digits.find(['e', 'E']) {
+        Some(index) => (&digits[..index], Some(&digits[index + 1..])),
+        None => (digits, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    let valid = (is_digits(whole) || whole.is_empty() && fraction.is_some())
+        && fraction.is_none_or(is_digits)
+        && exponent.is_none_or(|exponent| {
+            is_digits(exponent.strip_prefix(['-', '+']).unwrap_or(exponent))
+        });
+    if !valid {
+        return None;
+    }
+    value.parse().ok().filter(|number: &f64| number.is_finite())
+}
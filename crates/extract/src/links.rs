@@ -0,0 +1,514 @@
+//! The URLs a page refers to: its hyperlinks, images, scripts, frames,
+//! stylesheets and other `link`s, form actions, refresh targets and the
+//! `url()`s of inline styles.
+//!
+//! ```
+//! use extract::{extract_links, LinkKind, Url};
+//!
+//! let document = parser::parse_html(
+//!     "<a href=../about>About</a><img srcset=\"small.png 1x, large.png 2x\">",
+//! );
+//! let url = Url::parse("https://example.com/blog/post").unwrap();
+//! let links = extract_links(&document, &url);
+//! assert_eq!(links[0].kind, LinkKind::Hyperlink);
+//! assert_eq!(links[0].url.as_str(), "https://example.com/about");
+//! assert_eq!(links[2].url.as_str(), "https://example.com/blog/large.png");
+//! ```
+
+use ast::{Document, Element, Namespace, NodeId};
+use url::Url;
+
+use crate::{base_url, parse_integer, parse_number};
+
+/// A URL a document refers to, with where it was found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub url: Url,
+    pub kind: LinkKind,
+    /// The element the reference is on.
+    pub node: NodeId,
+    /// The attribute the reference is in, such as `href` or `srcset`.
+    pub attribute: &'static str,
+    /// The reference as written, before it was resolved.
+    pub reference: String,
+    /// The width or pixel density of an image candidate from `srcset`.
+    pub descriptor: Option<Descriptor>,
+    /// The `rel` keywords of `a`, `area`, `link` and `form` elements,
+    /// lowercased.
+    pub rel: Vec<String>,
+}
+
+/// What a [`Link`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LinkKind {
+    /// The `href` of `a` and `area` elements.
+    Hyperlink,
+    /// The `href` of a `link` element, whose `rel` says what it is.
+    Link,
+    /// The `src` of an `img`, or a candidate from the `srcset` of an `img`
+    /// or a `picture`'s `source`.
+    Image,
+    /// The `src` of a `script`.
+    Script,
+    /// The `src` of an `iframe`.
+    Frame,
+    /// The `action` of a `form`.
+    Form,
+    /// The URL a `<meta http-equiv=refresh>` redirects to.
+    Refresh,
+    /// A `url()` in a `style` attribute.
+    Style,
+}
+
+/// The descriptor of an image candidate in `srcset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Descriptor {
+    /// A width in pixels, such as `480w`.
+    Width(u32),
+    /// A pixel density, such as `2x`.
+    Density(f64),
+}
+
+/// An image candidate of a `srcset` attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCandidate {
+    pub url: String,
+    /// The descriptor, if one was given. Candidates without one are `1x`.
+    pub descriptor: Option<Descriptor>,
+}
+
+/// Extracts the URLs `document`, loaded from `url`, refers to, in document
+/// order. References that can't be resolved to URLs are left out, as are
+/// empty `src` attributes, which don't load anything.
+pub fn extract_links(document: &Document, url: &Url) -> Vec<Link> {
+    let base = base_url(document, url);
+    let mut links = Links {
+        base: &base,
+        url,
+        links: Vec::new(),
+    };
+    for element in document.descendants().filter_map(|node| node.as_element()) {
+        if element.namespace() == Namespace::Html {
+            links.read(element);
+        }
+        // `style` attributes are global, so SVG elements can have them too.
+        if let Some(style) = element.get_attribute("style") {
+            for reference in css_urls(style) {
+                links.push(element, LinkKind::Style, "style", &reference, None);
+            }
+        }
+    }
+    links.links
+}
+
+struct Links<'a> {
+    base: &'a Url,
+    /// The document's own URL, which empty form actions resolve to.
+    url: &'a Url,
+    links: Vec<Link>,
+}
+
+impl Links<'_> {
+    fn read(&mut self, element: Element) {
+        let attribute = |name| element.get_attribute(name);
+        match element.tag_name() {
+            "a" | "area" => {
+                if let Some(href) = attribute("href") {
+                    self.push(element, LinkKind::Hyperlink, "href", href, None);
+                }
+            }
+            "link" => {
+                if let Some(href) = attribute("href") {
+                    self.push(element, LinkKind::Link, "href", href, None);
+                }
+            }
+            "img" => {
+                self.source(element, LinkKind::Image);
+                self.srcset(element);
+            }
+            "source" => {
+                let in_picture = element
+                    .parent()
+                    .and_then(|parent| parent.as_element())
+                    .is_some_and(|parent| parent.tag_name() == "picture");
+                if in_picture {
+                    self.srcset(element);
+                }
+            }
+            "script" => self.source(element, LinkKind::Script),
+            "iframe" => self.source(element, LinkKind::Frame),
+            "form" => match attribute("action") {
+                Some(action) if action.trim().is_empty() => {
+                    let url = self.url.clone();
+                    self.links.push(Link {
+                        url,
+                        kind: LinkKind::Form,
+                        node: element.as_node().id(),
+                        attribute: "action",
+                        reference: action.to_string(),
+                        descriptor: None,
+                        rel: rel(element),
+                    });
+                }
+                Some(action) => self.push(element, LinkKind::Form, "action", action, None),
+                None => {}
+            },
+            "meta" => {
+                let is_refresh = attribute("http-equiv")
+                    .is_some_and(|value| value.trim().eq_ignore_ascii_case("refresh"));
+                if let Some(target) = attribute("content")
+                    .filter(|_| is_refresh)
+                    .and_then(refresh_url)
+                {
+                    self.push(element, LinkKind::Refresh, "content", target, None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn source(&mut self, element: Element, kind: LinkKind) {
+        if let Some(src) = element
+            .get_attribute("src")
+            .filter(|src| !src.trim().is_empty())
+        {
+            self.push(element, kind, "src", src, None);
+        }
+    }
+
+    fn srcset(&mut self, element: Element) {
+        let Some(srcset) = element.get_attribute("srcset") else {
+            return;
+        };
+        for candidate in parse_srcset(srcset) {
+            self.push(
+                element,
+                LinkKind::Image,
+                "srcset",
+                &candidate.url,
+                candidate.descriptor,
+            );
+        }
+    }
+
+    fn push(
+        &mut self,
+        element: Element,
+        kind: LinkKind,
+        attribute: &'static str,
+        reference: &str,
+        descriptor: Option<Descriptor>,
+    ) {
+        let Ok(url) = self.base.join(reference.trim()) else {
+            return;
+        };
+        self.links.push(Link {
+            url,
+            kind,
+            node: element.as_node().id(),
+            attribute,
+            reference: reference.to_string(),
+            descriptor,
+            rel: rel(element),
+        });
+    }
+}
+
+fn rel(element: Element) -> Vec<String> {
+    match element.tag_name() {
+        "a" | "area" | "link" | "form" => element
+            .get_attribute("rel")
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+            .map(str::to_ascii_lowercase)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parses a `srcset` attribute with the HTML standard's algorithm. Invalid
+/// candidates, such as those with two widths, are left out.
+pub fn parse_srcset(srcset: &str) -> Vec<ImageCandidate> {
+    enum State {
+        InDescriptor,
+        InParens,
+        AfterDescriptor,
+    }
+
+    let chars: Vec<char> = srcset.chars().collect();
+    let is_space = |ch: &char| ch.is_ascii_whitespace();
+    let mut candidates = Vec::new();
+    let mut position = 0;
+    loop {
+        while position < chars.len() && (is_space(&chars[position]) || chars[position] == ',') {
+            position += 1;
+        }
+        if position == chars.len() {
+            return candidates;
+        }
+        let start = position;
+        while position < chars.len() && !is_space(&chars[position]) {
+            position += 1;
+        }
+        let mut url: String = chars[start..position].iter().collect();
+
+        let mut descriptors = Vec::new();
+        if url.ends_with(',') {
+            url.truncate(url.trim_end_matches(',').len());
+        } else {
+            while position < chars.len() && is_space(&chars[position]) {
+                position += 1;
+            }
+            let mut current = String::new();
+            let mut state = State::InDescriptor;
+            loop {
+                let ch = chars.get(position).copied();
+                match (&state, ch) {
+                    (State::InDescriptor, Some(ch)) if ch.is_ascii_whitespace() => {
+                        if !current.is_empty() {
+                            descriptors.push(std::mem::take(&mut current));
+                            state = State::AfterDescriptor;
+                        }
+                    }
+                    (State::InDescriptor, Some(',')) => {
+                        position += 1;
+                        break;
+                    }
+                    (State::InDescriptor, Some('(')) => {
+                        current.push('(');
+                        state = State::InParens;
+                    }
+                    (State::InDescriptor | State::InParens, None) => break,
+                    (State::InParens, Some(')')) => {
+                        current.push(')');
+                        state = State::InDescriptor;
+                    }
+                    (State::InDescriptor | State::InParens, Some(ch)) => current.push(ch),
+                    (State::AfterDescriptor, Some(ch)) if ch.is_ascii_whitespace() => {}
+                    (State::AfterDescriptor, None) => break,
+                    (State::AfterDescriptor, Some(_)) => {
+                        state = State::InDescriptor;
+                        continue;
+                    }
+                }
+                position += 1;
+            }
+            if !current.is_empty() {
+                descriptors.push(current);
+            }
+        }
+
+        if let Some(descriptor) = parse_descriptors(&descriptors) {
+            candidates.push(ImageCandidate { url, descriptor });
+        }
+    }
+}
+
+/// The descriptor of a candidate, `Some(None)` if it has none, or `None` if
+/// its descriptors are invalid.
+fn parse_descriptors(descriptors: &[String]) -> Option<Option<Descriptor>> {
+    let mut width = None;
+    let mut density = None;
+    let mut height = None;
+    for descriptor in descriptors {
+        let (value, unit) =
+            descriptor.split_at(descriptor.len() - descriptor.chars().last()?.len_utf8());
+        match unit {
+            "w" if width.is_none() && density.is_none() => {
+                width = Some(parse_integer(value).filter(|&width| width > 0)?);
+            }
+            "x" if width.is_none() && density.is_none() && height.is_none() => {
+                density = Some(parse_number(value).filter(|&density| density >= 0.0)?);
+            }
+            "h" if height.is_none() && density.is_none() => {
+                height = Some(parse_integer(value).filter(|&height| height > 0)?);
+            }
+            _ => return None,
+        }
+    }
+    if height.is_some() && width.is_none() {
+        return None;
+    }
+    Some(
+        width
+            .map(Descriptor::Width)
+            .or(density.map(Descriptor::Density)),
+    )
+}
+
+/// The URL in the `content` of a `<meta http-equiv=refresh>`, such as
+/// `5; url=/next`, read like browsers read it.
+fn refresh_url(content: &str) -> Option<&str> {
+    let rest = content.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+    let time = rest.trim_start_matches(|ch: char| ch.is_ascii_digit());
+    if time.len() == rest.len() && !time.starts_with('.') {
+        return None;
+    }
+    let rest = time.trim_start_matches(|ch: char| ch.is_ascii_digit() || ch == '.');
+    if !rest.starts_with([';', ',']) && !rest.starts_with(|ch: char| ch.is_ascii_whitespace()) {
+        return None;
+    }
+    let rest = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+    let rest = rest.strip_prefix([';', ',']).unwrap_or(rest);
+    let url = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+    if url.is_empty() {
+        return None;
+    }
+
+    // An optional `url=`, then optional quotes. A prefix that only starts
+    // like `url=` is part of the URL.
+    let unquoted = match url.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+            let after = url[3..].trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+            match after.strip_prefix('=') {
+                Some(after) => after.trim_start_matches(|ch: char| ch.is_ascii_whitespace()),
+                None => return Some(url),
+            }
+        }
+        _ => url,
+    };
+    match unquoted.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let quoted = &unquoted[1..];
+            Some(quoted.split(quote).next().unwrap_or(quoted))
+        }
+        _ => Some(unquoted),
+    }
+}
+
+/// The URLs of the `url()` functions in a CSS declaration list, with escapes
+/// decoded. Comments and strings are skipped, as are malformed `url()`s.
+fn css_urls(css: &str) -> Vec<String> {
+    let chars: Vec<char> = css.chars().collect();
+    let mut urls = Vec::new();
+    let mut position = 0;
+    while position < chars.len() {
+        let rest = &chars[position..];
+        match rest[0] {
+            '/' if rest.get(1) == Some(&'*') => {
+                position += 2;
+                while position < chars.len() && !chars[position..].starts_with(&['*', '/']) {
+                    position += 1;
+                }
+                position += 2;
+            }
+            quote @ ('"' | '\'') => {
+                position = read_string(&chars, position + 1, quote).1;
+            }
+            '\\' => position += 2,
+            ch if is_name_char(ch) => {
+                let start = position;
+                while position < chars.len() && is_name_char(chars[position]) {
+                    position += 1;
+                }
+                let name: String = chars[start..position].iter().collect();
+                if name.eq_ignore_ascii_case("url") && chars.get(position) == Some(&'(') {
+                    let (url, end) = read_url(&chars, position + 1);
+                    urls.extend(url);
+                    position = end;
+                }
+            }
+            _ => position += 1,
+        }
+    }
+    urls
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_') || !ch.is_ascii()
+}
+
+/// Reads the rest of a `url(` from `position`, returning the URL, if the
+/// `url()` is well-formed, and where it ends.
+fn read_url(chars: &[char], mut position: usize) -> (Option<String>, usize) {
+    let skip_space = |mut position: usize| {
+        while chars
+            .get(position)
+            .is_some_and(|ch| ch.is_ascii_whitespace())
+        {
+            position += 1;
+        }
+        position
+    };
+    position = skip_space(position);
+    if let Some(&quote @ ('"' | '\'')) = chars.get(position) {
+        let (url, end) = read_string(chars, position + 1, quote);
+        position = skip_space(end);
+        return match (url, chars.get(position)) {
+            (Some(url), Some(')')) => (Some(url), position + 1),
+            _ => (None, position),
+        };
+    }
+
+    let mut url = String::new();
+    while let Some(&ch) = chars.get(position) {
+        position += 1;
+        match ch {
+            ')' => return (Some(url), position),
+            '\\' => {
+                let (ch, end) = read_escape(chars, position);
+                url.push(ch);
+                position = end;
+            }
+            ch if ch.is_ascii_whitespace() => {
+                position = skip_space(position);
+                return match chars.get(position) {
+                    Some(')') => (Some(url), position + 1),
+                    _ => (None, position),
+                };
+            }
+            '"' | '\'' | '(' => return (None, position),
+            ch => url.push(ch),
+        }
+    }
+    (None, position)
+}
+
+/// Reads a string from after its opening `quote`, returning its value, or
+/// `None` if a newline ends it early, and the position after it.
+fn read_string(chars: &[char], mut position: usize, quote: char) -> (Option<String>, usize) {
+    let mut value = String::new();
+    while let Some(&ch) = chars.get(position) {
+        position += 1;
+        match ch {
+            _ if ch == quote => return (Some(value), position),
+            '\n' => return (None, position),
+            '\\' if chars.get(position) == Some(&'\n') => position += 1,
+            '\\' => {
+                let (ch, end) = read_escape(chars, position);
+                value.push(ch);
+                position = end;
+            }
+            ch => value.push(ch),
+        }
+    }
+    (Some(value), position)
+}
+
+/// Reads an escape from after its backslash: up to six hex digits and a
+/// whitespace character, or any other character as itself.
+fn read_escape(chars: &[char], position: usize) -> (char, usize) {
+    let digits = chars[position..]
+        .iter()
+        .take(6)
+        .take_while(|ch| ch.is_ascii_hexdigit())
+        .count();
+    if digits == 0 {
+        return match chars.get(position) {
+            Some(&ch) => (ch, position + 1),
+            None => (char::REPLACEMENT_CHARACTER, position),
+        };
+    }
+    let hex: String = chars[position..position + digits].iter().collect();
+    let ch = u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .filter(|&ch| ch != '\0')
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    let mut end = position + digits;
+    if chars.get(end).is_some_and(|ch| ch.is_ascii_whitespace()) {
+        end += 1;
+    }
+    (ch, end)
+}
@@ -0,0 +1,230 @@
+//! The metadata of a page used for link previews: its title, `meta` tags,
+//! Open Graph and Twitter card properties, and its canonical, icon and
+//! alternate links.
+//!
+//! ```
+//! use extract::{extract_metadata, Url};
+//!
+//! let document = parser::parse_html(
+//!     "<title>Home</title><meta property=og:image content=/cover.png>",
+//! );
+//! let url = Url::parse("https://example.com/blog/").unwrap();
+//! let metadata = extract_metadata(&document, &url);
+//! assert_eq!(metadata.title.as_deref(), Some("Home"));
+//! assert_eq!(
+//!     metadata.open_graph_property("og:image"),
+//!     Some("https://example.com/cover.png")
+//! );
+//! ```
+
+use ast::{Document, Element};
+use url::Url;
+
+use crate::{base_url, collapse_whitespace, html_elements};
+
+/// Open Graph and Twitter card properties whose values are URLs.
+const URL_PROPERTIES: &[&str] = &[
+    "og:url",
+    "og:image",
+    "og:image:url",
+    "og:image:secure_url",
+    "og:video",
+    "og:video:url",
+    "og:video:secure_url",
+    "og:audio",
+    "og:audio:url",
+    "og:audio:secure_url",
+    "twitter:image",
+    "twitter:image:src",
+    "twitter:player",
+    "twitter:player:stream",
+];
+
+/// `rel` keywords of links to icons.
+const ICON_KEYWORDS: &[&str] = &[
+    "icon",
+    "apple-touch-icon",
+    "apple-touch-icon-precomposed",
+    "mask-icon",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageMetadata {
+    /// The text of the first `title` element, with its whitespace collapsed.
+    pub title: Option<String>,
+    /// The `lang` attribute of the `html` element.
+    pub lang: Option<String>,
+    /// The URL relative URLs were resolved against, from `<base href>` or
+    /// the document's URL.
+    pub base_url: Url,
+    /// The character encoding from `<meta charset>`.
+    pub charset: Option<String>,
+    /// The `name` and `content` of `meta` elements, with names lowercased,
+    /// in document order. Open Graph and Twitter properties are kept apart.
+    pub meta: Vec<(String, String)>,
+    /// Open Graph properties such as `og:title`, in document order. Some,
+    /// such as `og:image`, may be given more than once. Those that are URLs
+    /// are resolved.
+    pub open_graph: Vec<(String, String)>,
+    /// Twitter card properties such as `twitter:card`, in document order,
+    /// with URLs resolved.
+    pub twitter: Vec<(String, String)>,
+    /// The URL of the first `link rel=canonical`.
+    pub canonical: Option<Url>,
+    pub icons: Vec<Icon>,
+    pub alternates: Vec<Alternate>,
+}
+
+impl PageMetadata {
+    /// The content of the first `meta` element named `name`.
+    pub fn meta_content(&self, name: &str) -> Option<&str> {
+        find(&self.meta, name)
+    }
+
+    /// The first value of an Open Graph property, e.g. `og:title`.
+    pub fn open_graph_property(&self, property: &str) -> Option<&str> {
+        find(&self.open_graph, property)
+    }
+
+    /// The first value of a Twitter card property, e.g. `twitter:card`.
+    pub fn twitter_property(&self, property: &str) -> Option<&str> {
+        find(&self.twitter, property)
+    }
+}
+
+fn find<'a>(pairs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// A `link` to an icon, such as `rel=icon` or `rel=apple-touch-icon`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Icon {
+    pub url: Url,
+    /// The `rel` attribute, lowercased.
+    pub rel: String,
+    pub sizes: Option<String>,
+    /// The `type` attribute.
+    pub media_type: Option<String>,
+}
+
+/// A `link rel=alternate`, such as a translation or a feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alternate {
+    pub url: Url,
+    pub hreflang: Option<String>,
+    /// The `type` attribute.
+    pub media_type: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Extracts the metadata of a document loaded from `url`. Links that can't
+/// be resolved to URLs are left out.
+pub fn extract_metadata(document: &Document, url: &Url) -> PageMetadata {
+    let base = base_url(document, url);
+    let mut metadata = PageMetadata {
+        title: None,
+        lang: None,
+        base_url: base.clone(),
+        charset: None,
+        meta: Vec::new(),
+        open_graph: Vec::new(),
+        twitter: Vec::new(),
+        canonical: None,
+        icons: Vec::new(),
+        alternates: Vec::new(),
+    };
+
+    metadata.lang = document
+        .root()
+        .children()
+        .filter_map(|node| node.as_element())
+        .find(|element| element.tag_name() == "html")
+        .and_then(|html| html.get_attribute("lang"))
+        .map(str::to_string);
+
+    for element in html_elements(document) {
+        match element.tag_name() {
+            "title" if metadata.title.is_none() => {
+                metadata.title = Some(collapse_whitespace(&element.text_content()));
+            }
+            "meta" => read_meta(element, &base, &mut metadata),
+            "link" => read_link(element, &base, &mut metadata),
+            _ => {}
+        }
+    }
+    metadata
+}
+
+fn read_meta(element: Element, base: &Url, metadata: &mut PageMetadata) {
+    if let Some(charset) = element.get_attribute("charset") {
+        metadata
+            .charset
+            .get_or_insert_with(|| charset.trim().to_string());
+    }
+    let Some(content) = element.get_attribute("content") else {
+        return;
+    };
+    // Open Graph uses `property` and Twitter `name`, but pages mix them up.
+    let name = element
+        .get_attribute("property")
+        .or_else(|| element.get_attribute("name"))
+        .map(|name| name.trim().to_ascii_lowercase());
+    let Some(name) = name.filter(|name| !name.is_empty()) else {
+        return;
+    };
+
+    let content = match URL_PROPERTIES.contains(&name.as_str()) {
+        true => base
+            .join(content.trim())
+            .map_or_else(|_| content.to_string(), String::from),
+        false => content.to_string(),
+    };
+    let list = if name.starts_with("og:") {
+        &mut metadata.open_graph
+    } else if name.starts_with("twitter:") {
+        &mut metadata.twitter
+    } else if element.get_attribute("name").is_some() {
+        &mut metadata.meta
+    } else {
+        return;
+    };
+    list.push((name, content));
+}
+
+fn read_link(element: Element, base: &Url, metadata: &mut PageMetadata) {
+    let (Some(rel), Some(href)) = (element.get_attribute("rel"), element.get_attribute("href"))
+    else {
+        return;
+    };
+    let Ok(url) = base.join(href.trim()) else {
+        return;
+    };
+    let rel = rel.to_ascii_lowercase();
+    let keywords: Vec<&str> = rel.split_ascii_whitespace().collect();
+    let attribute = |name| element.get_attribute(name).map(str::to_string);
+    if keywords.contains(&"canonical") && metadata.canonical.is_none() {
+        metadata.canonical = Some(url.clone());
+    }
+    if keywords
+        .iter()
+        .any(|keyword| ICON_KEYWORDS.contains(keyword))
+    {
+        metadata.icons.push(Icon {
+            url: url.clone(),
+            rel: rel.clone(),
+            sizes: attribute("sizes"),
+            media_type: attribute("type"),
+        });
+    }
+    if keywords.contains(&"alternate") {
+        metadata.alternates.push(Alternate {
+            url,
+            hreflang: attribute("hreflang"),
+            media_type: attribute("type"),
+            title: attribute("title"),
+        });
+    }
+}
@@ -0,0 +1,168 @@
+//! Microdata items, read with the HTML standard's algorithm: an item's
+//! properties are the `itemprop` elements found by crawling its subtree and
+//! the elements its `itemref` names, stopping at nested items.
+
+use std::collections::{HashMap, HashSet};
+
+use ast::{Document, Element, NodeId};
+use url::Url;
+
+use crate::structured_data::{resolve, Format, Item, PropertyValue};
+
+/// The top-level items of `document`: those whose elements have
+/// `itemscope` but no `itemprop`.
+pub(crate) fn items(document: &Document, base: &Url) -> Vec<Item> {
+    let elements: Vec<Element> = document
+        .descendants()
+        .filter_map(|node| node.as_element())
+        .collect();
+    let mut microdata = Microdata {
+        base,
+        ids: HashMap::new(),
+        order: HashMap::new(),
+        built: HashMap::new(),
+        building: Vec::new(),
+    };
+    for (position, element) in elements.iter().enumerate() {
+        microdata.order.insert(element.as_node().id(), position);
+        if let Some(id) = element.id() {
+            microdata.ids.entry(id).or_insert(*element);
+        }
+    }
+
+    // Nested items come after the items they belong to, so building from
+    // the end finds them built already instead of recursing into them.
+    let scopes: Vec<Element> = elements
+        .into_iter()
+        .filter(|element| element.get_attribute("itemscope").is_some())
+        .collect();
+    let mut items: Vec<Item> = scopes
+        .iter()
+        .rev()
+        .map(|element| microdata.item(*element))
+        .collect();
+    items.reverse();
+    scopes
+        .iter()
+        .zip(items)
+        .filter(|(element, _)| element.get_attribute("itemprop").is_none())
+        .map(|(_, item)| item)
+        .collect()
+}
+
+struct Microdata<'a> {
+    base: &'a Url,
+    /// The first element with each `id`, for `itemref`.
+    ids: HashMap<&'a str, Element<'a>>,
+    /// The position of each element in document order.
+    order: HashMap<NodeId, usize>,
+    /// The items that can be reused wherever they are nested.
+    built: HashMap<NodeId, Item>,
+    /// The items being built, so `itemref` cycles leave an item out of its
+    /// own properties rather than recursing forever.
+    building: Vec<NodeId>,
+}
+
+impl<'a> Microdata<'a> {
+    fn item(&mut self, element: Element<'a>) -> Item {
+        self.build(element).0
+    }
+
+    /// Builds the item of `element`, and tells whether it could be kept:
+    /// only items that don't use `itemref` are the same wherever they are
+    /// nested, as only `itemref` can make cycles.
+    fn build(&mut self, element: Element<'a>) -> (Item, bool) {
+        let id = element.as_node().id();
+        if let Some(item) = self.built.get(&id) {
+            return (item.clone(), true);
+        }
+        let mut keep = element.get_attribute("itemref").is_none();
+        self.building.push(id);
+        let mut properties = Vec::new();
+        for property in self.properties(element) {
+            let value = if property.get_attribute("itemscope").is_some() {
+                if self.building.contains(&property.as_node().id()) {
+                    continue;
+                }
+                let (item, kept) = self.build(property);
+                keep &= kept;
+                PropertyValue::Item(item)
+            } else {
+                PropertyValue::Text(self.value(property))
+            };
+            let names = property.get_attribute("itemprop").unwrap_or_default();
+            for name in names.split_ascii_whitespace() {
+                properties.push((name.to_string(), value.clone()));
+            }
+        }
+        self.building.pop();
+
+        let item = Item {
+            format: Format::Microdata,
+            types: element
+                .get_attribute("itemtype")
+                .unwrap_or_default()
+                .split_ascii_whitespace()
+                .map(str::to_string)
+                .collect(),
+            id: element
+                .get_attribute("itemid")
+                .map(|itemid| resolve(self.base, Some(itemid)))
+                .filter(|itemid| !itemid.is_empty()),
+            properties,
+        };
+        if keep {
+            self.built.insert(id, item.clone());
+        }
+        (item, keep)
+    }
+
+    /// The elements with properties of the item `root`, in document order.
+    fn properties(&self, root: Element<'a>) -> Vec<Element<'a>> {
+        let mut results = Vec::new();
+        let mut memory = HashSet::from([root.as_node().id()]);
+        let mut pending: Vec<Element> = root
+            .children()
+            .filter_map(|node| node.as_element())
+            .collect();
+        let references = root.get_attribute("itemref").unwrap_or_default();
+        pending.extend(
+            references
+                .split_ascii_whitespace()
+                .filter_map(|id| self.ids.get(id).copied()),
+        );
+        while let Some(current) = pending.pop() {
+            if !memory.insert(current.as_node().id()) {
+                continue;
+            }
+            if current.get_attribute("itemscope").is_none() {
+                pending.extend(current.children().filter_map(|node| node.as_element()));
+            }
+            let has_names = current
+                .get_attribute("itemprop")
+                .is_some_and(|names| names.split_ascii_whitespace().next().is_some());
+            if has_names {
+                results.push(current);
+            }
+        }
+        results.sort_by_key(|element| self.order[&element.as_node().id()]);
+        results
+    }
+
+    /// The value of a property that isn't an item, which depends on the
+    /// element that gives it.
+    fn value(&self, element: Element<'a>) -> String {
+        let attribute = |name| element.get_attribute(name);
+        match element.tag_name() {
+            "meta" => attribute("content").unwrap_or_default().to_string(),
+            "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => {
+                resolve(self.base, attribute("src"))
+            }
+            "a" | "area" | "link" => resolve(self.base, attribute("href")),
+            "object" => resolve(self.base, attribute("data")),
+            "data" | "meter" => attribute("value").unwrap_or_default().to_string(),
+            "time" => attribute("datetime").map_or_else(|| element.text_content(), str::to_string),
+            _ => element.text_content(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! RDFa Lite items: elements with `typeof`, whose properties are the
+//! `property` elements below them up to the next `typeof`. Types are
+//! expanded with the nearest `vocab` or a prefix from `prefix` attributes.
+
+use std::collections::HashMap;
+
+use ast::{Document, Element, NodeId};
+use url::Url;
+
+use crate::structured_data::{expand, resolve, Format, Item, PropertyValue};
+
+/// Prefixes from the RDFa initial context that pages use without declaring.
+const PREFIXES: &[(&str, &str)] = &[
+    ("dc", "http://purl.org/dc/terms/"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("og", "http://ogp.me/ns#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("schema", "http://schema.org/"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// The top-level items of `document`: those whose elements have `typeof`
+/// and either no `property` or no item above them.
+pub(crate) fn items(document: &Document, base: &Url) -> Vec<Item> {
+    let elements: Vec<Element> = document
+        .descendants()
+        .filter_map(|node| node.as_element())
+        .collect();
+    // Going backwards, the properties of an item are all collected by the
+    // time its element is reached. They are reversed into document order
+    // when the item is built.
+    let mut properties: HashMap<NodeId, Vec<(String, PropertyValue)>> = HashMap::new();
+    let mut items = Vec::new();
+    for element in elements.into_iter().rev() {
+        let names = element.get_attribute("property");
+        let types = element.get_attribute("typeof");
+        if names.is_none() && types.is_none() {
+            continue;
+        }
+        let value = match types {
+            Some(types) => {
+                let mut own = properties
+                    .remove(&element.as_node().id())
+                    .unwrap_or_default();
+                own.reverse();
+                PropertyValue::Item(Item {
+                    format: Format::Rdfa,
+                    types: types
+                        .split_ascii_whitespace()
+                        .map(|type_| expand_term(element, type_))
+                        .collect(),
+                    id: element
+                        .get_attribute("resource")
+                        .map(|resource| resolve(base, Some(resource)))
+                        .filter(|resource| !resource.is_empty()),
+                    properties: own,
+                })
+            }
+            None => PropertyValue::Text(value(element, base)),
+        };
+        let owner = element
+            .ancestors()
+            .filter_map(|node| node.as_element())
+            .find(|ancestor| ancestor.get_attribute("typeof").is_some());
+        match (names, owner) {
+            (Some(names), Some(owner)) => {
+                let list = properties.entry(owner.as_node().id()).or_default();
+                for name in names.split_ascii_whitespace().rev() {
+                    list.push((name.to_string(), value.clone()));
+                }
+            }
+            _ => {
+                if let PropertyValue::Item(item) = value {
+                    items.push(item);
+                }
+            }
+        }
+    }
+    items.reverse();
+    items
+}
+
+/// The value of a `property` element that isn't an item.
+fn value(element: Element, base: &Url) -> String {
+    let attribute = |name| element.get_attribute(name);
+    if let Some(content) = attribute("content") {
+        return content.to_string();
+    }
+    if let Some(url) = attribute("resource")
+        .or_else(|| attribute("href"))
+        .or_else(|| attribute("src"))
+    {
+        return resolve(base, Some(url));
+    }
+    match attribute("datetime") {
+        Some(datetime) if element.tag_name() == "time" => datetime.to_string(),
+        _ => element.text_content(),
+    }
+}
+
+/// Expands a type such as `Product` or `schema:Product` to a URL.
+fn expand_term(element: Element, term: &str) -> String {
+    match term.split_once(':') {
+        Some((_, local)) if local.starts_with("//") => term.to_string(),
+        Some((prefix, local)) => match prefix_uri(element, prefix) {
+            Some(uri) => format!("{uri}{local}"),
+            None => term.to_string(),
+        },
+        None => {
+            let vocabulary = scopes(element).find_map(|scope| scope.get_attribute("vocab"));
+            expand(term, vocabulary.map(str::trim))
+        }
+    }
+}
+
+/// The URL of `prefix` as declared by the nearest `prefix` attribute that
+/// names it, or as predefined.
+fn prefix_uri<'a>(element: Element<'a>, prefix: &str) -> Option<&'a str> {
+    let declared = scopes(element)
+        .filter_map(|scope| scope.get_attribute("prefix"))
+        .find_map(|declarations| {
+            // Declarations are `name: uri` pairs.
+            let mut tokens = declarations.split_ascii_whitespace();
+            while let Some(name) = tokens.next() {
+                let uri = tokens.next()?;
+                if name.strip_suffix(':') == Some(prefix) {
+                    return Some(uri);
+                }
+            }
+            None
+        });
+    declared.or_else(|| {
+        PREFIXES
+            .iter()
+            .find(|(name, _)| *name == prefix)
+            .map(|(_, uri)| *uri)
+    })
+}
+
+/// `element` and the elements above it, nearest first.
+fn scopes(element: Element) -> impl Iterator<Item = Element> {
+    std::iter::once(element).chain(element.ancestors().filter_map(|node| node.as_element()))
+}
@@ -0,0 +1,325 @@
+//! Structured data embedded in pages, such as the schema.org descriptions of
+//! products and articles: JSON-LD `script` blocks, Microdata and RDFa Lite.
+//!
+//! The three formats are read into the same [`Item`] shape, so callers can
+//! look for a `Product` without caring how the page described it. Types are
+//! expanded to URLs when the page gives a vocabulary, and property names are
+//! kept as written.
+//!
+//! ```
+//! use extract::{extract_structured_data, Url};
+//!
+//! let document = parser::parse_html(
+//!     "<div itemscope itemtype=https://schema.org/Product>\
+//!      <h1 itemprop=name>Lamp</h1><img itemprop=image src=lamp.jpg></div>",
+//! );
+//! let url = Url::parse("https://shop.example/lamps/").unwrap();
+//! let data = extract_structured_data(&document, &url);
+//! let product = &data.items[0];
+//! assert!(product.has_type("Product"));
+//! assert_eq!(product.text("name"), Some("Lamp"));
+//! assert_eq!(product.text("image"), Some("https://shop.example/lamps/lamp.jpg"));
+//! ```
+
+use ast::{Document, NodeId};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::{base_url, html_elements, microdata, rdfa};
+
+/// The structured data of a document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StructuredData {
+    /// The items of all formats: those from JSON-LD first, then Microdata,
+    /// then RDFa, each in document order.
+    pub items: Vec<Item>,
+    /// The JSON-LD `script` blocks as they were parsed, in document order.
+    pub json_ld: Vec<Value>,
+    /// The JSON-LD blocks that couldn't be parsed.
+    pub json_ld_errors: Vec<JsonLdError>,
+}
+
+impl StructuredData {
+    /// The items of `type_`, including nested ones, in the order of
+    /// [`StructuredData::items`] with nested items after their parents.
+    pub fn items_of_type<'a>(&'a self, type_: &'a str) -> impl Iterator<Item = &'a Item> + 'a {
+        let mut stack: Vec<&Item> = self.items.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let item = stack.pop()?;
+            let nested = item
+                .properties
+                .iter()
+                .rev()
+                .filter_map(|(_, value)| value.as_item());
+            stack.extend(nested);
+            Some(item)
+        })
+        .filter(move |item| item.has_type(type_))
+    }
+}
+
+/// A JSON-LD `script` element whose text isn't valid JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonLdError {
+    /// The `script` element.
+    pub node: NodeId,
+    pub message: String,
+}
+
+/// The format an item was written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    JsonLd,
+    Microdata,
+    Rdfa,
+}
+
+/// A thing described by a page, such as a product, with its types and
+/// properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub format: Format,
+    /// The types, such as `https://schema.org/Product`. Types are left as
+    /// written when there is no vocabulary to expand them with.
+    pub types: Vec<String>,
+    /// The global identifier: `itemid`, `@id` or RDFa's `resource`.
+    pub id: Option<String>,
+    /// The properties in document order. A property may be given more than
+    /// once.
+    pub properties: Vec<(String, PropertyValue)>,
+}
+
+impl Item {
+    /// Whether the item has `type_`, given either as a URL or as the last
+    /// segment of one: `Product` matches `https://schema.org/Product`.
+    pub fn has_type(&self, type_: &str) -> bool {
+        self.types
+            .iter()
+            .any(|own| own == type_ || local_name(own) == type_)
+    }
+
+    /// The first value of the property `name`.
+    pub fn property(&self, name: &str) -> Option<&PropertyValue> {
+        self.properties
+            .iter()
+            .find(|(own, _)| own == name)
+            .map(|(_, value)| value)
+    }
+
+    /// All values of the property `name`, in document order.
+    pub fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a PropertyValue> + 'a {
+        self.properties
+            .iter()
+            .filter(move |(own, _)| own == name)
+            .map(|(_, value)| value)
+    }
+
+    /// The first value of the property `name` if it is text.
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.property(name)?.as_text()
+    }
+
+    /// The item in the shape of the HTML standard's JSON for Microdata:
+    /// `type`, `id` and `properties`, which maps each name to its values.
+    pub fn to_json(&self) -> Value {
+        let mut properties = Map::new();
+        for (name, value) in &self.properties {
+            let values = properties
+                .entry(name.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(values) = values {
+                values.push(value.to_json());
+            }
+        }
+        let mut object = Map::new();
+        object.insert(
+            "type".to_string(),
+            Value::Array(self.types.iter().cloned().map(Value::String).collect()),
+        );
+        if let Some(id) = &self.id {
+            object.insert("id".to_string(), Value::String(id.clone()));
+        }
+        object.insert("properties".to_string(), Value::Object(properties));
+        Value::Object(object)
+    }
+}
+
+/// The value of a property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    /// A string, with URLs resolved against the document's base URL.
+    Text(String),
+    /// A nested item.
+    Item(Item),
+    /// A JSON-LD number, boolean or `null`.
+    Json(Value),
+}
+
+impl PropertyValue {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            PropertyValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_item(&self) -> Option<&Item> {
+        match self {
+            PropertyValue::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            PropertyValue::Text(text) => Value::String(text.clone()),
+            PropertyValue::Item(item) => item.to_json(),
+            PropertyValue::Json(value) => value.clone(),
+        }
+    }
+}
+
+/// Extracts the structured data of a document loaded from `url`.
+pub fn extract_structured_data(document: &Document, url: &Url) -> StructuredData {
+    let base = base_url(document, url);
+    let mut data = StructuredData::default();
+    for element in html_elements(document) {
+        let is_json_ld = element.tag_name() == "script"
+            && element
+                .get_attribute("type")
+                .is_some_and(|type_| type_.trim().eq_ignore_ascii_case("application/ld+json"));
+        if !is_json_ld {
+            continue;
+        }
+        match serde_json::from_str::<Value>(&element.text_content()) {
+            Ok(value) => {
+                json_ld_items(&value, None, &mut data.items);
+                data.json_ld.push(value);
+            }
+            Err(error) => data.json_ld_errors.push(JsonLdError {
+                node: element.as_node().id(),
+                message: error.to_string(),
+            }),
+        }
+    }
+    data.items.extend(microdata::items(document, &base));
+    data.items.extend(rdfa::items(document, &base));
+    data
+}
+
+/// Adds the items of a JSON-LD value to `items`: the value itself if it is
+/// an object, the objects of an array or of a `@graph`.
+fn json_ld_items(value: &Value, vocabulary: Option<&str>, items: &mut Vec<Item>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                json_ld_items(value, vocabulary, items);
+            }
+        }
+        Value::Object(object) => {
+            let vocabulary = json_ld_vocabulary(object).or(vocabulary);
+            match object.get("@graph") {
+                Some(graph) => json_ld_items(graph, vocabulary, items),
+                None => items.push(json_ld_item(object, vocabulary)),
+            }
+        }
+        _ => {}
+    }
+}
+
+fn json_ld_item(object: &Map<String, Value>, vocabulary: Option<&str>) -> Item {
+    let vocabulary = json_ld_vocabulary(object).or(vocabulary);
+    let types = match object.get("@type") {
+        Some(Value::String(type_)) => vec![expand(type_, vocabulary)],
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|type_| expand(type_, vocabulary))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut properties = Vec::new();
+    for (name, value) in object {
+        if !name.starts_with('@') {
+            json_ld_values(name, value, vocabulary, &mut properties);
+        }
+    }
+    Item {
+        format: Format::JsonLd,
+        types,
+        id: object
+            .get("@id")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        properties,
+    }
+}
+
+fn json_ld_values(
+    name: &str,
+    value: &Value,
+    vocabulary: Option<&str>,
+    properties: &mut Vec<(String, PropertyValue)>,
+) {
+    let value = match value {
+        Value::Array(values) => {
+            for value in values {
+                json_ld_values(name, value, vocabulary, properties);
+            }
+            return;
+        }
+        Value::String(text) => PropertyValue::Text(text.clone()),
+        // A value object such as `{"@value": "9.99", "@type": "xsd:decimal"}`.
+        Value::Object(object) if object.contains_key("@value") => match &object["@value"] {
+            Value::String(text) => PropertyValue::Text(text.clone()),
+            value => PropertyValue::Json(value.clone()),
+        },
+        Value::Object(object) => PropertyValue::Item(json_ld_item(object, vocabulary)),
+        value => PropertyValue::Json(value.clone()),
+    };
+    properties.push((name.to_string(), value));
+}
+
+/// The vocabulary types are relative to: a `@context` that is a URL, or
+/// the `@vocab` of one that is an object.
+fn json_ld_vocabulary(object: &Map<String, Value>) -> Option<&str> {
+    match object.get("@context")? {
+        Value::Array(contexts) => contexts.iter().rev().find_map(context_vocabulary),
+        context => context_vocabulary(context),
+    }
+}
+
+fn context_vocabulary(context: &Value) -> Option<&str> {
+    match context {
+        Value::String(url) => Some(url),
+        Value::Object(context) => context.get("@vocab")?.as_str(),
+        _ => None,
+    }
+}
+
+/// Expands `term` to a URL in `vocabulary`, unless it already is one.
+pub(crate) fn expand(term: &str, vocabulary: Option<&str>) -> String {
+    match vocabulary {
+        Some(vocabulary) if !term.contains(':') => {
+            if vocabulary.ends_with(['/', '#']) {
+                format!("{vocabulary}{term}")
+            } else {
+                format!("{vocabulary}/{term}")
+            }
+        }
+        _ => term.to_string(),
+    }
+}
+
+/// The part of a type URL after its last `/` or `#`.
+fn local_name(type_: &str) -> &str {
+    type_.rsplit(['/', '#']).next().unwrap_or(type_)
+}
+
+/// The value of a URL attribute, resolved against `base`. Attributes that
+/// aren't valid URLs become empty strings, as in Microdata.
+pub(crate) fn resolve(base: &Url, value: Option<&str>) -> String {
+    value
+        .and_then(|value| base.join(value.trim()).ok())
+        .map_or_else(String::new, String::from)
+}
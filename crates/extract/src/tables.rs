@@ -0,0 +1,578 @@
+//! Tables as grids of cells, following the HTML standard's table model:
+//! cells spanning several rows or columns cover a slot in each, and header
+//! cells are found from `th` and `scope`.
+//!
+//! ```
+//! use extract::extract_tables;
+//!
+//! let document = parser::parse_html(
+//!     "<table><tr><th>Name<th>Age<tr><td>Ann<td>31<tr><td>Bob<td>27</table>",
+//! );
+//! let table = &extract_tables(&document)[0];
+//! assert_eq!(table.column_names(), vec!["Name", "Age"]);
+//! assert_eq!(table.to_csv(), "Name,Age\r\nAnn,31\r\nBob,27\r\n");
+//! ```
+
+use ast::{Document, Element, Namespace, NodeId};
+use serde_json::{Map, Value};
+
+use crate::html_elements;
+
+/// The largest `colspan` browsers honor.
+const MAX_COLSPAN: usize = 1000;
+/// The largest `rowspan` browsers honor.
+const MAX_ROWSPAN: usize = 65534;
+
+/// A table laid out as a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// The text of the table's `caption`.
+    pub caption: Option<String>,
+    /// The cells, row by row in the order of [`Table::rows`].
+    pub cells: Vec<Cell>,
+    /// The rows, with the first `thead` first and the first `tfoot` last as
+    /// browsers show them. Every row has a slot for each column.
+    pub rows: Vec<Row>,
+    /// The number of columns.
+    pub width: usize,
+}
+
+/// A row of a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub section: Section,
+    /// The row group the row is in, counting `thead`s, `tbody`s, `tfoot`s
+    /// and runs of rows directly in the table from the top.
+    pub group: usize,
+    /// For each column, the index in [`Table::cells`] of the cell covering
+    /// the slot, or `None` if no cell does.
+    pub slots: Vec<Option<usize>>,
+}
+
+/// The part of a table a row is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    /// `thead`.
+    Head,
+    /// `tbody`, or rows directly inside the `table`.
+    Body,
+    /// `tfoot`.
+    Foot,
+}
+
+/// A `td` or `th` cell of a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    /// The `td` or `th` element.
+    pub node: NodeId,
+    /// The text of the cell as a browser would render it, trimmed.
+    pub text: String,
+    /// Whether the cell is a `th`.
+    pub header: bool,
+    /// The `scope` of a `th`. Always [`Scope::Auto`] for `td`s.
+    pub scope: Scope,
+    /// The row of the cell's top left slot.
+    pub row: usize,
+    /// The column of the cell's top left slot.
+    pub column: usize,
+    /// The number of rows the cell covers, after clipping `rowspan` to its
+    /// row group.
+    pub row_span: usize,
+    /// The number of columns the cell covers.
+    pub column_span: usize,
+    /// The header cells that apply to this cell, as indexes in
+    /// [`Table::cells`].
+    pub headers: Vec<usize>,
+}
+
+/// The `scope` attribute of a header cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Row,
+    Column,
+    RowGroup,
+    ColumnGroup,
+    /// No `scope`, or an unknown one: what the header applies to depends on
+    /// where it is.
+    Auto,
+}
+
+impl Table {
+    /// The cell covering the slot in `row` and `column`.
+    pub fn cell(&self, row: usize, column: usize) -> Option<&Cell> {
+        let index = (*self.rows.get(row)?.slots.get(column)?)?;
+        Some(&self.cells[index])
+    }
+
+    /// The texts of the rows, with the text of a spanning cell repeated in
+    /// each slot it covers and empty strings for slots no cell covers.
+    pub fn grid(&self) -> Vec<Vec<&str>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.slots
+                    .iter()
+                    .map(|slot| slot.map_or("", |index| self.cells[index].text.as_str()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Whether the cell at `index` is a column header: a `th` with
+    /// `scope=col`, or with no scope in rows without data cells.
+    pub fn is_column_header(&self, index: usize) -> bool {
+        let cell = &self.cells[index];
+        match (cell.header, cell.scope) {
+            (true, Scope::Column) => true,
+            (true, Scope::Auto) => self.rows[cell.row..cell.row + cell.row_span]
+                .iter()
+                .all(|row| self.only_headers(&row.slots)),
+            _ => false,
+        }
+    }
+
+    /// Whether the cell at `index` is a row header: a `th` with
+    /// `scope=row`, or with no scope in columns without data cells that
+    /// isn't a column header.
+    pub fn is_row_header(&self, index: usize) -> bool {
+        let cell = &self.cells[index];
+        match (cell.header, cell.scope) {
+            (true, Scope::Row) => true,
+            (true, Scope::Auto) => {
+                !self.is_column_header(index)
+                    && (cell.column..cell.column + cell.column_span).all(|column| {
+                        let slots: Vec<_> = self.rows.iter().map(|row| row.slots[column]).collect();
+                        self.only_headers(&slots)
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    fn only_headers(&self, slots: &[Option<usize>]) -> bool {
+        slots
+            .iter()
+            .flatten()
+            .all(|&index| self.cells[index].header)
+    }
+
+    /// The header cells that apply to the cell at `index`, with the HTML
+    /// standard's algorithm: the row headers to its left, the column headers
+    /// above it and the `scope=rowgroup` headers of its row group. `kinds`
+    /// tells for each cell whether it is a column header and a row header.
+    fn find_headers(&self, index: usize, kinds: &[(bool, bool)]) -> Vec<usize> {
+        let cell = &self.cells[index];
+        let mut headers = Vec::new();
+        for row in cell.row..cell.row + cell.row_span {
+            self.scan(
+                index,
+                cell.column,
+                row,
+                Direction::Left,
+                kinds,
+                &mut headers,
+            );
+        }
+        for column in cell.column..cell.column + cell.column_span {
+            self.scan(index, column, cell.row, Direction::Up, kinds, &mut headers);
+        }
+        let group = self.rows[cell.row].group;
+        for (header, other) in self.cells.iter().enumerate() {
+            if other.header
+                && other.scope == Scope::RowGroup
+                && self.rows[other.row].group == group
+                && other.row <= cell.row
+                && other.column <= cell.column
+                && header != index
+                && !headers.contains(&header)
+            {
+                headers.push(header);
+            }
+        }
+        headers
+    }
+
+    /// Scans from the slot at `column` and `row` towards the table's edge
+    /// for headers of the cell at `principal`.
+    fn scan(
+        &self,
+        principal: usize,
+        mut column: usize,
+        mut row: usize,
+        direction: Direction,
+        kinds: &[(bool, bool)],
+        headers: &mut Vec<usize>,
+    ) {
+        let mut opaque: Vec<usize> = Vec::new();
+        let mut in_block = self.cells[principal].header;
+        let mut block: Vec<usize> = match in_block {
+            true => vec![principal],
+            false => Vec::new(),
+        };
+        loop {
+            match direction {
+                Direction::Left if column > 0 => column -= 1,
+                Direction::Up if row > 0 => row -= 1,
+                _ => return,
+            }
+            let Some(current) = self.rows[row].slots[column] else {
+                continue;
+            };
+            let cell = &self.cells[current];
+            if cell.header {
+                in_block = true;
+                if !block.contains(&current) {
+                    block.push(current);
+                }
+                let blocked = match direction {
+                    Direction::Up => {
+                        opaque.iter().any(|&header| {
+                            let header = &self.cells[header];
+                            header.column == cell.column && header.column_span == cell.column_span
+                        }) || !kinds[current].0
+                    }
+                    Direction::Left => {
+                        opaque.iter().any(|&header| {
+                            let header = &self.cells[header];
+                            header.row == cell.row && header.row_span == cell.row_span
+                        }) || !kinds[current].1
+                    }
+                };
+                if !blocked && !headers.contains(&current) {
+                    headers.push(current);
+                }
+            } else if in_block {
+                in_block = false;
+                opaque.append(&mut block);
+            }
+        }
+    }
+
+    /// The number of rows at the top that are column headers: those of the
+    /// `thead`, or without one, the leading rows whose cells are all column
+    /// headers.
+    pub fn header_rows(&self) -> usize {
+        let head = self
+            .rows
+            .iter()
+            .take_while(|row| row.section == Section::Head)
+            .count();
+        if head > 0 {
+            return head;
+        }
+        self.rows
+            .iter()
+            .take_while(|row| {
+                row.slots.iter().any(Option::is_some)
+                    && row
+                        .slots
+                        .iter()
+                        .flatten()
+                        .all(|&index| self.is_column_header(index))
+            })
+            .count()
+    }
+
+    /// A name for each column, from the texts of the header rows joined with
+    /// ` / `. Columns without a header are called `column 1`, `column 2` and
+    /// so on, and names that repeat get a number: `Price`, `Price 2`.
+    pub fn column_names(&self) -> Vec<String> {
+        let header_rows = &self.rows[..self.header_rows()];
+        let mut names: Vec<String> = Vec::with_capacity(self.width);
+        for column in 0..self.width {
+            let mut texts: Vec<&str> = Vec::new();
+            let mut previous = None;
+            for slot in header_rows.iter().map(|row| row.slots[column]) {
+                if let Some(index) = slot.filter(|&index| Some(index) != previous) {
+                    let text = self.cells[index].text.as_str();
+                    if !text.is_empty() {
+                        texts.push(text);
+                    }
+                }
+                previous = slot;
+            }
+            let mut name = match texts.is_empty() {
+                true => format!("column {}", column + 1),
+                false => texts.join(" / "),
+            };
+            let base = name.clone();
+            let mut count = 1;
+            while names.contains(&name) {
+                count += 1;
+                name = format!("{base} {count}");
+            }
+            names.push(name);
+        }
+        names
+    }
+
+    /// The table as CSV, as in RFC 4180: header rows first, a line for each
+    /// row ending with CRLF, and fields quoted when they contain a comma,
+    /// quote or line break. Spanning cells are repeated in each slot they
+    /// cover.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for row in self.grid() {
+            for (column, text) in row.iter().enumerate() {
+                if column > 0 {
+                    csv.push(',');
+                }
+                if text.contains([',', '"', '\r', '\n']) {
+                    csv.push('"');
+                    csv.push_str(&text.replace('"', "\"\""));
+                    csv.push('"');
+                } else {
+                    csv.push_str(text);
+                }
+            }
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+
+    /// The rows below the header rows as a JSON array of objects, one per
+    /// row, mapping the [column names](Table::column_names) to the texts of
+    /// the cells. Slots no cell covers are `null`.
+    pub fn to_json(&self) -> Value {
+        let names = self.column_names();
+        let rows = self.rows[self.header_rows()..]
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = names
+                    .iter()
+                    .zip(&row.slots)
+                    .map(|(name, slot)| {
+                        let value = slot.map_or(Value::Null, |index| {
+                            Value::String(self.cells[index].text.clone())
+                        });
+                        (name.clone(), value)
+                    })
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        Value::Array(rows)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Left,
+    Up,
+}
+
+/// The tables of `document`, in document order. Nested tables are tables of
+/// their own, and their text is part of the cell they are in.
+pub fn extract_tables(document: &Document) -> Vec<Table> {
+    html_elements(document).filter_map(extract_table).collect()
+}
+
+/// Lays out a `table` element as a grid, or gives `None` for any other
+/// element.
+pub fn extract_table(table: Element) -> Option<Table> {
+    if table.tag_name() != "table" || table.namespace() != Namespace::Html {
+        return None;
+    }
+    let children: Vec<Element> = table
+        .children()
+        .filter_map(|node| node.as_element())
+        .collect();
+    let caption = children
+        .iter()
+        .find(|child| child.tag_name() == "caption")
+        .map(|caption| caption.inner_text().trim().to_string());
+
+    // Row groups in the order browsers show them; runs of rows directly in
+    // the table form groups of their own.
+    let mut groups: Vec<(Section, Vec<Element>)> = Vec::new();
+    let mut loose = false;
+    for child in &children {
+        let section = match child.tag_name() {
+            "thead" => Section::Head,
+            "tbody" => Section::Body,
+            "tfoot" => Section::Foot,
+            "tr" => {
+                match groups.last_mut() {
+                    Some((_, rows)) if loose => rows.push(*child),
+                    _ => groups.push((Section::Body, vec![*child])),
+                }
+                loose = true;
+                continue;
+            }
+            _ => continue,
+        };
+        loose = false;
+        let rows = child
+            .children()
+            .filter_map(|node| node.as_element())
+            .filter(|row| row.tag_name() == "tr")
+            .collect();
+        groups.push((section, rows));
+    }
+    if let Some(head) = groups
+        .iter()
+        .position(|(section, _)| *section == Section::Head)
+    {
+        let group = groups.remove(head);
+        groups.insert(0, group);
+    }
+    if let Some(foot) = groups
+        .iter()
+        .position(|(section, _)| *section == Section::Foot)
+    {
+        let group = groups.remove(foot);
+        groups.push(group);
+    }
+
+    let mut layout = Layout::default();
+    for (group, (section, rows)) in groups.iter().enumerate() {
+        let end = layout.next_row + rows.len();
+        for row in rows {
+            layout.row(*row, *section, group, end);
+        }
+    }
+    let width = layout.width;
+    for row in &mut layout.rows {
+        row.slots.resize(width, None);
+    }
+    let mut table = Table {
+        caption,
+        cells: layout.cells,
+        rows: layout.rows,
+        width,
+    };
+
+    // Cells name their headers by `id` with `headers`, and other cells get
+    // theirs from where they are.
+    let kinds: Vec<(bool, bool)> = (0..table.cells.len())
+        .map(|index| (table.is_column_header(index), table.is_row_header(index)))
+        .collect();
+    for index in 0..table.cells.len() {
+        let element = layout.elements[index];
+        table.cells[index].headers = match element.get_attribute("headers") {
+            Some(ids) => ids
+                .split_ascii_whitespace()
+                .filter_map(|id| {
+                    layout
+                        .elements
+                        .iter()
+                        .zip(&table.cells)
+                        .position(|(other, cell)| cell.header && other.id() == Some(id))
+                })
+                .filter(|&header| header != index)
+                .collect(),
+            None => table.find_headers(index, &kinds),
+        };
+    }
+    Some(table)
+}
+
+#[derive(Default)]
+struct Layout<'a> {
+    cells: Vec<Cell>,
+    /// The element of each cell.
+    elements: Vec<Element<'a>>,
+    rows: Vec<Row>,
+    width: usize,
+    /// The row the next `tr` starts. Rows below it may exist already, made
+    /// by cells spanning several rows.
+    next_row: usize,
+}
+
+impl<'a> Layout<'a> {
+    /// Lays out a `tr` of the row group `group`, which ends before row
+    /// `end`.
+    fn row(&mut self, tr: Element<'a>, section: Section, group: usize, end: usize) {
+        let y = self.next_row;
+        self.next_row += 1;
+        self.add_rows(y + 1, section, group);
+        let mut x = 0;
+        for cell in tr.children().filter_map(|node| node.as_element()) {
+            let header = match cell.tag_name() {
+                "td" => false,
+                "th" => true,
+                _ => continue,
+            };
+            while self.rows[y].slots.get(x).is_some_and(Option::is_some) {
+                x += 1;
+            }
+            let column_span = span(cell, "colspan")
+                .filter(|&span| span > 0)
+                .unwrap_or(1)
+                .min(MAX_COLSPAN);
+            // A `rowspan` of zero reaches the end of the row group, and none
+            // reaches past it.
+            let row_span = match span(cell, "rowspan").unwrap_or(1) {
+                0 => end - y,
+                span => span.min(MAX_ROWSPAN).min(end - y),
+            };
+            self.add_rows(y + row_span, section, group);
+            let index = self.cells.len();
+            for row in &mut self.rows[y..y + row_span] {
+                if row.slots.len() < x + column_span {
+                    row.slots.resize(x + column_span, None);
+                }
+                // Cells overlapping others only cover the free slots.
+                for slot in &mut row.slots[x..x + column_span] {
+                    slot.get_or_insert(index);
+                }
+            }
+            self.width = self.width.max(x + column_span);
+            self.cells.push(Cell {
+                node: cell.as_node().id(),
+                text: cell.inner_text().trim().to_string(),
+                header,
+                scope: match header {
+                    true => scope(cell),
+                    false => Scope::Auto,
+                },
+                row: y,
+                column: x,
+                row_span,
+                column_span,
+                headers: Vec::new(),
+            });
+            self.elements.push(cell);
+            x += column_span;
+        }
+    }
+
+    fn add_rows(&mut self, count: usize, section: Section, group: usize) {
+        while self.rows.len() < count {
+            self.rows.push(Row {
+                section,
+                group,
+                slots: Vec::new(),
+            });
+        }
+    }
+}
+
+/// Parses a `colspan` or `rowspan` like browsers: leading digits after
+/// optional whitespace and `+`, ignoring what follows.
+fn span(cell: Element, name: &str) -> Option<usize> {
+    let value = cell
+        .get_attribute(name)?
+        .trim_start_matches(|ch: char| ch.is_ascii_whitespace());
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let digits = value.len()
+        - value
+            .trim_start_matches(|ch: char| ch.is_ascii_digit())
+            .len();
+    if digits == 0 {
+        return None;
+    }
+    // Spans too large for `usize` are clamped anyway.
+    Some(value[..digits].parse().unwrap_or(usize::MAX))
+}
+
+fn scope(cell: Element) -> Scope {
+    let scope = cell.get_attribute("scope").unwrap_or_default().trim();
+    match scope.to_ascii_lowercase().as_str() {
+        "row" => Scope::Row,
+        "col" => Scope::Column,
+        "rowgroup" => Scope::RowGroup,
+        "colgroup" => Scope::ColumnGroup,
+        _ => Scope::Auto,
+    }
+}
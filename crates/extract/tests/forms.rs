@@ -0,0 +1,276 @@
+use extract::{
+    extract_forms, urlencode, ButtonType, ControlKind, Direction, Enctype, Entry, EntryValue, Form,
+    InputType, Method, Url,
+};
+use parser::parse_html;
+
+fn forms(html: &str) -> Vec<Form> {
+    let url = Url::parse("https://example.com/account/edit?tab=2").unwrap();
+    extract_forms(&parse_html(html), &url)
+}
+
+fn form(html: &str) -> Form {
+    forms(html).remove(0)
+}
+
+/// The starting value of each control.
+fn values(form: &Form) -> Vec<&str> {
+    form.controls
+        .iter()
+        .map(|control| control.value.as_str())
+        .collect()
+}
+
+#[test]
+fn reads_form_attributes() {
+    let forms = forms(
+        "<base href=https://cdn.example/>\
+         <form id=login name=login action=\" /session \" method=POST enctype=multipart/form-data></form>\
+         <form method=dialog enctype=text/PLAIN></form>\
+         <form action=\"\" method=put enctype=json></form>\
+         <form action=\"http://[bad\"></form>",
+    );
+    assert_eq!(forms.len(), 4);
+    assert_eq!(forms[0].id.as_deref(), Some("login"));
+    assert_eq!(forms[0].name.as_deref(), Some("login"));
+    assert_eq!(
+        forms[0].action.as_ref().map(Url::as_str),
+        Some("https://cdn.example/session")
+    );
+    assert_eq!(
+        (forms[0].method, forms[0].enctype),
+        (Method::Post, Enctype::Multipart)
+    );
+    assert_eq!(
+        (forms[1].method, forms[1].enctype),
+        (Method::Dialog, Enctype::TextPlain)
+    );
+    // Without an action, forms submit to the document, whatever the base.
+    assert_eq!(
+        forms[1].action.as_ref().map(Url::as_str),
+        Some("https://example.com/account/edit?tab=2")
+    );
+    assert_eq!(forms[2].action, forms[1].action);
+    assert_eq!(
+        (forms[2].method, forms[2].enctype),
+        (Method::Get, Enctype::UrlEncoded)
+    );
+    assert_eq!(forms[3].action, None);
+    assert_eq!(Enctype::Multipart.as_str(), "multipart/form-data");
+}
+
+#[test]
+fn associates_controls_with_their_owner() {
+    let forms = forms(
+        "<input name=before form=b>\
+         <form id=a><input name=a1><input name=moved form=b>\
+         <input name=lost form=missing><datalist><option><input name=hint></datalist></form>\
+         <form id=b><p><textarea name=b1></textarea></form>\
+         <select name=after form=b></select><button form=a name=go></button>\
+         <input name=orphan><div id=c></div><input name=not-a-form form=c>",
+    );
+    let names = |form: &Form| -> Vec<String> {
+        form.controls
+            .iter()
+            .map(|control| control.name.clone().unwrap())
+            .collect()
+    };
+    assert_eq!(names(&forms[0]), vec!["a1", "go"]);
+    assert_eq!(names(&forms[1]), vec!["before", "moved", "b1", "after"]);
+    let kinds: Vec<ControlKind> = forms[1]
+        .controls
+        .iter()
+        .map(|control| control.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ControlKind::Input(InputType::Text),
+            ControlKind::Input(InputType::Text),
+            ControlKind::Textarea,
+            ControlKind::Select,
+        ]
+    );
+    assert_eq!(
+        forms[0].controls[1].kind,
+        ControlKind::Button(ButtonType::Submit)
+    );
+}
+
+#[test]
+fn sanitizes_input_values() {
+    let form = form(
+        "<form>\
+         <input value=\"a\nb\">\
+         <input type=EMAIL value=\" x@example.com \">\
+         <input type=email multiple value=\" a@example.com , b@example.com\">\
+         <input type=url value=\" https://example.com/ \">\
+         <input type=number value=1.5e3><input type=number value=1.>\
+         <input type=range value=150><input type=range min=10 max=20 value=x>\
+         <input type=color value=#FFaa00><input type=color value=red>\
+         <input type=date value=2024-02-29><input type=date value=2023-02-29>\
+         <input type=month value=2024-12><input type=month value=2024-1>\
+         <input type=week value=2020-W53><input type=week value=2021-W53>\
+         <input type=time value=09:30:15.250><input type=time value=24:00>\
+         <input type=datetime-local value=\"2024-05-01 08:00\">\
+         <input type=checkbox><input type=radio value=\"\"><input type=file value=C:\\x>\
+         </form>",
+    );
+    assert_eq!(
+        values(&form),
+        vec![
+            "ab",
+            "x@example.com",
+            "a@example.com,b@example.com",
+            "https://example.com/",
+            "1.5e3",
+            "",
+            "100",
+            "15",
+            "#ffaa00",
+            "#000000",
+            "2024-02-29",
+            "",
+            "2024-12",
+            "",
+            "2020-W53",
+            "",
+            "09:30:15.250",
+            "",
+            "2024-05-01T08:00",
+            "on",
+            "",
+            "",
+        ]
+    );
+    assert_eq!(form.controls[8].kind, ControlKind::Input(InputType::Color));
+    assert_eq!(InputType::DatetimeLocal.as_str(), "datetime-local");
+    assert_eq!(InputType::from_attribute(Some("fancy")), InputType::Text);
+}
+
+#[test]
+fn reads_select_options() {
+    let form = form(
+        "<form>\
+         <select name=one><option disabled>Pick\
+         <optgroup label=Fruit disabled><option value=apple>Apple</optgroup>\
+         <option> Pear  tree </option><option value=fig>Fig</select>\
+         <select name=last><option selected>A<option selected>B</select>\
+         <select name=many multiple><option selected>A<option>B<option selected>C</select>\
+         <select name=list size=3><option>A<option>B</select>\
+         </form>",
+    );
+    let select = &form.controls[0];
+    let options: Vec<(&str, &str, bool, bool)> = select
+        .options
+        .iter()
+        .map(|option| {
+            (
+                option.value.as_str(),
+                option.text.as_str(),
+                option.selected,
+                option.disabled,
+            )
+        })
+        .collect();
+    assert_eq!(
+        options,
+        vec![
+            ("Pick", "Pick", false, true),
+            ("apple", "Apple", false, true),
+            ("Pear tree", "Pear tree", true, false),
+            ("fig", "Fig", false, false),
+        ]
+    );
+    let selected = |index: usize| -> Vec<bool> {
+        form.controls[index]
+            .options
+            .iter()
+            .map(|option| option.selected)
+            .collect()
+    };
+    assert_eq!(selected(1), vec![false, true]);
+    assert_eq!(selected(2), vec![true, false, true]);
+    assert!(form.controls[2].multiple);
+    assert_eq!(selected(3), vec![false, false]);
+    assert_eq!(
+        urlencode(&form.form_data(None)),
+        "one=Pear+tree&last=B&many=A&many=C"
+    );
+}
+
+#[test]
+fn finds_disabled_controls() {
+    let form = form(
+        "<form><fieldset disabled>\
+         <legend><input name=in-legend></legend><legend><input name=second-legend></legend>\
+         <input name=inside><fieldset><input name=nested></fieldset></fieldset>\
+         <input name=own disabled><input name=enabled></form>",
+    );
+    let disabled: Vec<(&str, bool)> = form
+        .controls
+        .iter()
+        .map(|control| (control.name.as_deref().unwrap(), control.disabled))
+        .collect();
+    assert_eq!(
+        disabled,
+        vec![
+            ("in-legend", false),
+            ("second-legend", true),
+            ("inside", true),
+            ("nested", true),
+            ("own", true),
+            ("enabled", false),
+        ]
+    );
+    assert_eq!(urlencode(&form.form_data(None)), "in-legend=&enabled=");
+}
+
+#[test]
+fn constructs_the_entry_list() {
+    let form = form(
+        "<form dir=rtl>\
+         <input type=hidden name=_CHARSET_ value=latin1>\
+         <input name=q value=\"a b&c\" dirname=q.dir>\
+         <input value=unnamed>\
+         <input type=checkbox name=tos><input type=checkbox name=news value=weekly checked>\
+         <input type=radio name=plan value=free><input type=radio name=plan value=pro checked>\
+         <input type=file name=avatar>\
+         <textarea name=bio dir=ltr dirname=bio.dir>line one\nline two</textarea>\
+         <input type=reset name=reset><input type=button name=button>\
+         <input type=submit name=save value=Save><input type=image name=map>\
+         <button name=cancel value=1 type=button></button>\
+         </form>",
+    );
+    assert_eq!(form.controls[1].direction, Direction::Rtl);
+    assert_eq!(form.controls[1].dirname.as_deref(), Some("q.dir"));
+    let entries = form.form_data(None);
+    assert_eq!(
+        entries[5],
+        Entry {
+            name: "avatar".to_string(),
+            value: EntryValue::File {
+                filename: String::new(),
+                content_type: "application/octet-stream".to_string(),
+            },
+        }
+    );
+    assert_eq!(
+        urlencode(&entries),
+        "_CHARSET_=UTF-8&q=a+b%26c&q.dir=rtl&news=weekly&plan=pro&avatar=\
+         &bio=line+one%0D%0Aline+two&bio.dir=ltr"
+    );
+
+    // Only the button submitting the form is included, and only if it is
+    // a submit button.
+    let submitted = |submitter| {
+        let entries = form.form_data(Some(submitter));
+        let extra: Vec<Entry> = entries[8..].to_vec();
+        urlencode(&extra)
+    };
+    assert_eq!(submitted(11), "save=Save");
+    assert_eq!(submitted(12), "map.x=0&map.y=0");
+    assert_eq!(submitted(13), "");
+    assert_eq!(submitted(9), "");
+    assert_eq!(submitted(99), "");
+}
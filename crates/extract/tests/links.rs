@@ -0,0 +1,215 @@
+use extract::{extract_links, parse_srcset, Descriptor, ImageCandidate, Link, LinkKind, Url};
+use parser::parse_html;
+
+fn links(html: &str) -> Vec<Link> {
+    let url = Url::parse("https://example.com/blog/post?page=2").unwrap();
+    extract_links(&parse_html(html), &url)
+}
+
+/// The kind and resolved URL of each link.
+fn urls(html: &str) -> Vec<(LinkKind, String)> {
+    links(html)
+        .into_iter()
+        .map(|link| (link.kind, link.url.to_string()))
+        .collect()
+}
+
+#[test]
+fn finds_references_of_each_kind() {
+    assert_eq!(
+        urls(
+            "<link rel=stylesheet href=/main.css>\
+             <a href=../about>About</a><area href=#map>\
+             <img src=a.png><script src=app.js></script>\
+             <iframe src=//video.example/embed></iframe>\
+             <form action=search></form>\
+             <meta http-equiv=Refresh content=\"5; url=next\">"
+        ),
+        vec![
+            (LinkKind::Link, "https://example.com/main.css".to_string()),
+            (LinkKind::Hyperlink, "https://example.com/about".to_string()),
+            (
+                LinkKind::Hyperlink,
+                "https://example.com/blog/post?page=2#map".to_string()
+            ),
+            (
+                LinkKind::Image,
+                "https://example.com/blog/a.png".to_string()
+            ),
+            (
+                LinkKind::Script,
+                "https://example.com/blog/app.js".to_string()
+            ),
+            (LinkKind::Frame, "https://video.example/embed".to_string()),
+            (
+                LinkKind::Form,
+                "https://example.com/blog/search".to_string()
+            ),
+            (
+                LinkKind::Refresh,
+                "https://example.com/blog/next".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn keeps_context() {
+    let document = parse_html("<p><a href=\" /x \" rel=\"NoFollow external\">X</a></p>");
+    let url = Url::parse("https://example.com/").unwrap();
+    let links = extract_links(&document, &url);
+    assert_eq!(links.len(), 1);
+    let link = &links[0];
+    assert_eq!(link.attribute, "href");
+    assert_eq!(link.reference, " /x ");
+    assert_eq!(link.rel, vec!["nofollow", "external"]);
+    assert_eq!(link.descriptor, None);
+    let element = document.node(link.node).as_element().unwrap();
+    assert_eq!(element.tag_name(), "a");
+}
+
+#[test]
+fn resolves_against_the_base_element() {
+    assert_eq!(
+        urls("<base href=https://cdn.example/assets/><img src=logo.png><a href=\"\">Self</a>"),
+        vec![
+            (
+                LinkKind::Image,
+                "https://cdn.example/assets/logo.png".to_string()
+            ),
+            (
+                LinkKind::Hyperlink,
+                "https://cdn.example/assets/".to_string()
+            ),
+        ]
+    );
+    // Empty actions submit to the document itself, whatever the base.
+    assert_eq!(
+        urls("<base href=https://cdn.example/><form action=\"\"></form><form></form>"),
+        vec![(
+            LinkKind::Form,
+            "https://example.com/blog/post?page=2".to_string()
+        )]
+    );
+}
+
+#[test]
+fn leaves_out_what_loads_nothing() {
+    assert!(urls(
+        "<img src=\"\"><script src=\" \"></script><iframe></iframe><a name=top>\
+         <a href=\"http://[bad\">Bad</a><source srcset=loose.png>\
+         <meta http-equiv=refresh content=5><meta name=refresh content=\"0; url=x\">"
+    )
+    .is_empty());
+}
+
+#[test]
+fn reads_image_candidates() {
+    let links = links(
+        "<picture><source srcset=\"wide.webp 1200w, narrow.webp 600w\" media=\"(min-width: 600px)\">\
+         <img src=fallback.png srcset=\"a.png, b.png 2x\"></picture>",
+    );
+    let candidates: Vec<(&str, &str, Option<Descriptor>)> = links
+        .iter()
+        .map(|link| (link.attribute, link.url.path(), link.descriptor))
+        .collect();
+    assert_eq!(
+        candidates,
+        vec![
+            ("srcset", "/blog/wide.webp", Some(Descriptor::Width(1200))),
+            ("srcset", "/blog/narrow.webp", Some(Descriptor::Width(600))),
+            ("src", "/blog/fallback.png", None),
+            ("srcset", "/blog/a.png", None),
+            ("srcset", "/blog/b.png", Some(Descriptor::Density(2.0))),
+        ]
+    );
+    assert!(links.iter().all(|link| link.kind == LinkKind::Image));
+}
+
+#[test]
+fn parses_srcset_like_browsers() {
+    let candidate = |url: &str, descriptor| ImageCandidate {
+        url: url.to_string(),
+        descriptor,
+    };
+    assert_eq!(
+        parse_srcset(" a.png 1.5x ,b, c.png,, d.png 100w 50h, e.png\t3e0x, f.png .5x"),
+        vec![
+            candidate("a.png", Some(Descriptor::Density(1.5))),
+            candidate("b", None),
+            candidate("c.png", None),
+            candidate("d.png", Some(Descriptor::Width(100))),
+            candidate("e.png", Some(Descriptor::Density(3.0))),
+            candidate("f.png", Some(Descriptor::Density(0.5))),
+        ]
+    );
+    // Commas inside URLs belong to them, and parentheses group descriptors.
+    assert_eq!(
+        parse_srcset("data:image/png;base64,iVBO 2x, f.png (future, 1) 1x"),
+        vec![candidate(
+            "data:image/png;base64,iVBO",
+            Some(Descriptor::Density(2.0))
+        )]
+    );
+    // Invalid descriptors drop their candidate.
+    assert_eq!(
+        parse_srcset(
+            "a.png 0w, b.png 1x 2x, c.png 50h, d.png +1x, e.png 10w 1x, f.png 2.x, g.png 1x"
+        ),
+        vec![candidate("g.png", Some(Descriptor::Density(1.0)))]
+    );
+    assert!(parse_srcset(" , ").is_empty());
+}
+
+#[test]
+fn reads_refresh_targets() {
+    let target = |content: &str| {
+        let html = format!("<meta http-equiv=refresh content=\"{content}\">");
+        urls(&html).into_iter().map(|(_, url)| url).next()
+    };
+    assert_eq!(
+        target("0;URL='/moved' ignored"),
+        Some("https://example.com/moved".to_string())
+    );
+    assert_eq!(
+        target(" 3.5 , url = next"),
+        Some("https://example.com/blog/next".to_string())
+    );
+    assert_eq!(
+        target("1 https://other.example/"),
+        Some("https://other.example/".to_string())
+    );
+    assert_eq!(
+        target("1; urlish"),
+        Some("https://example.com/blog/urlish".to_string())
+    );
+    assert_eq!(target("soon; url=x"), None);
+    assert_eq!(target("5x; url=x"), None);
+}
+
+#[test]
+fn reads_urls_in_inline_styles() {
+    let links = links(
+        "<div style=\"background: URL( 'bg.png' ) no-repeat, url(a\\29 b.png);\
+         /* url(comment.png) */ content: 'url(string.png)'; --x: myurl(no.png);\
+         cursor: url(bad url.png), url(  \\\"\"></div>\
+         <svg><rect style=\"fill: url(#gradient)\"/></svg>",
+    );
+    let found: Vec<(&str, String)> = links
+        .iter()
+        .map(|link| (link.reference.as_str(), link.url.to_string()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("bg.png", "https://example.com/blog/bg.png".to_string()),
+            ("a)b.png", "https://example.com/blog/a)b.png".to_string()),
+            (
+                "#gradient",
+                "https://example.com/blog/post?page=2#gradient".to_string()
+            ),
+        ]
+    );
+    assert!(links.iter().all(|link| link.kind == LinkKind::Style));
+    assert!(links.iter().all(|link| link.attribute == "style"));
+}
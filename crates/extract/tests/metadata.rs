@@ -0,0 +1,147 @@
+use extract::{base_url, extract_metadata, Url};
+use parser::parse_html;
+
+const PAGE: &str = "<!DOCTYPE html>\
+    <html lang=en-GB><head>\
+    <meta charset=utf-8>\
+    <title>\n  An   Article\n</title>\
+    <base href=/static/>\
+    <meta name=Description content=\"About things\">\
+    <meta name=viewport content=\"width=device-width\">\
+    <meta property=og:title content=\"Open title\">\
+    <meta property=og:image content=a.png>\
+    <meta property=og:image content=https://cdn.example/b.png>\
+    <meta name=og:url content=/article>\
+    <meta name=twitter:card content=summary_large_image>\
+    <meta property=twitter:image content=card.jpg>\
+    <meta property=article:author content=Ann>\
+    <link rel=canonical href=https://example.com/article>\
+    <link rel=\"shortcut icon\" href=/favicon.ico>\
+    <link rel=apple-touch-icon sizes=180x180 href=touch.png type=image/png>\
+    <link rel=alternate hreflang=de href=https://example.de/artikel>\
+    <link rel=alternate type=application/rss+xml title=Feed href=feed.xml>\
+    <link rel=stylesheet href=style.css>\
+    </head><body><svg><title>Not the title</title></svg><title>Second</title></body></html>";
+
+fn url() -> Url {
+    Url::parse("https://example.com/blog/post").unwrap()
+}
+
+#[test]
+fn reads_title_language_and_charset() {
+    let metadata = extract_metadata(&parse_html(PAGE), &url());
+    assert_eq!(metadata.title.as_deref(), Some("An Article"));
+    assert_eq!(metadata.lang.as_deref(), Some("en-GB"));
+    assert_eq!(metadata.charset.as_deref(), Some("utf-8"));
+}
+
+#[test]
+fn reads_meta_tags() {
+    let metadata = extract_metadata(&parse_html(PAGE), &url());
+    assert_eq!(
+        metadata.meta,
+        vec![
+            ("description".to_string(), "About things".to_string()),
+            ("viewport".to_string(), "width=device-width".to_string()),
+        ]
+    );
+    assert_eq!(metadata.meta_content("DESCRIPTION"), Some("About things"));
+}
+
+#[test]
+fn reads_open_graph_and_twitter_properties() {
+    let metadata = extract_metadata(&parse_html(PAGE), &url());
+    assert_eq!(
+        metadata.open_graph,
+        vec![
+            ("og:title".to_string(), "Open title".to_string()),
+            (
+                "og:image".to_string(),
+                "https://example.com/static/a.png".to_string()
+            ),
+            (
+                "og:image".to_string(),
+                "https://cdn.example/b.png".to_string()
+            ),
+            (
+                "og:url".to_string(),
+                "https://example.com/article".to_string()
+            ),
+        ]
+    );
+    assert_eq!(metadata.open_graph_property("og:title"), Some("Open title"));
+    assert_eq!(
+        metadata.twitter_property("twitter:card"),
+        Some("summary_large_image")
+    );
+    assert_eq!(
+        metadata.twitter_property("twitter:image"),
+        Some("https://example.com/static/card.jpg")
+    );
+}
+
+#[test]
+fn reads_links() {
+    let metadata = extract_metadata(&parse_html(PAGE), &url());
+    assert_eq!(
+        metadata.canonical.as_ref().map(Url::as_str),
+        Some("https://example.com/article")
+    );
+
+    let icons: Vec<_> = metadata
+        .icons
+        .iter()
+        .map(|icon| (icon.url.as_str(), icon.rel.as_str(), icon.sizes.as_deref()))
+        .collect();
+    assert_eq!(
+        icons,
+        vec![
+            ("https://example.com/favicon.ico", "shortcut icon", None),
+            (
+                "https://example.com/static/touch.png",
+                "apple-touch-icon",
+                Some("180x180")
+            ),
+        ]
+    );
+    assert_eq!(metadata.icons[1].media_type.as_deref(), Some("image/png"));
+
+    assert_eq!(metadata.alternates.len(), 2);
+    assert_eq!(metadata.alternates[0].hreflang.as_deref(), Some("de"));
+    assert_eq!(
+        metadata.alternates[1].url.as_str(),
+        "https://example.com/static/feed.xml"
+    );
+    assert_eq!(metadata.alternates[1].title.as_deref(), Some("Feed"));
+}
+
+#[test]
+fn resolves_against_the_base_element() {
+    let document = parse_html(PAGE);
+    assert_eq!(
+        base_url(&document, &url()).as_str(),
+        "https://example.com/static/"
+    );
+    assert_eq!(
+        extract_metadata(&document, &url()).base_url.as_str(),
+        "https://example.com/static/"
+    );
+
+    let document = parse_html("<base target=_blank><base href=//other.example/x/>");
+    assert_eq!(
+        base_url(&document, &url()).as_str(),
+        "https://other.example/x/"
+    );
+    let document = parse_html("<p>no base</p>");
+    assert_eq!(base_url(&document, &url()), url());
+}
+
+#[test]
+fn missing_metadata_is_empty() {
+    let metadata = extract_metadata(&parse_html("<p>Just text"), &url());
+    assert_eq!(metadata.title, None);
+    assert_eq!(metadata.lang, None);
+    assert!(metadata.meta.is_empty() && metadata.open_graph.is_empty());
+    assert!(metadata.icons.is_empty() && metadata.alternates.is_empty());
+    assert_eq!(metadata.canonical, None);
+}
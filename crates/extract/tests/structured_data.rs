@@ -0,0 +1,233 @@
+use extract::{extract_structured_data, Format, Item, PropertyValue, StructuredData, Url};
+use parser::parse_html;
+use serde_json::json;
+
+fn extract(html: &str) -> StructuredData {
+    let url = Url::parse("https://shop.example/lamps/desk").unwrap();
+    extract_structured_data(&parse_html(html), &url)
+}
+
+fn texts<'a>(item: &'a Item, name: &'a str) -> Vec<&'a str> {
+    item.values(name)
+        .filter_map(PropertyValue::as_text)
+        .collect()
+}
+
+#[test]
+fn reads_json_ld() {
+    let data = extract(
+        "<script type=\"application/ld+json\">\
+         {\"@context\": \"https://schema.org\", \"@type\": \"Product\", \"@id\": \"#lamp\",\
+          \"name\": \"Desk lamp\", \"image\": [\"a.jpg\", \"b.jpg\"],\
+          \"offers\": {\"@type\": \"Offer\", \"price\": 19.5, \"priceCurrency\": \"EUR\"}}\
+         </script>\
+         <script type=' Application/LD+JSON '>[{\"@context\": {\"@vocab\": \"http://schema.org/\"},\
+          \"@graph\": [{\"@type\": [\"Organization\", \"ex:Shop\"], \"name\": \"Lamps\"}]}]</script>\
+         <script type=application/json>{\"@type\": \"Ignored\"}</script>",
+    );
+    assert_eq!(data.json_ld.len(), 2);
+    assert_eq!(data.json_ld[0]["name"], json!("Desk lamp"));
+    assert_eq!(data.items.len(), 2);
+
+    let product = &data.items[0];
+    assert_eq!(product.format, Format::JsonLd);
+    assert_eq!(product.types, vec!["https://schema.org/Product"]);
+    assert_eq!(product.id.as_deref(), Some("#lamp"));
+    assert_eq!(texts(product, "image"), vec!["a.jpg", "b.jpg"]);
+    let offer = product.property("offers").unwrap().as_item().unwrap();
+    assert_eq!(offer.types, vec!["https://schema.org/Offer"]);
+    assert_eq!(
+        offer.property("price"),
+        Some(&PropertyValue::Json(json!(19.5)))
+    );
+
+    let organization = &data.items[1];
+    assert_eq!(
+        organization.types,
+        vec!["http://schema.org/Organization", "ex:Shop"]
+    );
+    assert_eq!(organization.text("name"), Some("Lamps"));
+}
+
+#[test]
+fn reports_invalid_json_ld() {
+    let data = extract(
+        "<script type=application/ld+json>{\"name\": </script>\
+         <script type=application/ld+json>{\"@type\": \"Thing\"}</script>",
+    );
+    assert_eq!(data.json_ld_errors.len(), 1);
+    assert!(data.json_ld_errors[0].message.contains("EOF"));
+    assert_eq!(data.items.len(), 1);
+    assert_eq!(data.items[0].types, vec!["Thing"]);
+}
+
+#[test]
+fn reads_microdata_values_by_element() {
+    let data = extract(
+        "<base href=/static/>\
+         <div itemscope itemtype=\"https://schema.org/Product https://schema.org/Thing\" itemid=/p/1>\
+         <h1 itemprop=\"name alternateName\">Desk <b>lamp</b></h1>\
+         <meta itemprop=sku content=L-1>\
+         <img itemprop=image src=lamp.jpg>\
+         <a itemprop=url href=/lamp>Lamp</a>\
+         <data itemprop=gtin value=0123>barcode</data>\
+         <time itemprop=releaseDate datetime=2024-01-02>January</time>\
+         <time itemprop=validThrough>2025</time>\
+         <object itemprop=manual data=\"//[invalid\"></object>\
+         <p>Not a property</p>\
+         </div>",
+    );
+    assert_eq!(data.items.len(), 1);
+    let product = &data.items[0];
+    assert_eq!(product.format, Format::Microdata);
+    assert!(product.has_type("Product") && product.has_type("https://schema.org/Thing"));
+    assert!(!product.has_type("Offer"));
+    assert_eq!(product.id.as_deref(), Some("https://shop.example/p/1"));
+    let names: Vec<&str> = product
+        .properties
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "name",
+            "alternateName",
+            "sku",
+            "image",
+            "url",
+            "gtin",
+            "releaseDate",
+            "validThrough",
+            "manual"
+        ]
+    );
+    assert_eq!(product.text("alternateName"), Some("Desk lamp"));
+    assert_eq!(product.text("sku"), Some("L-1"));
+    assert_eq!(
+        product.text("image"),
+        Some("https://shop.example/static/lamp.jpg")
+    );
+    assert_eq!(product.text("url"), Some("https://shop.example/lamp"));
+    assert_eq!(product.text("gtin"), Some("0123"));
+    assert_eq!(product.text("releaseDate"), Some("2024-01-02"));
+    assert_eq!(product.text("validThrough"), Some("2025"));
+    assert_eq!(product.text("manual"), Some(""));
+}
+
+#[test]
+fn nests_microdata_items_and_follows_itemref() {
+    let data = extract(
+        "<div itemscope itemtype=https://schema.org/Product itemref=\"brand missing\">\
+         <span itemprop=name>Lamp</span>\
+         <div itemprop=offers itemscope itemtype=https://schema.org/Offer>\
+         <span itemprop=price>19.50</span></div>\
+         </div>\
+         <p id=brand itemprop=brand>Acme</p>\
+         <div itemscope><span itemprop=name>Second</span></div>",
+    );
+    assert_eq!(data.items.len(), 2);
+    let product = &data.items[0];
+    assert_eq!(texts(product, "brand"), vec!["Acme"]);
+    let offer = product.property("offers").unwrap().as_item().unwrap();
+    assert_eq!(offer.text("price"), Some("19.50"));
+    // Properties of the nested item aren't the outer item's.
+    assert_eq!(product.property("price"), None);
+    assert!(data.items[1].types.is_empty());
+
+    let offers: Vec<_> = data.items_of_type("Offer").collect();
+    assert_eq!(offers, vec![offer]);
+}
+
+#[test]
+fn breaks_itemref_cycles() {
+    let data = extract(
+        "<div itemscope itemref=x></div>\
+         <div id=x itemprop=a itemscope itemref=y><span itemprop=name>X</span></div>\
+         <div id=y itemprop=b itemscope itemref=x><span itemprop=name>Y</span></div>",
+    );
+    assert_eq!(data.items.len(), 1);
+    assert_eq!(
+        data.items[0].to_json(),
+        json!({
+            "type": [],
+            "properties": {
+                "a": [{
+                    "type": [],
+                    "properties": {
+                        "name": ["X"],
+                        "b": [{"type": [], "properties": {"name": ["Y"]}}],
+                    },
+                }],
+            },
+        })
+    );
+}
+
+#[test]
+fn reads_rdfa_lite() {
+    let data = extract(
+        "<div vocab=https://schema.org/ typeof=Product resource=#lamp>\
+         <span property=name>Desk lamp</span>\
+         <img property=image src=lamp.jpg>\
+         <div property=offers typeof=\"Offer og:product\">\
+         <meta property=price content=19.50>\
+         <a property=seller href=/acme>Acme</a>\
+         </div>\
+         <span property=\"description disambiguatingDescription\">Bright</span>\
+         </div>\
+         <p prefix=\"ex: http://example.com/ns#\"><span typeof=ex:Thing property=orphan>\
+         <time property=date datetime=2024-05-06>May</time></span></p>\
+         <span property=ignored>No item</span>",
+    );
+    assert_eq!(data.items.len(), 2);
+    let product = &data.items[0];
+    assert_eq!(product.format, Format::Rdfa);
+    assert_eq!(product.types, vec!["https://schema.org/Product"]);
+    assert_eq!(
+        product.id.as_deref(),
+        Some("https://shop.example/lamps/desk#lamp")
+    );
+    assert_eq!(product.text("name"), Some("Desk lamp"));
+    assert_eq!(
+        product.text("image"),
+        Some("https://shop.example/lamps/lamp.jpg")
+    );
+    assert_eq!(product.text("disambiguatingDescription"), Some("Bright"));
+
+    let offer = product.property("offers").unwrap().as_item().unwrap();
+    assert_eq!(
+        offer.types,
+        vec!["https://schema.org/Offer", "http://ogp.me/ns#product"]
+    );
+    assert_eq!(offer.text("price"), Some("19.50"));
+    assert_eq!(offer.text("seller"), Some("https://shop.example/acme"));
+    assert_eq!(product.property("price"), None);
+
+    let thing = &data.items[1];
+    assert_eq!(thing.types, vec!["http://example.com/ns#Thing"]);
+    assert_eq!(thing.text("date"), Some("2024-05-06"));
+}
+
+#[test]
+fn combines_formats() {
+    let data = extract(
+        "<div itemscope itemtype=https://schema.org/Product><span itemprop=name>M</span></div>\
+         <div vocab=https://schema.org/ typeof=Product><span property=name>R</span></div>\
+         <script type=application/ld+json>{\"@context\": \"https://schema.org/\",\
+          \"@type\": \"Product\", \"name\": \"J\"}</script>",
+    );
+    let products: Vec<(Format, &str)> = data
+        .items_of_type("https://schema.org/Product")
+        .map(|item| (item.format, item.text("name").unwrap()))
+        .collect();
+    assert_eq!(
+        products,
+        vec![
+            (Format::JsonLd, "J"),
+            (Format::Microdata, "M"),
+            (Format::Rdfa, "R"),
+        ]
+    );
+    assert!(extract("<p>Nothing here</p>").items.is_empty());
+}
@@ -0,0 +1,216 @@
+use extract::{extract_table, extract_tables, Scope, Section, Table};
+use parser::parse_html;
+use serde_json::json;
+
+fn table(html: &str) -> Table {
+    extract_tables(&parse_html(html)).remove(0)
+}
+
+#[test]
+fn lays_out_spanning_cells() {
+    let table = table(
+        "<table><caption> Scores </caption>\
+         <thead><tr><th rowspan=2>Name<th colspan=2>Score\
+         <tr><th>Math<th>Art\
+         <tbody><tr><td>Ann<td>90<td>85\
+         <tr><td>Bob<td colspan=2>absent\
+         </table>",
+    );
+    assert_eq!(table.caption.as_deref(), Some("Scores"));
+    assert_eq!(table.width, 3);
+    assert_eq!(
+        table.grid(),
+        vec![
+            vec!["Name", "Score", "Score"],
+            vec!["Name", "Math", "Art"],
+            vec!["Ann", "90", "85"],
+            vec!["Bob", "absent", "absent"],
+        ]
+    );
+    let name = table.cell(1, 0).unwrap();
+    assert_eq!(
+        (name.row, name.column, name.row_span, name.column_span),
+        (0, 0, 2, 1)
+    );
+    assert!(name.header);
+    assert_eq!(table.header_rows(), 2);
+    assert_eq!(
+        table.column_names(),
+        vec!["Name", "Score / Math", "Score / Art"]
+    );
+    assert_eq!(
+        table.to_json(),
+        json!([
+            {"Name": "Ann", "Score / Math": "90", "Score / Art": "85"},
+            {"Name": "Bob", "Score / Math": "absent", "Score / Art": "absent"},
+        ])
+    );
+}
+
+#[test]
+fn orders_sections_like_browsers() {
+    let table = table(
+        "<table><tfoot><tr><td>Total</tfoot>\
+         <tbody><tr><td>1</tbody>\
+         <thead><tr><th>N</thead>\
+         <tr><td>2</table>",
+    );
+    assert_eq!(
+        table.grid(),
+        vec![vec!["N"], vec!["1"], vec!["2"], vec!["Total"]]
+    );
+    let sections: Vec<Section> = table.rows.iter().map(|row| row.section).collect();
+    assert_eq!(
+        sections,
+        vec![Section::Head, Section::Body, Section::Body, Section::Foot]
+    );
+    let groups: Vec<usize> = table.rows.iter().map(|row| row.group).collect();
+    assert_eq!(groups, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn clips_spans_to_row_groups() {
+    let table = table(
+        "<table><tbody><tr><td rowspan=5>a<td>b<tr><td>c</tbody>\
+         <tbody><tr><td rowspan=0>x<td colspan=\" 2abc\">1<tr><td colspan=0>2<tr><td>3</tbody></table>",
+    );
+    assert_eq!(
+        table.grid(),
+        vec![
+            vec!["a", "b", ""],
+            vec!["a", "c", ""],
+            vec!["x", "1", "1"],
+            vec!["x", "2", ""],
+            vec!["x", "3", ""],
+        ]
+    );
+    assert_eq!(table.cells[0].row_span, 2);
+    assert_eq!(table.cells[3].row_span, 3);
+    assert_eq!(table.rows[0].slots, vec![Some(0), Some(1), None]);
+    assert_eq!(table.cell(0, 2), None);
+}
+
+#[test]
+fn finds_headers_from_th_and_scope() {
+    let table = table(
+        "<table>\
+         <tr><th><th scope=col>Q1<th scope=COL>Q2\
+         <tr><th scope=row>North<td>1<td>2\
+         <tr><th>South<td>3<td>4\
+         </table>",
+    );
+    let text = |index: usize| table.cells[index].text.as_str();
+    let headers = |index: usize| -> Vec<&str> {
+        table.cells[index]
+            .headers
+            .iter()
+            .map(|&header| text(header))
+            .collect()
+    };
+    assert_eq!(text(8), "4");
+    assert_eq!(headers(8), vec!["South", "Q2"]);
+    assert_eq!(headers(4), vec!["North", "Q1"]);
+    assert_eq!(table.cells[3].scope, Scope::Row);
+    assert_eq!(table.cells[6].scope, Scope::Auto);
+    assert!(table.is_row_header(6) && !table.is_column_header(6));
+    assert!(table.is_column_header(1) && !table.is_row_header(1));
+    assert_eq!(table.header_rows(), 1);
+    assert_eq!(table.column_names(), vec!["column 1", "Q1", "Q2"]);
+    assert_eq!(
+        table.to_json()[1],
+        json!({"column 1": "South", "Q1": "3", "Q2": "4"})
+    );
+
+    // With a data cell in the corner, `th`s without a scope are neither
+    // column nor row headers, but explicit scopes still apply.
+    let headers = |html: &str| -> Vec<String> {
+        let table = self::table(html);
+        table.cells[3]
+            .headers
+            .iter()
+            .map(|&header| table.cells[header].text.clone())
+            .collect()
+    };
+    assert!(headers("<table><tr><td><th>A<tr><th>B<td>1</table>").is_empty());
+    assert_eq!(
+        headers("<table><tr><td><th scope=col>A<tr><th scope=row>B<td>1</table>"),
+        vec!["B", "A"]
+    );
+}
+
+#[test]
+fn reads_headers_attributes_and_row_groups() {
+    let table = table(
+        "<table><tr><th id=a>A<th id=b>B<th>C\
+         <tr><td headers=\"b a missing\">x<td>y<td headers=\"\">z</table>",
+    );
+    let texts = |index: usize| -> Vec<&str> {
+        table.cells[index]
+            .headers
+            .iter()
+            .map(|&header| table.cells[header].text.as_str())
+            .collect()
+    };
+    assert_eq!(texts(3), vec!["B", "A"]);
+    assert_eq!(texts(4), vec!["B"]);
+    assert!(texts(5).is_empty());
+
+    let table = self::table(
+        "<table><tbody><tr><th scope=rowgroup>Fruit<td>apple<tr><td>pear</tbody>\
+         <tbody><tr><td>carrot</tbody></table>",
+    );
+    let texts = |index: usize| -> Vec<&str> {
+        table.cells[index]
+            .headers
+            .iter()
+            .map(|&header| table.cells[header].text.as_str())
+            .collect()
+    };
+    assert_eq!(table.cells[2].text, "pear");
+    assert_eq!(texts(2), vec!["Fruit"]);
+    assert!(texts(3).is_empty());
+}
+
+#[test]
+fn writes_csv() {
+    let document = parse_html(
+        "<table><tr><th>Name<th>Note<th>Nested\
+         <tr><td>a, \"b\"<td>line<br>two<td><table><tr><td>inner</table></table>",
+    );
+    let tables = extract_tables(&document);
+    assert_eq!(tables.len(), 2);
+    assert_eq!(
+        tables[0].to_csv(),
+        "Name,Note,Nested\r\n\"a, \"\"b\"\"\",\"line\ntwo\",inner\r\n"
+    );
+    assert_eq!(tables[1].to_csv(), "inner\r\n");
+}
+
+#[test]
+fn names_columns_uniquely() {
+    let table = table(
+        "<table><tr><th>Price<th>Price<th><th>Price 2\
+         <tr><td>1<td>2<td>3<td>4</table>",
+    );
+    assert_eq!(
+        table.column_names(),
+        vec!["Price", "Price 2", "column 3", "Price 2 2"]
+    );
+    let table = self::table("<table><tr><td>1<td>2</table>");
+    assert_eq!(table.header_rows(), 0);
+    assert_eq!(table.to_json(), json!([{"column 1": "1", "column 2": "2"}]));
+}
+
+#[test]
+fn only_lays_out_tables() {
+    let document = parse_html("<div><table></table></div>");
+    let div = document
+        .descendants()
+        .find_map(|node| node.as_element())
+        .unwrap();
+    assert_eq!(extract_table(div), None);
+    let tables = extract_tables(&document);
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].width, 0);
+    assert!(tables[0].rows.is_empty() && tables[0].to_csv().is_empty());
+}
@@ -0,0 +1,311 @@
+//! Pretty-prints HTML documents.
+//!
+//! Block elements are put on lines of their own and their children indented.
+//! The contents of inline elements and text are filled into lines up to the
+//! configured width, breaking only where the whitespace sensitivity allows.
+//! The contents of `pre`, `textarea`, `script`, `style` and other raw text
+//! elements are written verbatim. Formatting is idempotent: formatting the
+//! output again gives the same output.
+
+mod run;
+
+use ast::{elements, walk, Document, Element, Node, Text, Visit, Visitor};
+use parser::parse_html;
+use run::Run;
+
+/// Elements whose contents are written exactly as they are.
+const VERBATIM: &[&str] = &["listing", "plaintext", "pre", "textarea"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level.
+    pub indent_width: usize,
+    /// Lines are kept to this many characters where possible.
+    pub line_width: usize,
+    pub wrap_attributes: WrapAttributes,
+    pub whitespace_sensitivity: WhitespaceSensitivity,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 2,
+            line_width: 80,
+            wrap_attributes: WrapAttributes::Auto,
+            whitespace_sensitivity: WhitespaceSensitivity::Css,
+        }
+    }
+}
+
+/// When the attributes of a block element's start tag are put on lines of
+/// their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapAttributes {
+    /// When the start tag doesn't fit on a line.
+    Auto,
+    /// Whenever there is more than one attribute.
+    Always,
+    /// Never.
+    Never,
+}
+
+/// Which whitespace the formatter may add or remove.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitespaceSensitivity {
+    /// Whitespace around and directly inside block elements is insignificant;
+    /// inside inline content lines are only broken where there already is
+    /// whitespace.
+    Css,
+    /// All elements are treated as inline, so whitespace is only ever
+    /// reflowed, never added or removed.
+    Strict,
+    /// Whitespace is insignificant everywhere, so lines can also be broken
+    /// between a tag and the text next to it.
+    Ignore,
+}
+
+/// Parses and formats `source`.
+pub fn format(source: &str, options: &FormatOptions) -> String {
+    format_document(&parse_html(source), options)
+}
+
+/// Formats a parsed document.
+pub fn format_document(document: &Document, options: &FormatOptions) -> String {
+    let mut formatter = Formatter {
+        options,
+        blocks: block_elements(document, options),
+        output: String::new(),
+        depth: 0,
+        open: Vec::new(),
+        run: Run::new(options.whitespace_sensitivity),
+    };
+    walk(document.root(), &mut formatter);
+    formatter.output
+}
+
+fn is_verbatim(name: &str) -> bool {
+    VERBATIM.contains(&name) || elements::is_raw_text(name)
+}
+
+/// Finds the elements that are laid out as blocks: block elements, and inline
+/// elements that contain one. Elements that aren't rendered, such as scripts,
+/// are only put on lines of their own when they don't sit in inline content,
+/// where the line breaks around them would become spaces.
+fn block_elements(document: &Document, options: &FormatOptions) -> Vec<bool> {
+    struct Blocks(Vec<bool>);
+
+    impl<'a> Visitor<'a> for Blocks {
+        fn leave_element(&mut self, element: Element<'a>) -> Visit {
+            let id = element.as_node().id().index();
+            let name = element.tag_name();
+            self.0[id] |= if elements::is_hidden(name) {
+                !in_inline_content(element)
+            } else {
+                elements::is_block(name)
+            };
+            if self.0[id] {
+                if let Some(parent) = element.parent() {
+                    self.0[parent.id().index()] = true;
+                }
+            }
+            Visit::Continue
+        }
+    }
+
+    let mut blocks = Blocks(vec![false; document.len() + 1]);
+    if options.whitespace_sensitivity != WhitespaceSensitivity::Strict {
+        walk(document.root(), &mut blocks);
+    }
+    blocks.0
+}
+
+/// Whether the nearest rendered content on either side of an element is
+/// inline, or it has none and its parent lays out inline content.
+fn in_inline_content(element: Element) -> bool {
+    let rendered = |node: &Node| match node.as_element() {
+        Some(element) => !elements::is_hidden(element.tag_name()),
+        None => node
+            .as_text()
+            .is_some_and(|text| !text.text().trim_ascii().is_empty()),
+    };
+    let is_inline = |node: Node| match node.as_element() {
+        Some(element) => !elements::is_layout_block(element.tag_name()),
+        None => true,
+    };
+    let node = element.as_node();
+    let previous = std::iter::successors(node.previous_sibling(), |node| node.previous_sibling())
+        .find(rendered);
+    let next =
+        std::iter::successors(node.next_sibling(), |node| node.next_sibling()).find(rendered);
+    match (previous, next) {
+        (None, None) => node.parent().is_some_and(|parent| {
+            !parent.is_document()
+                && parent
+                    .as_element()
+                    .is_some_and(|parent| !elements::is_block(parent.tag_name()))
+        }),
+        (previous, next) => previous.is_some_and(is_inline) || next.is_some_and(is_inline),
+    }
+}
+
+struct Formatter<'o> {
+    options: &'o FormatOptions,
+    blocks: Vec<bool>,
+    output: String,
+    depth: usize,
+    /// For each entered element, whether its children were indented and it
+    /// still needs an end tag line.
+    open: Vec<bool>,
+    /// Inline content waiting to be filled into lines.
+    run: Run,
+}
+
+impl Formatter<'_> {
+    fn is_block(&self, node: Node) -> bool {
+        self.blocks[node.id().index()]
+    }
+
+    fn indent(&self, depth: usize) -> usize {
+        depth * self.options.indent_width
+    }
+
+    fn write_line(&mut self, depth: usize, line: &str) {
+        let indent = self.indent(depth);
+        self.output.extend(std::iter::repeat_n(' ', indent));
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Writes any pending inline content.
+    fn flush(&mut self) {
+        if !self.run.is_empty() {
+            let indent = self.indent(self.depth);
+            self.run
+                .fill(indent, self.options.line_width, &mut self.output);
+            self.output.push('\n');
+            self.run.clear();
+        }
+    }
+
+    fn wraps_attributes(&self, element: Element, depth: usize) -> bool {
+        let attributes = element.attributes().len();
+        match self.options.wrap_attributes {
+            WrapAttributes::Never => false,
+            WrapAttributes::Always => attributes > 1,
+            WrapAttributes::Auto => {
+                attributes > 0
+                    && self.indent(depth) + width(&start_tag(element)) > self.line_width()
+            }
+        }
+    }
+
+    fn line_width(&self) -> usize {
+        self.options.line_width
+    }
+
+    fn write_start_tag(&mut self, element: Element) {
+        if !self.wraps_attributes(element, self.depth) {
+            self.write_line(self.depth, &start_tag(element));
+            return;
+        }
+        self.write_line(self.depth, &format!("<{}", element.tag_name()));
+        for attribute in element.attributes() {
+            let mut line = String::from(attribute.name());
+            line.push_str("=\"");
+            serializer::escape_attribute(attribute.value(), &mut line);
+            line.push('"');
+            self.write_line(self.depth + 1, &line);
+        }
+        self.write_line(self.depth, ">");
+    }
+
+    /// Lays out an element whose contents are all inline on a single line, if
+    /// it fits.
+    fn single_line(&self, element: Element) -> Option<String> {
+        if element.children().any(|child| self.is_block(child))
+            || self.options.wrap_attributes == WrapAttributes::Always
+                && element.attributes().len() > 1
+        {
+            return None;
+        }
+        let mut run = Run::new(self.options.whitespace_sensitivity);
+        for child in element.children() {
+            walk(child, &mut run);
+        }
+        let mut line = start_tag(element);
+        run.fill(0, usize::MAX, &mut line);
+        serializer::write_end_tag(element, &mut line);
+        let fits =
+            !line.contains('\n') && self.indent(self.depth) + width(&line) <= self.line_width();
+        fits.then_some(line)
+    }
+}
+
+impl<'a> Visitor<'a> for Formatter<'_> {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        if !self.is_block(element.as_node()) {
+            walk(element.as_node(), &mut self.run);
+            self.open.push(false);
+            return Visit::SkipChildren;
+        }
+
+        self.flush();
+        self.open.push(false);
+        if is_verbatim(element.tag_name()) {
+            let html = serializer::outer_html(element.as_node());
+            self.write_line(self.depth, &html);
+        } else if let Some(line) = self.single_line(element) {
+            self.write_line(self.depth, &line);
+        } else {
+            self.write_start_tag(element);
+            self.depth += 1;
+            *self.open.last_mut().unwrap() = true;
+            return Visit::Continue;
+        }
+        Visit::SkipChildren
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        if self.open.pop() == Some(true) {
+            self.flush();
+            self.depth -= 1;
+            if !elements::is_void(element.tag_name()) {
+                let mut line = String::new();
+                serializer::write_end_tag(element, &mut line);
+                self.write_line(self.depth, &line);
+            }
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        self.run.visit_text(text)
+    }
+
+    fn visit_comment(&mut self, comment: Node<'a>) -> Visit {
+        self.run.visit_comment(comment)
+    }
+
+    fn visit_doctype(&mut self, doctype: Node<'a>) -> Visit {
+        self.flush();
+        let mut line = String::new();
+        serializer::write_leaf(doctype, &mut line);
+        self.write_line(self.depth, &line);
+        Visit::Continue
+    }
+
+    fn leave_document(&mut self, _document: Node<'a>) -> Visit {
+        self.flush();
+        Visit::Continue
+    }
+}
+
+fn start_tag(element: Element) -> String {
+    let mut tag = String::new();
+    serializer::write_start_tag(element, &mut tag);
+    tag
+}
+
+fn width(line: &str) -> usize {
+    line.chars().count()
+}
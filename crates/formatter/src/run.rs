@@ -0,0 +1,148 @@
+use crate::{is_verbatim, width, WhitespaceSensitivity};
+use ast::{Element, Node, Text, Visit, Visitor};
+
+/// What may go between two pieces of inline content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    /// Nothing: the pieces must stay on the same line.
+    Glue,
+    /// Nothing, or a line break.
+    Soft,
+    /// A space, or a line break.
+    Space,
+}
+
+/// A run of inline content: words, tags, comments and verbatim elements, with
+/// the separators that may go between them.
+pub(crate) struct Run {
+    sensitivity: WhitespaceSensitivity,
+    pieces: Vec<(Separator, String)>,
+    /// The separator that goes before the next piece.
+    pending: Separator,
+}
+
+impl Run {
+    pub(crate) fn new(sensitivity: WhitespaceSensitivity) -> Run {
+        Run {
+            sensitivity,
+            pieces: Vec::new(),
+            pending: Separator::Glue,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pieces.clear();
+        self.pending = Separator::Glue;
+    }
+
+    fn push(&mut self, piece: String) {
+        let separator = match self.pending {
+            Separator::Glue if self.sensitivity == WhitespaceSensitivity::Ignore => Separator::Soft,
+            separator => separator,
+        };
+        self.pieces.push((separator, piece));
+        self.pending = Separator::Glue;
+    }
+
+    fn space(&mut self) {
+        self.pending = Separator::Space;
+    }
+
+    /// Writes the run, greedily filling lines of `line_width` characters
+    /// indented by `indent` spaces. The first line is indented too. Whitespace
+    /// at the start and end of the run is dropped.
+    pub(crate) fn fill(&self, indent: usize, line_width: usize, output: &mut String) {
+        let mut column = indent;
+        output.extend(std::iter::repeat_n(' ', indent));
+        for (index, (separator, piece)) in self.pieces.iter().enumerate() {
+            let first_line = width(piece.split('\n').next().unwrap_or_default());
+            if index > 0 {
+                match separator {
+                    Separator::Glue => {}
+                    Separator::Soft | Separator::Space => {
+                        let space = usize::from(*separator == Separator::Space);
+                        if column > indent && column.saturating_add(space + first_line) > line_width
+                        {
+                            output.push('\n');
+                            output.extend(std::iter::repeat_n(' ', indent));
+                            column = indent;
+                        } else if space == 1 {
+                            output.push(' ');
+                            column += 1;
+                        }
+                    }
+                }
+            }
+            output.push_str(piece);
+            column = match piece.rfind('\n') {
+                Some(newline) => width(&piece[newline + 1..]),
+                None => column + width(piece),
+            };
+        }
+    }
+}
+
+/// Collects inline content by walking it.
+impl<'a> Visitor<'a> for Run {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        if is_verbatim(element.tag_name()) {
+            self.push(serializer::outer_html(element.as_node()));
+            return Visit::SkipChildren;
+        }
+        let mut tag = String::new();
+        serializer::write_start_tag(element, &mut tag);
+        self.push(tag);
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        if !is_verbatim(element.tag_name()) {
+            let mut tag = String::new();
+            serializer::write_end_tag(element, &mut tag);
+            if !tag.is_empty() {
+                self.push(tag);
+            }
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        let value = text.text();
+        if value.starts_with(is_whitespace) {
+            self.space();
+        }
+        for (index, word) in value
+            .split(is_whitespace)
+            .filter(|word| !word.is_empty())
+            .enumerate()
+        {
+            if index > 0 {
+                self.space();
+            }
+            let mut piece = String::new();
+            serializer::escape_text(word, &mut piece);
+            self.push(piece);
+        }
+        if value.ends_with(is_whitespace) {
+            self.space();
+        }
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, comment: Node<'a>) -> Visit {
+        let mut piece = String::new();
+        serializer::write_leaf(comment, &mut piece);
+        self.push(piece);
+        Visit::Continue
+    }
+}
+
+/// ASCII whitespace as HTML defines it. Other whitespace, such as no-break
+/// spaces, is part of words.
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\u{c}' | '\r')
+}
@@ -0,0 +1,143 @@
+use formatter::{format, FormatOptions, WhitespaceSensitivity, WrapAttributes};
+
+const PAGE: &str = "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>Formatting test</title>\
+<style>body { margin: 0 }\n  p > a { color: red }</style></head><body><!-- header --><header class=\"site-header\" id=\"top\" data-role=\"banner\" aria-label=\"Site header\"><h1>Hello <em>world</em></h1></header>\
+<main><p>Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad <a href=\"/minim\">minim veniam</a>, quis <b>nostrud</b> exercitation.</p>\
+<ul><li>One</li><li>Two <span>inline</span></li><li><p>Nested paragraph</p></li></ul>\
+<pre>  keep\n    this   spacing\n</pre><p>Text with a <textarea>raw\n  area</textarea> in it and <a href=\"#\"><div>a block in a link</div></a></p>\
+<table><tr><td>1</td><td>2 &amp; 3</td></tr></table><script>if (a < b) {\n  go();\n}</script></main></body></html>";
+
+fn options() -> Vec<FormatOptions> {
+    let mut all = Vec::new();
+    for whitespace_sensitivity in [
+        WhitespaceSensitivity::Css,
+        WhitespaceSensitivity::Strict,
+        WhitespaceSensitivity::Ignore,
+    ] {
+        for wrap_attributes in [
+            WrapAttributes::Auto,
+            WrapAttributes::Always,
+            WrapAttributes::Never,
+        ] {
+            for (indent_width, line_width) in [(2, 80), (4, 40), (1, 10)] {
+                all.push(FormatOptions {
+                    indent_width,
+                    line_width,
+                    wrap_attributes,
+                    whitespace_sensitivity,
+                });
+            }
+        }
+    }
+    all
+}
+
+#[test]
+fn formatting_is_idempotent() {
+    for options in options() {
+        let once = format(PAGE, &options);
+        let twice = format(&once, &options);
+        assert_eq!(once, twice, "not idempotent with {:?}", options);
+    }
+}
+
+#[test]
+fn formatting_keeps_the_text() {
+    let expected = parser::parse_html(PAGE).root().text_content();
+    let strip = |text: &str| text.split_whitespace().collect::<String>();
+    for options in options() {
+        let formatted = parser::parse_html(&format(PAGE, &options));
+        assert_eq!(strip(&formatted.root().text_content()), strip(&expected));
+    }
+}
+
+#[test]
+fn indents_blocks_and_fills_inline_content() {
+    let options = FormatOptions {
+        line_width: 30,
+        ..FormatOptions::default()
+    };
+    let source = "<div><h1>Title</h1><p>Some <b>bold text</b>, which goes on for a while.</p><p>Short</p></div>";
+    assert_eq!(
+        format(source, &options),
+        "<div>\n  <h1>Title</h1>\n  <p>\n    Some <b>bold text</b>,\n    which goes on for a while.\n  </p>\n  <p>Short</p>\n</div>\n"
+    );
+}
+
+#[test]
+fn indent_width_is_configurable() {
+    let options = FormatOptions {
+        indent_width: 4,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format("<ul><li><p>a</p></li></ul>", &options),
+        "<ul>\n    <li>\n        <p>a</p>\n    </li>\n</ul>\n"
+    );
+}
+
+#[test]
+fn preserves_verbatim_contents() {
+    let source = "<div><pre>  a\n    b  </pre><textarea>x\n\n  y</textarea><script>if (a < b) {\n go();\n}</script></div>";
+    assert_eq!(
+        format(source, &FormatOptions::default()),
+        "<div>\n  <pre>  a\n    b  </pre>\n  <textarea>x\n\n  y</textarea><script>if (a < b) {\n go();\n}</script>\n</div>\n"
+    );
+}
+
+#[test]
+fn wraps_attributes() {
+    let source = "<div id=\"a\" class=\"b\"><p>x</p></div>";
+    let wrap = |wrap_attributes, line_width| {
+        let options = FormatOptions {
+            wrap_attributes,
+            line_width,
+            ..FormatOptions::default()
+        };
+        format(source, &options)
+    };
+    let wrapped = "<div\n  id=\"a\"\n  class=\"b\"\n>\n  <p>x</p>\n</div>\n";
+    let unwrapped = "<div id=\"a\" class=\"b\">\n  <p>x</p>\n</div>\n";
+    assert_eq!(wrap(WrapAttributes::Auto, 80), unwrapped);
+    assert_eq!(wrap(WrapAttributes::Auto, 20), wrapped);
+    assert_eq!(wrap(WrapAttributes::Always, 80), wrapped);
+    assert_eq!(wrap(WrapAttributes::Never, 20), unwrapped);
+}
+
+#[test]
+fn whitespace_sensitivity() {
+    let source = "<div>zero <span>one</span><span>two</span></div>";
+    let format_with = |whitespace_sensitivity| {
+        let options = FormatOptions {
+            whitespace_sensitivity,
+            line_width: 10,
+            ..FormatOptions::default()
+        };
+        format(source, &options)
+    };
+    assert_eq!(
+        format_with(WhitespaceSensitivity::Css),
+        "<div>\n  zero\n  <span>one</span><span>two</span>\n</div>\n"
+    );
+    assert_eq!(
+        format_with(WhitespaceSensitivity::Strict),
+        "<div>zero\n<span>one</span><span>two</span></div>\n"
+    );
+    assert_eq!(
+        format_with(WhitespaceSensitivity::Ignore),
+        "<div>\n  zero\n  <span>\n  one\n  </span>\n  <span>\n  two\n  </span>\n</div>\n"
+    );
+}
+
+#[test]
+fn scripts_in_inline_content_stay_inline() {
+    let options = FormatOptions::default();
+    assert_eq!(
+        format("<p>a<script></script>b <style>p {}</style> c</p>", &options),
+        "<p>a<script></script>b <style>p {}</style> c</p>\n"
+    );
+    assert_eq!(
+        format("<div><p>a</p><script>go()</script></div>", &options),
+        "<div>\n  <p>a</p>\n  <script>go()</script>\n</div>\n"
+    );
+}
@@ -0,0 +1,311 @@
+//! Conversion between [`ast::Document`] and [hast], the HTML syntax tree
+//! format used by rehype and the rest of the unified ecosystem.
+//!
+//! Attributes become hast `properties` using the same names and value types
+//! as `property-information`: `class` becomes a `className` array, boolean
+//! attributes become `true`, numeric ones become numbers and so on. Nodes
+//! with a source span get a unist `position`.
+//!
+//! SVG and MathML elements carry their namespace in a `space` field of `svg`
+//! or `mathml`, and keep the case of their attribute names, so `viewBox`
+//! stays `viewBox`. Elements without a `space` are HTML.
+//!
+//! [hast]: https://github.com/syntax-tree/hast
+
+mod properties;
+
+use std::error::Error;
+use std::fmt;
+
+use ast::{
+    walk, Attribute, Document, Element, Location, Namespace, Node, NodeId, Span, Text, Visit,
+    Visitor,
+};
+use properties::Kind;
+use serde_json::{json, Map, Value};
+
+/// Converts a document to a hast `root` node.
+pub fn to_hast(document: &Document) -> Value {
+    node_to_hast(document.root())
+}
+
+/// Converts a node and its subtree to hast.
+pub fn node_to_hast(node: Node) -> Value {
+    let mut builder = Builder {
+        open: Vec::new(),
+        result: None,
+    };
+    walk(node, &mut builder);
+    builder.result.unwrap()
+}
+
+/// Builds hast bottom up while walking the tree, so deep trees don't take
+/// deep recursion.
+struct Builder {
+    /// The objects of the open document and elements, with their children so
+    /// far.
+    open: Vec<(Map<String, Value>, Vec<Value>)>,
+    result: Option<Value>,
+}
+
+impl Builder {
+    fn open(&mut self, object: Map<String, Value>) -> Visit {
+        self.open.push((object, Vec::new()));
+        Visit::Continue
+    }
+
+    fn close(&mut self, node: Node) -> Visit {
+        let (mut object, children) = self.open.pop().unwrap();
+        object.insert("children".into(), Value::Array(children));
+        self.add(node, object)
+    }
+
+    /// Adds a finished node to its parent, or makes it the result.
+    fn add(&mut self, node: Node, mut object: Map<String, Value>) -> Visit {
+        if let Some(span) = node.span() {
+            object.insert("position".into(), position_to_hast(span));
+        }
+        match self.open.last_mut() {
+            Some((_, children)) => children.push(Value::Object(object)),
+            None => self.result = Some(Value::Object(object)),
+        }
+        Visit::Continue
+    }
+}
+
+impl<'a> Visitor<'a> for Builder {
+    fn enter_document(&mut self, _document: Node<'a>) -> Visit {
+        let mut object = Map::new();
+        object.insert("type".into(), "root".into());
+        self.open(object)
+    }
+
+    fn leave_document(&mut self, document: Node<'a>) -> Visit {
+        self.close(document)
+    }
+
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        let mut object = Map::new();
+        object.insert("type".into(), "element".into());
+        object.insert("tagName".into(), element.tag_name().into());
+        match element.namespace() {
+            Namespace::Html => {}
+            Namespace::Svg => {
+                object.insert("space".into(), "svg".into());
+            }
+            Namespace::MathMl => {
+                object.insert("space".into(), "mathml".into());
+            }
+        }
+        object.insert(
+            "properties".into(),
+            properties_to_hast(element.attributes(), element.namespace()),
+        );
+        self.open(object)
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        self.close(element.as_node())
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        let mut object = Map::new();
+        object.insert("type".into(), "text".into());
+        object.insert("value".into(), text.text().into());
+        self.add(text.as_node(), object)
+    }
+
+    fn visit_comment(&mut self, comment: Node<'a>) -> Visit {
+        let mut object = Map::new();
+        object.insert("type".into(), "comment".into());
+        object.insert("value".into(), comment.as_comment().unwrap().text().into());
+        self.add(comment, object)
+    }
+
+    fn visit_doctype(&mut self, doctype: Node<'a>) -> Visit {
+        let mut object = Map::new();
+        object.insert("type".into(), "doctype".into());
+        self.add(doctype, object)
+    }
+}
+
+fn properties_to_hast(attributes: &[Attribute], namespace: Namespace) -> Value {
+    let mut properties = Map::new();
+    for attribute in attributes {
+        let name = properties::property_name(attribute.name(), namespace);
+        let value = attribute.value();
+        let value = match properties::kind(&name) {
+            Kind::String => value.into(),
+            Kind::Boolean => true.into(),
+            Kind::Number => match value.trim().parse::<f64>() {
+                Ok(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+                    json!(number as i64)
+                }
+                Ok(number) if number.is_finite() => json!(number),
+                _ => value.into(),
+            },
+            Kind::SpaceSeparated => value.split_ascii_whitespace().collect::<Vec<_>>().into(),
+            Kind::CommaSeparated => value
+                .split(',')
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .collect::<Vec<_>>()
+                .into(),
+        };
+        properties.insert(name, value);
+    }
+    Value::Object(properties)
+}
+
+fn position_to_hast(span: Span) -> Value {
+    let point = |location: Location| {
+        json!({
+            "line": location.line,
+            "column": location.column,
+            "offset": location.offset,
+        })
+    };
+    json!({ "start": point(span.start), "end": point(span.end) })
+}
+
+/// Why a hast tree could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HastError {
+    /// The top level node is not a `root`.
+    ExpectedRoot,
+    /// A node has a `type` this crate does not support.
+    UnsupportedNode(String),
+    /// A required field is missing or has the wrong type.
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for HastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HastError::ExpectedRoot => write!(f, "expected a hast root node"),
+            HastError::UnsupportedNode(kind) => write!(f, "unsupported hast node type `{kind}`"),
+            HastError::InvalidField(field) => write!(f, "missing or invalid field `{field}`"),
+        }
+    }
+}
+
+impl Error for HastError {}
+
+/// Builds a document from a hast `root` node.
+pub fn from_hast(value: &Value) -> Result<Document, HastError> {
+    if value.get("type").and_then(Value::as_str) != Some("root") {
+        return Err(HastError::ExpectedRoot);
+    }
+
+    let mut document = Document::new();
+    document.set_span(Document::ROOT, position_from_hast(value));
+    let mut pending = vec![(Document::ROOT, value)];
+    while let Some((parent, value)) = pending.pop() {
+        let children = value
+            .get("children")
+            .and_then(Value::as_array)
+            .ok_or(HastError::InvalidField("children"))?;
+        for child in children {
+            let id = match child.get("type").and_then(Value::as_str) {
+                Some("element") => {
+                    let name = child
+                        .get("tagName")
+                        .and_then(Value::as_str)
+                        .ok_or(HastError::InvalidField("tagName"))?;
+                    let attributes = match child.get("properties") {
+                        Some(properties) => properties_from_hast(properties)?,
+                        None => Vec::new(),
+                    };
+                    let namespace = match child.get("space") {
+                        None | Some(Value::Null) => Namespace::Html,
+                        Some(space) => match space.as_str() {
+                            Some("html") => Namespace::Html,
+                            Some("svg") => Namespace::Svg,
+                            Some("mathml") => Namespace::MathMl,
+                            _ => return Err(HastError::InvalidField("space")),
+                        },
+                    };
+                    let id = document.create_element_ns(name.to_string(), namespace, attributes);
+                    if child.get("children").is_some() {
+                        pending.push((id, child));
+                    }
+                    id
+                }
+                Some("text") => {
+                    let value = child
+                        .get("value")
+                        .and_then(Value::as_str)
+                        .ok_or(HastError::InvalidField("value"))?;
+                    document.create_text(value.to_string())
+                }
+                Some("comment") => {
+                    let value = child
+                        .get("value")
+                        .and_then(Value::as_str)
+                        .ok_or(HastError::InvalidField("value"))?;
+                    document.create_comment(value.to_string())
+                }
+                // hast doctypes carry no name or identifiers.
+                Some("doctype") => document.create_doctype("html".to_string(), None, None),
+                Some(kind) => return Err(HastError::UnsupportedNode(kind.to_string())),
+                None => return Err(HastError::InvalidField("type")),
+            };
+            document.set_span(id, position_from_hast(child));
+            append(&mut document, parent, id);
+        }
+    }
+    Ok(document)
+}
+
+fn append(document: &mut Document, parent: NodeId, child: NodeId) {
+    document
+        .append_child(parent, child)
+        .expect("new nodes can be appended to documents and elements");
+}
+
+fn properties_from_hast(value: &Value) -> Result<Vec<Attribute>, HastError> {
+    let properties = value
+        .as_object()
+        .ok_or(HastError::InvalidField("properties"))?;
+    let mut attributes = Vec::new();
+    for (property, value) in properties {
+        let separator = match properties::kind(property) {
+            Kind::CommaSeparated => ", ",
+            _ => " ",
+        };
+        let value = match value {
+            Value::Null | Value::Bool(false) => continue,
+            Value::Bool(true) => String::new(),
+            Value::Number(number) => number.to_string(),
+            Value::String(string) => string.clone(),
+            Value::Array(tokens) => tokens
+                .iter()
+                .map(|token| match token {
+                    Value::String(string) => Ok(string.clone()),
+                    Value::Number(number) => Ok(number.to_string()),
+                    _ => Err(HastError::InvalidField("properties")),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .join(separator),
+            Value::Object(_) => return Err(HastError::InvalidField("properties")),
+        };
+        attributes.push(Attribute::new(properties::attribute_name(property), value));
+    }
+    Ok(attributes)
+}
+
+fn position_from_hast(value: &Value) -> Option<Span> {
+    let position = value.get("position")?;
+    let point = |value: &Value| {
+        let field = |name| value.get(name)?.as_u64().map(|n| n as usize);
+        Some(Location {
+            line: field("line")?,
+            column: field("column")?,
+            offset: field("offset")?,
+        })
+    };
+    Some(Span::new(
+        point(position.get("start")?)?,
+        point(position.get("end")?)?,
+    ))
+}
@@ -0,0 +1,194 @@
+//! Mapping between HTML attribute names and hast property names, following
+//! the `property-information` package used by the unified ecosystem.
+
+use ast::Namespace;
+
+/// Attributes whose property name is not simply the attribute name.
+const RENAMED: &[(&str, &str)] = &[
+    ("accept-charset", "acceptCharset"),
+    ("accesskey", "accessKey"),
+    ("allowfullscreen", "allowFullScreen"),
+    ("autocomplete", "autoComplete"),
+    ("autofocus", "autoFocus"),
+    ("autoplay", "autoPlay"),
+    ("cellpadding", "cellPadding"),
+    ("cellspacing", "cellSpacing"),
+    ("charset", "charSet"),
+    ("class", "className"),
+    ("colspan", "colSpan"),
+    ("contenteditable", "contentEditable"),
+    ("crossorigin", "crossOrigin"),
+    ("datetime", "dateTime"),
+    ("enctype", "encType"),
+    ("enterkeyhint", "enterKeyHint"),
+    ("for", "htmlFor"),
+    ("formaction", "formAction"),
+    ("formenctype", "formEncType"),
+    ("formmethod", "formMethod"),
+    ("formnovalidate", "formNoValidate"),
+    ("formtarget", "formTarget"),
+    ("frameborder", "frameBorder"),
+    ("hreflang", "hrefLang"),
+    ("http-equiv", "httpEquiv"),
+    ("inputmode", "inputMode"),
+    ("ismap", "isMap"),
+    ("itemid", "itemId"),
+    ("itemprop", "itemProp"),
+    ("itemref", "itemRef"),
+    ("itemscope", "itemScope"),
+    ("itemtype", "itemType"),
+    ("maxlength", "maxLength"),
+    ("minlength", "minLength"),
+    ("nomodule", "noModule"),
+    ("novalidate", "noValidate"),
+    ("playsinline", "playsInline"),
+    ("readonly", "readOnly"),
+    ("referrerpolicy", "referrerPolicy"),
+    ("rowspan", "rowSpan"),
+    ("spellcheck", "spellCheck"),
+    ("srcdoc", "srcDoc"),
+    ("srclang", "srcLang"),
+    ("srcset", "srcSet"),
+    ("tabindex", "tabIndex"),
+    ("usemap", "useMap"),
+];
+
+/// Properties that are `true` when the attribute is present.
+const BOOLEAN: &[&str] = &[
+    "allowFullScreen",
+    "async",
+    "autoFocus",
+    "autoPlay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formNoValidate",
+    "hidden",
+    "inert",
+    "isMap",
+    "itemScope",
+    "loop",
+    "multiple",
+    "muted",
+    "noModule",
+    "noValidate",
+    "open",
+    "playsInline",
+    "readOnly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// Properties holding a space separated list of tokens.
+const SPACE_SEPARATED: &[&str] = &[
+    "accessKey",
+    "className",
+    "headers",
+    "itemProp",
+    "itemRef",
+    "itemType",
+    "ping",
+    "rel",
+    "sandbox",
+];
+
+/// Properties holding a comma separated list of tokens.
+const COMMA_SEPARATED: &[&str] = &["accept"];
+
+/// Properties holding a number.
+const NUMBER: &[&str] = &[
+    "colSpan",
+    "cols",
+    "height",
+    "maxLength",
+    "minLength",
+    "rowSpan",
+    "rows",
+    "size",
+    "span",
+    "start",
+    "tabIndex",
+    "width",
+];
+
+/// How a property's value is represented in hast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    String,
+    Boolean,
+    Number,
+    SpaceSeparated,
+    CommaSeparated,
+}
+
+pub fn kind(property: &str) -> Kind {
+    if BOOLEAN.contains(&property) {
+        Kind::Boolean
+    } else if NUMBER.contains(&property) {
+        Kind::Number
+    } else if SPACE_SEPARATED.contains(&property) {
+        Kind::SpaceSeparated
+    } else if COMMA_SEPARATED.contains(&property) {
+        Kind::CommaSeparated
+    } else {
+        Kind::String
+    }
+}
+
+/// Converts an attribute name such as `data-user-id` to its property name
+/// (`dataUserId`). Only HTML attribute names are case-insensitive.
+pub fn property_name(attribute: &str, namespace: Namespace) -> String {
+    let attribute = match namespace {
+        Namespace::Html => attribute.to_ascii_lowercase(),
+        Namespace::Svg | Namespace::MathMl => attribute.to_string(),
+    };
+    if let Some((_, property)) = RENAMED.iter().find(|(name, _)| *name == attribute) {
+        return property.to_string();
+    }
+    for prefix in ["data-", "aria-"] {
+        if let Some(rest) = attribute.strip_prefix(prefix) {
+            if rest.is_empty() {
+                break;
+            }
+            let mut property = prefix[..4].to_string();
+            for part in rest.split('-') {
+                let mut chars = part.chars();
+                if let Some(first) = chars.next() {
+                    property.push(first.to_ascii_uppercase());
+                    property.extend(chars);
+                } else {
+                    property.push('-');
+                }
+            }
+            return property;
+        }
+    }
+    attribute
+}
+
+/// Converts a property name back to the attribute name it came from.
+pub fn attribute_name(property: &str) -> String {
+    if let Some((attribute, _)) = RENAMED.iter().find(|(_, name)| *name == property) {
+        return attribute.to_string();
+    }
+    for prefix in ["data", "aria"] {
+        if let Some(rest) = property.strip_prefix(prefix) {
+            if rest.starts_with(|c: char| c.is_ascii_uppercase()) {
+                let mut attribute = prefix.to_string();
+                for c in rest.chars() {
+                    if c.is_ascii_uppercase() {
+                        attribute.push('-');
+                        attribute.push(c.to_ascii_lowercase());
+                    } else {
+                        attribute.push(c);
+                    }
+                }
+                return attribute;
+            }
+        }
+    }
+    property.to_string()
+}
@@ -0,0 +1,139 @@
+use ast::Namespace;
+use hast::{from_hast, to_hast, HastError};
+use parser::parse_html;
+use serde_json::json;
+
+#[test]
+fn exports_elements_with_properties_and_positions() {
+    let document = parse_html(
+        "<label for=\"name\" class=\"big  bold\" data-user-id=\"7\">Hi</label><input tabindex=\"2\" disabled/>",
+    );
+    let tree = to_hast(&document);
+
+    assert_eq!(tree["type"], "root");
+    let label = &tree["children"][0];
+    assert_eq!(label["type"], "element");
+    assert_eq!(label["tagName"], "label");
+    assert_eq!(
+        label["properties"],
+        json!({
+            "htmlFor": "name",
+            "className": ["big", "bold"],
+            "dataUserId": "7",
+        })
+    );
+    assert_eq!(
+        label["children"][0],
+        json!({
+            "type": "text",
+            "value": "Hi",
+            "position": {
+                "start": { "line": 1, "column": 54, "offset": 53 },
+                "end": { "line": 1, "column": 56, "offset": 55 },
+            },
+        })
+    );
+    assert_eq!(
+        label["position"]["start"],
+        json!({ "line": 1, "column": 1, "offset": 0 })
+    );
+
+    let input = &tree["children"][1];
+    assert_eq!(
+        input["properties"],
+        json!({ "tabIndex": 2, "disabled": true })
+    );
+}
+
+#[test]
+fn imports_hast() {
+    let document = from_hast(&json!({
+        "type": "root",
+        "children": [{
+            "type": "element",
+            "tagName": "a",
+            "properties": {
+                "href": "/",
+                "className": ["nav", "active"],
+                "ariaLabel": "Home",
+                "hidden": true,
+                "draggable": null,
+                "rowSpan": 2,
+            },
+            "children": [{ "type": "text", "value": "Home" }],
+            "position": {
+                "start": { "line": 2, "column": 3, "offset": 10 },
+                "end": { "line": 2, "column": 40, "offset": 47 },
+            },
+        }],
+    }))
+    .unwrap();
+
+    let link = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(link.tag_name(), "a");
+    assert_eq!(link.get_attribute("href"), Some("/"));
+    assert_eq!(link.get_attribute("class"), Some("nav active"));
+    assert_eq!(link.get_attribute("aria-label"), Some("Home"));
+    assert_eq!(link.get_attribute("hidden"), Some(""));
+    assert_eq!(link.get_attribute("rowspan"), Some("2"));
+    assert_eq!(link.get_attribute("draggable"), None);
+    assert_eq!(link.text_content(), "Home");
+    assert_eq!(link.span().unwrap().start.line, 2);
+}
+
+#[test]
+fn round_trips_through_hast() {
+    let document = parse_html(
+        "<div id=\"x\" class=\"a b\"><p data-foo-bar=\"1\" hidden>Text</p><img src=\"a.png\" width=\"10\"/></div>",
+    );
+    let tree = to_hast(&document);
+    let restored = from_hast(&tree).unwrap();
+    assert_eq!(to_hast(&restored), tree);
+}
+
+#[test]
+fn foreign_elements_keep_their_namespace() {
+    let document = parse_html(
+        "<div><svg viewBox=\"0 0 1 1\"><foreignObject><p>x</p></foreignObject></svg>\
+         <math><mi>y</mi></math></div>",
+    );
+    let tree = to_hast(&document);
+    let svg = &tree["children"][0]["children"][0];
+    assert_eq!(tree["children"][0].get("space"), None);
+    assert_eq!(svg["space"], "svg");
+    assert_eq!(svg["properties"], json!({ "viewBox": "0 0 1 1" }));
+    assert_eq!(svg["children"][0]["children"][0].get("space"), None);
+    assert_eq!(tree["children"][0]["children"][1]["space"], "mathml");
+
+    let restored = from_hast(&tree).unwrap();
+    assert_eq!(to_hast(&restored), tree);
+    let svg = restored.root().descendants().nth(1).unwrap();
+    let svg = svg.as_element().unwrap();
+    assert_eq!(svg.namespace(), Namespace::Svg);
+    assert_eq!(svg.get_attribute("viewBox"), Some("0 0 1 1"));
+}
+
+#[test]
+fn rejects_invalid_trees() {
+    assert_eq!(
+        from_hast(&json!({ "type": "element" })).unwrap_err(),
+        HastError::ExpectedRoot
+    );
+    assert_eq!(
+        from_hast(&json!({ "type": "root", "children": [{ "type": "raw", "value": "<b>" }] }))
+            .unwrap_err(),
+        HastError::UnsupportedNode("raw".to_string())
+    );
+    assert_eq!(
+        from_hast(&json!({ "type": "root", "children": [{ "type": "element" }] })).unwrap_err(),
+        HastError::InvalidField("tagName")
+    );
+    assert_eq!(
+        from_hast(&json!({
+            "type": "root",
+            "children": [{ "type": "element", "tagName": "svg", "space": "xml" }]
+        }))
+        .unwrap_err(),
+        HastError::InvalidField("space")
+    );
+}
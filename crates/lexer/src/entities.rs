@@ -0,0 +1,80 @@
+//! Character reference decoding.
+
+mod named;
+
+use crate::ErrorCode;
+
+use named::NAMED;
+
+/// The length of the longest reference name, including its `;`.
+pub const LONGEST_NAME: usize = 32;
+
+/// Replacements for numeric references in the C1 control range, which
+/// browsers interpret as windows-1252.
+const C1_REPLACEMENTS: &[(u32, char)] = &[
+    (0x80, '€'),
+    (0x82, '‚'),
+    (0x83, 'ƒ'),
+    (0x84, '„'),
+    (0x85, '…'),
+    (0x86, '†'),
+    (0x87, '‡'),
+    (0x88, 'ˆ'),
+    (0x89, '‰'),
+    (0x8A, 'Š'),
+    (0x8B, '‹'),
+    (0x8C, 'Œ'),
+    (0x8E, 'Ž'),
+    (0x91, '‘'),
+    (0x92, '’'),
+    (0x93, '“'),
+    (0x94, '”'),
+    (0x95, '•'),
+    (0x96, '–'),
+    (0x97, '—'),
+    (0x98, '˜'),
+    (0x99, '™'),
+    (0x9A, 'š'),
+    (0x9B, '›'),
+    (0x9C, 'œ'),
+    (0x9E, 'ž'),
+    (0x9F, 'Ÿ'),
+];
+
+/// Looks up a named character reference by its name, including the `;` if
+/// it is written with one. Some references stand for two characters.
+pub fn named(name: &str) -> Option<&'static str> {
+    NAMED
+        .binary_search_by(|(entity, _)| entity.cmp(&name))
+        .ok()
+        .map(|index| NAMED[index].1)
+}
+
+/// Maps the value of a numeric character reference to the character it
+/// stands for.
+pub fn numeric(value: u32) -> char {
+    if let Some((_, ch)) = C1_REPLACEMENTS.iter().find(|(code, _)| *code == value) {
+        return *ch;
+    }
+    match value {
+        0 => '\u{fffd}',
+        value => char::from_u32(value).unwrap_or('\u{fffd}'),
+    }
+}
+
+/// The parse error for a numeric character reference to `value`, if it names
+/// a character that shouldn't be written that way.
+pub fn numeric_error(value: u32) -> Option<ErrorCode> {
+    match value {
+        0 => Some(ErrorCode::NullCharacterReference),
+        0x11_0000.. => Some(ErrorCode::CharacterReferenceOutsideUnicodeRange),
+        0xd800..=0xdfff => Some(ErrorCode::SurrogateCharacterReference),
+        0xfdd0..=0xfdef => Some(ErrorCode::NoncharacterCharacterReference),
+        value if value & 0xfffe == 0xfffe => Some(ErrorCode::NoncharacterCharacterReference),
+        0x0d | 0x7f..=0x9f => Some(ErrorCode::ControlCharacterReference),
+        0x01..=0x1f if !matches!(value, 0x09 | 0x0a | 0x0c) => {
+            Some(ErrorCode::ControlCharacterReference)
+        }
+        _ => None,
+    }
+}
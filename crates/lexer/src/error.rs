@@ -0,0 +1,184 @@
+//! Parse error codes.
+
+use std::fmt;
+
+use crate::Location;
+
+/// A parse error found while tokenizing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub code: ErrorCode,
+    /// Where the offending input starts.
+    pub start: Location,
+    /// Where the offending input ends, the same as `start` at the end of the
+    /// source.
+    pub end: Location,
+}
+
+/// How much a parse error matters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Content that is kept, but not as written.
+    Warning,
+    /// Markup that breaks the rules of HTML.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal,)*) => {
+        /// Identifies a kind of parse error.
+        ///
+        /// The tokenizer errors have the codes given to them by the HTML
+        /// specification. The specification doesn't name tree construction
+        /// errors, so those, and the errors for exceeded parser limits, have
+        /// codes of their own in the same style.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl ErrorCode {
+            /// The code as written in the specification, e.g.
+            /// `missing-whitespace-between-attributes`.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+
+            /// Looks up an error by its code.
+            pub fn from_code(code: &str) -> Option<ErrorCode> {
+                match code {
+                    $($code => Some(ErrorCode::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    AbruptClosingOfEmptyComment => "abrupt-closing-of-empty-comment",
+    AbruptDoctypePublicIdentifier => "abrupt-doctype-public-identifier",
+    AbruptDoctypeSystemIdentifier => "abrupt-doctype-system-identifier",
+    AbsenceOfDigitsInNumericCharacterReference => "absence-of-digits-in-numeric-character-reference",
+    CdataInHtmlContent => "cdata-in-html-content",
+    CharacterReferenceOutsideUnicodeRange => "character-reference-outside-unicode-range",
+    ControlCharacterInInputStream => "control-character-in-input-stream",
+    ControlCharacterReference => "control-character-reference",
+    DuplicateAttribute => "duplicate-attribute",
+    EndTagWithAttributes => "end-tag-with-attributes",
+    EndTagWithTrailingSolidus => "end-tag-with-trailing-solidus",
+    EofBeforeTagName => "eof-before-tag-name",
+    EofInCdata => "eof-in-cdata",
+    EofInComment => "eof-in-comment",
+    EofInDoctype => "eof-in-doctype",
+    EofInScriptHtmlCommentLikeText => "eof-in-script-html-comment-like-text",
+    EofInTag => "eof-in-tag",
+    IncorrectlyClosedComment => "incorrectly-closed-comment",
+    IncorrectlyOpenedComment => "incorrectly-opened-comment",
+    InvalidCharacterSequenceAfterDoctypeName => "invalid-character-sequence-after-doctype-name",
+    InvalidFirstCharacterOfTagName => "invalid-first-character-of-tag-name",
+    MissingAttributeValue => "missing-attribute-value",
+    MissingDoctypeName => "missing-doctype-name",
+    MissingDoctypePublicIdentifier => "missing-doctype-public-identifier",
+    MissingDoctypeSystemIdentifier => "missing-doctype-system-identifier",
+    MissingEndTagName => "missing-end-tag-name",
+    MissingQuoteBeforeDoctypePublicIdentifier => "missing-quote-before-doctype-public-identifier",
+    MissingQuoteBeforeDoctypeSystemIdentifier => "missing-quote-before-doctype-system-identifier",
+    MissingSemicolonAfterCharacterReference => "missing-semicolon-after-character-reference",
+    MissingWhitespaceAfterDoctypePublicKeyword => "missing-whitespace-after-doctype-public-keyword",
+    MissingWhitespaceAfterDoctypeSystemKeyword => "missing-whitespace-after-doctype-system-keyword",
+    MissingWhitespaceBeforeDoctypeName => "missing-whitespace-before-doctype-name",
+    MissingWhitespaceBetweenAttributes => "missing-whitespace-between-attributes",
+    MissingWhitespaceBetweenDoctypePublicAndSystemIdentifiers => "missing-whitespace-between-doctype-public-and-system-identifiers",
+    NestedComment => "nested-comment",
+    NoncharacterCharacterReference => "noncharacter-character-reference",
+    NoncharacterInInputStream => "noncharacter-in-input-stream",
+    NonVoidHtmlElementStartTagWithTrailingSolidus => "non-void-html-element-start-tag-with-trailing-solidus",
+    NullCharacterReference => "null-character-reference",
+    SurrogateCharacterReference => "surrogate-character-reference",
+    SurrogateInInputStream => "surrogate-in-input-stream",
+    UnexpectedCharacterAfterDoctypeSystemIdentifier => "unexpected-character-after-doctype-system-identifier",
+    UnexpectedCharacterInAttributeName => "unexpected-character-in-attribute-name",
+    UnexpectedCharacterInUnquotedAttributeValue => "unexpected-character-in-unquoted-attribute-value",
+    UnexpectedEqualsSignBeforeAttributeName => "unexpected-equals-sign-before-attribute-name",
+    UnexpectedNullCharacter => "unexpected-null-character",
+    UnexpectedQuestionMarkInsteadOfTagName => "unexpected-question-mark-instead-of-tag-name",
+    UnexpectedSolidusInTag => "unexpected-solidus-in-tag",
+    UnknownNamedCharacterReference => "unknown-named-character-reference",
+
+    /// Content came before any doctype.
+    MissingDoctype => "missing-doctype",
+    /// A doctype other than `<!DOCTYPE html>`.
+    NonConformingDoctype => "non-conforming-doctype",
+    /// A doctype after the start of the document.
+    UnexpectedDoctype => "unexpected-doctype",
+    /// An end tag without a matching open element.
+    UnexpectedEndTag => "unexpected-end-tag",
+    /// An element closed by something other than its end tag, where that end
+    /// tag can't be left out.
+    UnclosedElement => "unclosed-element",
+    /// An HTML start tag that closed the SVG or MathML content it was in.
+    UnexpectedHtmlInForeignContent => "unexpected-html-in-foreign-content",
+
+    /// An element past the parser's maximum depth.
+    NestingTooDeep => "nesting-too-deep",
+    /// Attributes past the parser's maximum per element.
+    TooManyAttributes => "too-many-attributes",
+    /// An attribute name or value past the parser's maximum length.
+    AttributeTooLong => "attribute-too-long",
+    /// Text or a comment past the parser's maximum length.
+    TextTooLong => "text-too-long",
+    /// A node past the parser's maximum number of nodes.
+    TooManyNodes => "too-many-nodes",
+    /// Source past the parser's maximum size.
+    SourceTooLong => "source-too-long",
+}
+
+impl ErrorCode {
+    /// Exceeded limits are warnings, since the document is still read as far
+    /// as the limits allow. Everything else is an error.
+    pub fn severity(self) -> Severity {
+        match self {
+            ErrorCode::NestingTooDeep
+            | ErrorCode::TooManyAttributes
+            | ErrorCode::AttributeTooLong
+            | ErrorCode::TextTooLong
+            | ErrorCode::TooManyNodes
+            | ErrorCode::SourceTooLong => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// A short description, which is the code written out in words.
+    pub fn message(self) -> String {
+        let words: Vec<_> = self
+            .as_str()
+            .split('-')
+            .map(|word| match word {
+                "eof" => "end of file",
+                "cdata" => "CDATA",
+                "html" => "HTML",
+                word => word,
+            })
+            .collect();
+        words.join(" ")
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
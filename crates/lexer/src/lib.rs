@@ -1,10 +1,25 @@
+mod entities;
+mod error;
 mod token;
 
+use std::collections::HashSet;
+
+pub use error::{Error, ErrorCode, Severity};
 pub use token::*;
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// Record parse errors, to be collected with [`HtmlLexer::take_errors`].
+    /// Errors are always recovered from; without this they are dropped.
+    pub collect_errors: bool,
+}
+
 #[derive(Debug)]
 pub enum State {
     Data,
+    RcData,
+    RawText,
+    ScriptData,
     PlainText,
     TagOpen,
     TagName,
@@ -18,10 +33,25 @@ pub enum State {
     AttributeValueUnquoted,
     AfterAttributeValueQuoted,
     SelfClosingStartTag,
+    BogusComment,
+    Comment,
+    Doctype,
+    CdataSection,
 }
 
 pub const EOF_CHAR: char = '\0';
 
+/// A point in the source text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The byte offset from the start of the source.
+    pub offset: usize,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column, counted in characters.
+    pub column: usize,
+}
+
 pub struct HtmlLexer {
     // The list of characters
     chars: Vec<(usize, char)>,
@@ -39,10 +69,39 @@ pub struct HtmlLexer {
     current_token: Option<Token>,
 
     reconsume_char: bool,
+
+    // The name of the last start tag emitted, which ends raw text
+    last_start_tag: String,
+
+    // Characters collected for tokens that are built after the fact
+    temporary_buffer: String,
+
+    // The location of the next character to consume
+    location: Location,
+
+    // The location of the current character
+    current_char_start: Location,
+
+    // Where the most recently emitted token started
+    token_start: Location,
+
+    // Tokens to emit before reading further, last one first
+    pending_tokens: Vec<Token>,
+
+    options: LexerOptions,
+
+    // Whether `<![CDATA[` starts a CDATA section
+    allow_cdata: bool,
+
+    errors: Vec<Error>,
 }
 
 impl HtmlLexer {
     pub fn new(source: &str) -> HtmlLexer {
+        HtmlLexer::with_options(source, LexerOptions::default())
+    }
+
+    pub fn with_options(source: &str, options: LexerOptions) -> HtmlLexer {
         HtmlLexer {
             chars: source.char_indices().collect(),
             index: 0,
@@ -50,20 +109,116 @@ impl HtmlLexer {
             current_token: None,
             state: State::Data,
             reconsume_char: false,
+            last_start_tag: String::new(),
+            temporary_buffer: String::new(),
+            current_char_start: Location {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            location: Location {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            token_start: Location {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            pending_tokens: Vec::new(),
+            options,
+            allow_cdata: false,
+            errors: Vec::new(),
         }
     }
 
+    /// Returns the errors recorded since the last call, if errors are being
+    /// collected.
+    pub fn take_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Where the most recently emitted token started.
+    pub fn token_start(&self) -> Location {
+        self.token_start
+    }
+
+    /// Where the most recently emitted token ended, which is also where the
+    /// next one will start.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Switches the tokenizer state. The tree builder uses this to read the
+    /// contents of elements such as `script` and `textarea` as text.
+    pub fn set_state(&mut self, state: State) {
+        self.switch_to(state);
+    }
+
+    /// Sets whether `<![CDATA[` starts a CDATA section, whose contents are
+    /// read as text. That is only the case inside SVG and MathML elements;
+    /// elsewhere it starts a bogus comment.
+    pub fn set_allow_cdata(&mut self, allow_cdata: bool) {
+        self.allow_cdata = allow_cdata;
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Token {
+        if let Some(token) = self.pending_tokens.pop() {
+            return token;
+        }
+        self.token_start = if self.reconsume_char {
+            self.current_char_start
+        } else {
+            self.location
+        };
         loop {
             match self.state {
                 State::Data => {
                     let ch = self.consume_next();
                     match ch {
                         '<' => self.switch_to(State::TagOpen),
+                        '&' => return self.emit_character_reference(),
+                        EOF_CHAR => return self.emit_eof(),
+                        _ => return self.emit_char(),
+                    }
+                }
+                State::RcData => {
+                    let ch = self.consume_next();
+                    match ch {
+                        '<' if self.at_appropriate_end_tag() => {
+                            self.switch_to(State::EndTagOpen);
+                        }
+                        '&' => return self.emit_character_reference(),
+                        EOF_CHAR => return self.emit_eof(),
+                        _ => return self.emit_char(),
+                    }
+                }
+                State::RawText | State::ScriptData => {
+                    let ch = self.consume_next();
+                    match ch {
+                        '<' if self.at_appropriate_end_tag() => {
+                            self.switch_to(State::EndTagOpen);
+                        }
                         EOF_CHAR => return self.emit_eof(),
                         _ => return self.emit_char(),
                     }
                 }
+                State::CdataSection => {
+                    let ch = self.consume_next();
+                    match ch {
+                        ']' if self.lookahead("]>", false) => {
+                            self.skip(2);
+                            self.switch_to(State::Data);
+                        }
+                        EOF_CHAR => {
+                            self.error(ErrorCode::EofInCdata);
+                            return self.emit_eof();
+                        }
+                        _ => return self.emit_char(),
+                    }
+                }
                 State::PlainText => {
                     let ch = self.consume_next();
                     match ch {
@@ -74,15 +229,31 @@ impl HtmlLexer {
                 State::TagOpen => {
                     let ch = self.consume_next();
                     match ch {
-                        '!' => todo!(),
-                        '?' => todo!(),
+                        '!' => {
+                            if let Some(token) = self.markup_declaration_open() {
+                                return token;
+                            }
+                        }
+                        '?' => {
+                            self.error(ErrorCode::UnexpectedQuestionMarkInsteadOfTagName);
+                            self.current_token = Some(Token::Comment(String::new()));
+                            self.reconsume_in(State::BogusComment);
+                        }
                         '/' => self.switch_to(State::EndTagOpen),
                         c if c.is_alphabetic() => {
                             self.current_token = Some(Token::new_start_tag());
                             self.reconsume_in(State::TagName);
                         }
-                        EOF_CHAR => panic!("Invalid tag name"),
-                        _ => panic!("Invalid tag name"),
+                        EOF_CHAR => {
+                            self.error(ErrorCode::EofBeforeTagName);
+                            self.reconsume_in(State::Data);
+                            return Token::Character('<');
+                        }
+                        _ => {
+                            self.error(ErrorCode::InvalidFirstCharacterOfTagName);
+                            self.reconsume_in(State::Data);
+                            return Token::Character('<');
+                        }
                     }
                 }
                 State::EndTagOpen => {
@@ -92,9 +263,21 @@ impl HtmlLexer {
                             self.current_token = Some(Token::new_end_tag());
                             self.reconsume_in(State::TagName);
                         }
-                        '>' => panic!("Missing end tag name"),
-                        EOF_CHAR => panic!("Invalid EOF before tag name"),
-                        _ => panic!("Invalid first character of tag name"),
+                        '>' => {
+                            self.error(ErrorCode::MissingEndTagName);
+                            self.switch_to(State::Data);
+                        }
+                        EOF_CHAR => {
+                            self.error(ErrorCode::EofBeforeTagName);
+                            self.reconsume_in(State::Data);
+                            self.pending_tokens.push(Token::Character('/'));
+                            return Token::Character('<');
+                        }
+                        _ => {
+                            self.error(ErrorCode::InvalidFirstCharacterOfTagName);
+                            self.current_token = Some(Token::Comment(String::new()));
+                            self.reconsume_in(State::BogusComment);
+                        }
                     }
                 }
                 State::TagName => {
@@ -111,7 +294,7 @@ impl HtmlLexer {
                         c if c.is_ascii_uppercase() => {
                             self.append_character_to_tag_name(c.to_ascii_lowercase());
                         }
-                        EOF_CHAR => panic!("EOF in tag"),
+                        EOF_CHAR => return self.eof_in_tag(),
                         c => self.append_character_to_tag_name(c),
                     }
                 }
@@ -119,9 +302,14 @@ impl HtmlLexer {
                     let ch = self.consume_next();
                     match ch {
                         c if c.is_whitespace() => {}
-                        '/' => self.reconsume_in(State::AfterAttributeName),
-                        '=' => panic!("unexpected-equals-sign-before-attribute-name"),
+                        '/' | '>' => self.reconsume_in(State::AfterAttributeName),
                         EOF_CHAR => self.reconsume_in(State::AfterAttributeName),
+                        '=' => {
+                            self.error(ErrorCode::UnexpectedEqualsSignBeforeAttributeName);
+                            self.new_attribute(Attribute::new());
+                            self.append_character_to_attribute_name('=');
+                            self.switch_to(State::AttributeName);
+                        }
                         _ => {
                             let attribute = Attribute::new();
                             self.new_attribute(attribute);
@@ -133,10 +321,13 @@ impl HtmlLexer {
                     let ch = self.consume_next();
                     match ch {
                         c if c.is_whitespace() => self.reconsume_in(State::AfterAttributeName),
-                        '/' | '>' => self.reconsume_in(State::AfterAttributeName),
+                        '/' | '>' | EOF_CHAR => self.reconsume_in(State::AfterAttributeName),
                         '=' => self.switch_to(State::BeforeAttributeValue),
-                        '"' | '\'' | '<' => panic!("unexpected-character-in-attribute-name"),
-                        c => self.append_character_to_attribute_name(c),
+                        c @ ('"' | '\'' | '<') => {
+                            self.error(ErrorCode::UnexpectedCharacterInAttributeName);
+                            self.append_character_to_attribute_name(c);
+                        }
+                        c => self.append_character_to_attribute_name(c.to_ascii_lowercase()),
                     }
                 }
                 State::BeforeAttributeValue => {
@@ -145,7 +336,11 @@ impl HtmlLexer {
                         c if c.is_whitespace() => {}
                         '"' => self.switch_to(State::AttributeValueDoubleQuoted),
                         '\'' => self.switch_to(State::AttributeValueSingleQuoted),
-                        '>' => panic!("missing-attribute-value"),
+                        '>' => {
+                            self.error(ErrorCode::MissingAttributeValue);
+                            self.switch_to(State::Data);
+                            return self.emit_current_token();
+                        }
                         _ => self.reconsume_in(State::AttributeValueUnquoted),
                     }
                 }
@@ -160,7 +355,7 @@ impl HtmlLexer {
                             self.switch_to(State::Data);
                             return self.emit_current_token();
                         }
-                        EOF_CHAR => panic!("eof-in-tag"),
+                        EOF_CHAR => return self.eof_in_tag(),
                         _ => {
                             let attribute = Attribute::new();
                             self.new_attribute(attribute);
@@ -184,16 +379,19 @@ impl HtmlLexer {
                             self.switch_to(State::Data);
                             return self.emit_current_token();
                         }
-                        EOF_CHAR => panic!("eof-in-tag"),
-                        _ => panic!("unexpected-solidus-in-tag"),
+                        EOF_CHAR => return self.eof_in_tag(),
+                        _ => {
+                            self.error(ErrorCode::UnexpectedSolidusInTag);
+                            self.reconsume_in(State::BeforeAttributeName);
+                        }
                     }
                 }
                 State::AttributeValueDoubleQuoted => {
                     let ch = self.consume_next();
                     match ch {
                         '"' => self.switch_to(State::AfterAttributeValueQuoted),
-                        '&' => todo!(),
-                        EOF_CHAR => panic!("eof-in-tag"),
+                        '&' => self.append_character_reference_to_attribute_value(),
+                        EOF_CHAR => return self.eof_in_tag(),
                         c => self.append_character_to_attribute_value(c),
                     }
                 }
@@ -201,8 +399,8 @@ impl HtmlLexer {
                     let ch = self.consume_next();
                     match ch {
                         '\'' => self.switch_to(State::AfterAttributeValueQuoted),
-                        '&' => todo!(),
-                        EOF_CHAR => panic!("eof-in-tag"),
+                        '&' => self.append_character_reference_to_attribute_value(),
+                        EOF_CHAR => return self.eof_in_tag(),
                         c => self.append_character_to_attribute_value(c),
                     }
                 }
@@ -214,10 +412,12 @@ impl HtmlLexer {
                             self.switch_to(State::Data);
                             return self.emit_current_token();
                         }
-                        '"' | '\'' | '<' | '=' | '`' => {
-                            panic!("unexpected-character-in-unquoted-attribute-value");
+                        '&' => self.append_character_reference_to_attribute_value(),
+                        c @ ('"' | '\'' | '<' | '=' | '`') => {
+                            self.error(ErrorCode::UnexpectedCharacterInUnquotedAttributeValue);
+                            self.append_character_to_attribute_value(c);
                         }
-                        EOF_CHAR => panic!("eof-in-tag"),
+                        EOF_CHAR => return self.eof_in_tag(),
                         c => self.append_character_to_attribute_value(c),
                     }
                 }
@@ -230,12 +430,273 @@ impl HtmlLexer {
                             self.switch_to(State::Data);
                             return self.emit_current_token();
                         }
-                        EOF_CHAR => panic!("eof-in-tag"),
-                        _ => panic!("missing-whitespace-between-attributes"),
+                        EOF_CHAR => return self.eof_in_tag(),
+                        _ => {
+                            self.error(ErrorCode::MissingWhitespaceBetweenAttributes);
+                            self.reconsume_in(State::BeforeAttributeName);
+                        }
                     }
                 }
+                State::BogusComment => {
+                    let ch = self.consume_next();
+                    match ch {
+                        '>' => {
+                            self.switch_to(State::Data);
+                            return self.emit_current_token();
+                        }
+                        EOF_CHAR => {
+                            self.reconsume_in(State::Data);
+                            return self.emit_current_token();
+                        }
+                        c => self.append_character_to_comment(c),
+                    }
+                }
+                State::Comment => {
+                    let ch = self.consume_next();
+                    match ch {
+                        '>' if self.comment_ends_with("--") => {
+                            self.truncate_comment(2);
+                            self.switch_to(State::Data);
+                            return self.emit_current_token();
+                        }
+                        '>' if self.comment_ends_with("--!") => {
+                            self.error(ErrorCode::IncorrectlyClosedComment);
+                            self.truncate_comment(3);
+                            self.switch_to(State::Data);
+                            return self.emit_current_token();
+                        }
+                        EOF_CHAR => {
+                            self.error(ErrorCode::EofInComment);
+                            self.reconsume_in(State::Data);
+                            return self.emit_current_token();
+                        }
+                        c => {
+                            self.append_character_to_comment(c);
+                            if self.comment_ends_with("<!--") {
+                                self.error(ErrorCode::NestedComment);
+                            }
+                        }
+                    }
+                }
+                State::Doctype => {
+                    let ch = self.consume_next();
+                    match ch {
+                        '>' => {
+                            self.switch_to(State::Data);
+                            return self.emit_doctype();
+                        }
+                        EOF_CHAR => {
+                            self.error(ErrorCode::EofInDoctype);
+                            self.reconsume_in(State::Data);
+                            let mut doctype = self.emit_doctype();
+                            if let Token::Doctype { force_quirks, .. } = &mut doctype {
+                                *force_quirks = true;
+                            }
+                            return doctype;
+                        }
+                        c => self.temporary_buffer.push(c),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handles `<!`, which starts a comment, a doctype or a bogus comment.
+    /// Returns the token right away for comments that are already complete.
+    fn markup_declaration_open(&mut self) -> Option<Token> {
+        if self.lookahead("--", false) {
+            self.skip(2);
+            // `<!-->` and `<!--->` are complete, empty comments.
+            for ending in [">", "->"] {
+                if self.lookahead(ending, false) {
+                    self.error(ErrorCode::AbruptClosingOfEmptyComment);
+                    self.skip(ending.len());
+                    self.switch_to(State::Data);
+                    return Some(Token::Comment(String::new()));
+                }
             }
+            self.current_token = Some(Token::Comment(String::new()));
+            self.switch_to(State::Comment);
+        } else if self.lookahead("doctype", true) {
+            self.skip(7);
+            self.temporary_buffer.clear();
+            self.switch_to(State::Doctype);
+        } else if self.allow_cdata && self.lookahead("[CDATA[", false) {
+            self.skip(7);
+            self.switch_to(State::CdataSection);
+        } else if self.lookahead("[CDATA[", false) {
+            // The comment keeps the `[CDATA[`.
+            self.error(ErrorCode::CdataInHtmlContent);
+            self.current_token = Some(Token::Comment(String::new()));
+            self.switch_to(State::BogusComment);
+        } else {
+            self.error(ErrorCode::IncorrectlyOpenedComment);
+            self.current_token = Some(Token::Comment(String::new()));
+            self.switch_to(State::BogusComment);
+        }
+        None
+    }
+
+    /// Returns true if the upcoming characters close the element whose
+    /// contents are being read as text, e.g. `</script>` after `<script>`.
+    /// Consumes the `/` if so.
+    fn at_appropriate_end_tag(&mut self) -> bool {
+        if self.last_start_tag.is_empty() || !self.lookahead("/", false) {
+            return false;
+        }
+        let name_start = self.index + 1;
+        let name_end = name_start + self.last_start_tag.chars().count();
+        let name_matches = self
+            .last_start_tag
+            .chars()
+            .enumerate()
+            .all(|(i, expected)| {
+                self.chars
+                    .get(name_start + i)
+                    .is_some_and(|(_, ch)| ch.to_ascii_lowercase() == expected)
+            });
+        let terminated = matches!(
+            self.chars.get(name_end).map(|(_, ch)| *ch),
+            Some(c) if c.is_whitespace() || c == '/' || c == '>'
+        );
+        if name_matches && terminated {
+            self.skip(1);
+        }
+        name_matches && terminated
+    }
+
+    /// Returns true if the characters after the current one are `expected`.
+    fn lookahead(&self, expected: &str, ignore_case: bool) -> bool {
+        expected
+            .chars()
+            .enumerate()
+            .all(|(i, expected)| match self.chars.get(self.index + i) {
+                Some((_, ch)) => {
+                    *ch == expected || ignore_case && ch.eq_ignore_ascii_case(&expected)
+                }
+                None => false,
+            })
+    }
+
+    fn skip(&mut self, count: usize) {
+        for _ in 0..count {
+            self.consume_next();
+        }
+    }
+
+    /// Decodes the character reference after a `&`, consuming it if it is
+    /// recognised. Named references are matched against the longest name
+    /// they start with, which for a few legacy names needn't end in `;`;
+    /// unknown references are left alone. Errors span the reference from
+    /// its `&`.
+    fn consume_character_reference(&mut self, in_attribute: bool) -> Option<String> {
+        let start = self.current_char_start;
+        let peek = |offset: usize| self.chars.get(self.index + offset).map(|(_, ch)| *ch);
+
+        if peek(0) == Some('#') {
+            let hex = matches!(peek(1), Some('x') | Some('X'));
+            let digits_start = if hex { 2 } else { 1 };
+            let radix = if hex { 16 } else { 10 };
+            let mut length = 0;
+            while peek(digits_start + length).is_some_and(|c| c.is_digit(radix)) {
+                length += 1;
+            }
+            if length == 0 {
+                self.error_from(start, ErrorCode::AbsenceOfDigitsInNumericCharacterReference);
+                return None;
+            }
+            let digits: String = (0..length)
+                .map(|i| peek(digits_start + i).unwrap())
+                .collect();
+            let value = u32::from_str_radix(&digits, radix).unwrap_or(u32::MAX);
+            let semicolon = peek(digits_start + length) == Some(';');
+            self.skip(digits_start + length + usize::from(semicolon));
+            if !semicolon {
+                self.error_from(start, ErrorCode::MissingSemicolonAfterCharacterReference);
+            }
+            if let Some(code) = entities::numeric_error(value) {
+                self.error_from(start, code);
+            }
+            return Some(entities::numeric(value).to_string());
+        }
+
+        let mut length = 0;
+        while length < entities::LONGEST_NAME
+            && peek(length).is_some_and(|c| c.is_ascii_alphanumeric())
+        {
+            length += 1;
+        }
+        let mut name: String = (0..length).map(|i| peek(i).unwrap()).collect();
+        if peek(length) == Some(';') {
+            name.push(';');
+        }
+        // Only names without a `;` can be a prefix of what follows.
+        let found = (1..=name.len())
+            .rev()
+            .find_map(|end| entities::named(&name[..end]).map(|value| (end, value)));
+        let Some((end, value)) = found else {
+            if name.len() > 1 && name.ends_with(';') {
+                self.error_from(start, ErrorCode::UnknownNamedCharacterReference);
+            }
+            return None;
+        };
+        let semicolon = name[..end].ends_with(';');
+        // Attribute values such as `?a=1&copy=2` in URLs are kept as written.
+        if !semicolon
+            && in_attribute
+            && peek(end).is_some_and(|c| c == '=' || c.is_ascii_alphanumeric())
+        {
+            return None;
+        }
+        self.skip(end);
+        if !semicolon {
+            self.error_from(start, ErrorCode::MissingSemicolonAfterCharacterReference);
         }
+        Some(value.to_string())
+    }
+
+    fn emit_character_reference(&mut self) -> Token {
+        let Some(value) = self.consume_character_reference(false) else {
+            return Token::Character('&');
+        };
+        let mut chars = value.chars();
+        let first = chars.next().unwrap();
+        self.pending_tokens
+            .extend(chars.rev().map(Token::Character));
+        Token::Character(first)
+    }
+
+    fn append_character_reference_to_attribute_value(&mut self) {
+        let value = self
+            .consume_character_reference(true)
+            .unwrap_or_else(|| "&".to_string());
+        for ch in value.chars() {
+            self.append_character_to_attribute_value(ch);
+        }
+    }
+
+    fn append_character_to_comment(&mut self, ch: char) {
+        if let Some(Token::Comment(data)) = self.current_token.as_mut() {
+            data.push(ch);
+        }
+    }
+
+    fn comment_ends_with(&self, suffix: &str) -> bool {
+        matches!(&self.current_token, Some(Token::Comment(data)) if data.ends_with(suffix))
+    }
+
+    fn truncate_comment(&mut self, count: usize) {
+        if let Some(Token::Comment(data)) = self.current_token.as_mut() {
+            data.truncate(data.len() - count);
+        }
+    }
+
+    fn emit_doctype(&mut self) -> Token {
+        let doctype = parse_doctype(&self.temporary_buffer);
+        if matches!(&doctype, Token::Doctype { name, .. } if name.is_empty()) {
+            self.error(ErrorCode::MissingDoctypeName);
+        }
+        doctype
     }
 
     fn new_attribute(&mut self, attribute: Attribute) {
@@ -279,8 +740,62 @@ impl HtmlLexer {
         }
     }
 
-    fn emit_current_token(&self) -> Token {
-        self.current_token.clone().unwrap()
+    fn emit_current_token(&mut self) -> Token {
+        let mut token = self.current_token.take().unwrap();
+        if let Token::Tag {
+            tag_name,
+            attributes,
+            is_end_tag,
+            self_closing,
+        } = &mut token
+        {
+            // Later duplicates of an attribute are dropped.
+            if attributes.len() > 1 {
+                let mut seen = HashSet::new();
+                let keep: Vec<bool> = attributes
+                    .iter()
+                    .map(|attribute| seen.insert(attribute.name.as_str()))
+                    .collect();
+                if keep.contains(&false) {
+                    let mut keep = keep.into_iter();
+                    attributes.retain(|_| keep.next().unwrap());
+                    self.error(ErrorCode::DuplicateAttribute);
+                }
+            }
+            if *is_end_tag {
+                if !attributes.is_empty() {
+                    self.error(ErrorCode::EndTagWithAttributes);
+                }
+                if *self_closing {
+                    self.error(ErrorCode::EndTagWithTrailingSolidus);
+                }
+            } else {
+                self.last_start_tag = tag_name.clone();
+            }
+        }
+        token
+    }
+
+    /// Handles the end of the input inside a tag, which is dropped.
+    fn eof_in_tag(&mut self) -> Token {
+        self.error(ErrorCode::EofInTag);
+        self.emit_eof()
+    }
+
+    /// Records an error at the current character.
+    fn error(&mut self, code: ErrorCode) {
+        self.error_from(self.current_char_start, code);
+    }
+
+    /// Records an error spanning from `start` to the current character.
+    fn error_from(&mut self, start: Location, code: ErrorCode) {
+        if self.options.collect_errors {
+            self.errors.push(Error {
+                code,
+                start,
+                end: self.location,
+            });
+        }
     }
 
     fn reconsume_in(&mut self, state: State) {
@@ -310,6 +825,19 @@ impl HtmlLexer {
                 .get(self.index)
                 .map(|(_, ch)| *ch)
                 .unwrap_or(EOF_CHAR);
+            self.current_char_start = self.location;
+            let ch = if self.index < self.chars.len() {
+                self.advance_location(ch);
+                // A NUL in the source would otherwise read as the end of it.
+                if ch == EOF_CHAR {
+                    self.error(ErrorCode::UnexpectedNullCharacter);
+                    '\u{fffd}'
+                } else {
+                    ch
+                }
+            } else {
+                ch
+            };
             self.index += 1;
             ch
         };
@@ -317,4 +845,64 @@ impl HtmlLexer {
         self.current_char = ch;
         ch
     }
+
+    fn advance_location(&mut self, ch: char) {
+        self.location.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+    }
+}
+
+/// Splits the contents of a doctype, everything between `<!DOCTYPE` and `>`,
+/// into its name and identifiers.
+fn parse_doctype(contents: &str) -> Token {
+    let contents = contents.trim_start();
+    let name_end = contents
+        .find(|c: char| c.is_whitespace())
+        .unwrap_or(contents.len());
+    let name = contents[..name_end].to_ascii_lowercase();
+    let rest = contents[name_end..].trim_start();
+
+    let (public_id, system_id) = if let Some(rest) = strip_keyword(rest, "public") {
+        let (public_id, rest) = quoted(rest);
+        (public_id, quoted(rest).0)
+    } else if let Some(rest) = strip_keyword(rest, "system") {
+        (None, quoted(rest).0)
+    } else {
+        (None, None)
+    };
+
+    Token::Doctype {
+        force_quirks: name.is_empty(),
+        name,
+        public_id,
+        system_id,
+    }
+}
+
+/// Returns the input after `keyword` if it starts with it, ignoring ASCII
+/// case.
+fn strip_keyword<'a>(input: &'a str, keyword: &str) -> Option<&'a str> {
+    input
+        .get(..keyword.len())
+        .filter(|start| start.eq_ignore_ascii_case(keyword))
+        .map(|_| &input[keyword.len()..])
+}
+
+/// Reads a single or double quoted string at the start of `input`, returning
+/// it and the input after it.
+fn quoted(input: &str) -> (Option<String>, &str) {
+    let input = input.trim_start();
+    let quote = match input.chars().next() {
+        Some(quote @ ('"' | '\'')) => quote,
+        _ => return (None, input),
+    };
+    match input[1..].find(quote) {
+        Some(end) => (Some(input[1..end + 1].to_string()), &input[end + 2..]),
+        None => (Some(input[1..].to_string()), ""),
+    }
 }
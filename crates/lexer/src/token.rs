@@ -1,4 +1,4 @@
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
 pub struct Attribute {
     pub name: String,
     pub value: String,
@@ -19,7 +19,14 @@ impl Attribute {
 
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum Token {
-    Doctype,
+    Doctype {
+        name: String,
+        public_id: Option<String>,
+        system_id: Option<String>,
+        /// Set for doctypes so broken that the document has to be rendered in
+        /// quirks mode whatever they say.
+        force_quirks: bool,
+    },
     Tag {
         tag_name: String,
         self_closing: bool,
@@ -27,6 +34,7 @@ pub enum Token {
         is_end_tag: bool,
     },
     Character(char),
+    Comment(String),
     EOF,
 }
 
@@ -50,10 +58,7 @@ impl Token {
     }
 
     pub fn is_tag(&self) -> bool {
-        match self {
-            Token::Tag { .. } => true,
-            _ => false,
-        }
+        matches!(self, Token::Tag { .. })
     }
 
     pub fn is_start_tag(&self) -> bool {
@@ -82,7 +87,7 @@ impl Token {
 
     pub fn attributes(&self) -> &Vec<Attribute> {
         if let Token::Tag { attributes, .. } = self {
-            &attributes
+            attributes
         } else {
             panic!("Token is not a tag");
         }
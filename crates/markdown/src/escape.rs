@@ -0,0 +1,110 @@
+/// Whether `text` starts with something markdown would read as a character
+/// reference, such as `&amp;` or `&#123;`.
+fn starts_with_reference(text: &str) -> bool {
+    let rest = match text.strip_prefix('&') {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let name_length = rest
+        .find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '#')
+        .unwrap_or(rest.len());
+    name_length > 0 && rest[name_length..].starts_with(';')
+}
+
+/// Escapes text so that markdown reads it back literally. `line_start` says
+/// whether the text begins a line, where more characters start blocks.
+pub(crate) fn escape_text(text: &str, line_start: bool, gfm: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    if line_start {
+        if text.starts_with(['#', '>', '-', '+', '=']) {
+            output.push('\\');
+        } else {
+            // An ordered list marker, such as `1.` or `2)`.
+            let digits = text.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(0);
+            if (1..10).contains(&digits) && text[digits..].starts_with(['.', ')']) {
+                output.push_str(&text[..digits]);
+                output.push('\\');
+                return output + &escape_text(&text[digits..], false, gfm);
+            }
+        }
+    }
+
+    let mut previous = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let escape = match ch {
+            '\\' | '`' | '*' | '[' | ']' | '<' => true,
+            '~' | '|' => gfm,
+            '&' => starts_with_reference(&text[index..]),
+            // Underscores inside words never start emphasis.
+            '_' => {
+                let next = chars.peek().map(|&(_, ch)| ch);
+                !(is_word(previous) && is_word(next))
+            }
+            _ => false,
+        };
+        if escape {
+            output.push('\\');
+        }
+        output.push(ch);
+        previous = Some(ch);
+    }
+    output
+}
+
+fn is_word(ch: Option<char>) -> bool {
+    ch.is_some_and(char::is_alphanumeric)
+}
+
+/// Writes a link destination, in angle brackets when it has characters that
+/// would end it early.
+pub(crate) fn write_destination(url: &str, output: &mut String) {
+    let bracketed =
+        url.contains(|ch: char| ch.is_ascii_whitespace() || matches!(ch, '(' | ')' | '<' | '>'));
+    if bracketed {
+        output.push('<');
+    }
+    for (index, ch) in url.char_indices() {
+        match ch {
+            '\\' | '<' | '>' => output.push('\\'),
+            '&' if starts_with_reference(&url[index..]) => output.push('\\'),
+            _ => {}
+        }
+        output.push(ch);
+    }
+    if bracketed {
+        output.push('>');
+    }
+}
+
+/// Writes the optional title of a link or image.
+pub(crate) fn write_title(title: Option<&str>, output: &mut String) {
+    if let Some(title) = title {
+        output.push_str(" \"");
+        for (index, ch) in title.char_indices() {
+            match ch {
+                '\\' | '"' => output.push('\\'),
+                '&' if starts_with_reference(&title[index..]) => output.push('\\'),
+                _ => {}
+            }
+            output.push(ch);
+        }
+        output.push('"');
+    }
+}
+
+/// The shortest run of backticks that can delimit `text`: one longer than
+/// the longest run inside it.
+pub(crate) fn backtick_fence(text: &str, minimum: usize) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for ch in text.chars() {
+        if ch == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    "`".repeat(minimum.max(longest + 1))
+}
@@ -0,0 +1,745 @@
+//! Converts HTML documents to CommonMark, with GitHub Flavored Markdown
+//! extensions.
+//!
+//! Headings, paragraphs, emphasis, links, images, lists, block quotes, code
+//! blocks, rules and line breaks become their markdown forms; tables,
+//! strikethrough and task list items do too when GFM is enabled. Markdown
+//! can hold HTML, so anything it has no syntax for is written as HTML:
+//! inline elements such as `sup` keep their tags around converted contents,
+//! and block elements such as `dl` are written out whole. Elements that
+//! aren't rendered, such as `script` and `head`, are left out.
+
+mod escape;
+mod table;
+
+use ast::{elements, walk, Document, Element, Node, Text, Visit, Visitor};
+use escape::{backtick_fence, escape_text, write_destination, write_title};
+use parser::parse_html;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// Use the GitHub Flavored Markdown extensions: tables, strikethrough
+    /// and task list items. Without them, those are written as HTML.
+    pub gfm: bool,
+    /// The marker for bullet list items: `-`, `*` or `+`.
+    pub bullet: char,
+    /// The delimiter for emphasis: `*` or `_`. Strong emphasis doubles it.
+    pub emphasis: char,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            gfm: true,
+            bullet: '-',
+            emphasis: '*',
+        }
+    }
+}
+
+/// Parses `source` and converts it to markdown.
+pub fn html_to_markdown(source: &str, options: &MarkdownOptions) -> String {
+    to_markdown(&parse_html(source), options)
+}
+
+/// Converts a whole document to markdown.
+pub fn to_markdown(document: &Document, options: &MarkdownOptions) -> String {
+    node_to_markdown(document.root(), options)
+}
+
+/// Converts a node and its descendants to markdown.
+pub fn node_to_markdown(node: Node, options: &MarkdownOptions) -> String {
+    let mut converter = Converter::new(options);
+    walk(node, &mut converter);
+    converter.flush();
+    converter.output
+}
+
+/// Converts the children of a node that holds only inline content, such as
+/// a table cell. Line breaks are left as a `\` at the end of a line.
+pub(crate) fn inline_markdown(node: Node, options: &MarkdownOptions) -> String {
+    let mut converter = Converter::new(options);
+    for child in node.children() {
+        walk(child, &mut converter);
+    }
+    while converter.in_inline() {
+        converter.close_inline();
+    }
+    converter.inlines.pop().unwrap().text
+}
+
+/// Elements that aren't rendered, so they have no markdown.
+const HIDDEN: &[&str] = &[
+    "base", "head", "link", "meta", "noscript", "script", "style", "template", "title",
+];
+
+/// Block elements that only group their contents. Their children are
+/// converted as if they weren't there.
+const TRANSPARENT: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "body",
+    "center",
+    "div",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "header",
+    "hgroup",
+    "html",
+    "main",
+    "nav",
+    "search",
+    "section",
+];
+
+/// Inline elements whose contents aren't text in the document flow, so
+/// they are written out whole as HTML.
+const OPAQUE: &[&str] = &[
+    "audio", "button", "canvas", "iframe", "map", "math", "object", "picture", "select", "svg",
+    "textarea", "video",
+];
+
+/// Something that lines are nested inside, which puts a prefix in front of
+/// each of them.
+enum Container {
+    Quote,
+    Item {
+        marker: String,
+        /// The marker hasn't been written yet.
+        open: bool,
+        tight: bool,
+    },
+}
+
+struct List {
+    ordered: bool,
+    next: u64,
+    tight: bool,
+    marker: char,
+    /// No item has been started yet.
+    first: bool,
+}
+
+/// Inline content that is wrapped in markup once it is complete.
+enum Wrap {
+    /// The leaf block the inline content belongs to.
+    Block,
+    Emphasis(String),
+    Link {
+        href: String,
+        title: Option<String>,
+    },
+    Html(String),
+}
+
+struct Inline {
+    wrap: Wrap,
+    text: String,
+}
+
+struct Converter<'o> {
+    options: &'o MarkdownOptions,
+    output: String,
+    containers: Vec<Container>,
+    lists: Vec<List>,
+    inlines: Vec<Inline>,
+    /// Whether the next block needs a blank line in front of it.
+    blank: bool,
+    /// The level of the heading being collected, if any.
+    heading: Option<usize>,
+    /// The container depth and marker of a list that ended just before the
+    /// current position. A list right after it needs another marker, or the
+    /// two would be read as one.
+    previous_list: Option<(usize, char)>,
+}
+
+impl<'o> Converter<'o> {
+    fn new(options: &'o MarkdownOptions) -> Converter<'o> {
+        Converter {
+            options,
+            output: String::new(),
+            containers: Vec::new(),
+            lists: Vec::new(),
+            inlines: vec![Inline {
+                wrap: Wrap::Block,
+                text: String::new(),
+            }],
+            blank: false,
+            heading: None,
+            previous_list: None,
+        }
+    }
+
+    /// Whether inline markup is open, in which case block elements can't
+    /// start new blocks.
+    fn in_inline(&self) -> bool {
+        self.inlines.len() > 1
+    }
+
+    fn current(&mut self) -> &mut String {
+        &mut self.inlines.last_mut().unwrap().text
+    }
+
+    /// Whether text written now would start a line.
+    fn at_line_start(&self) -> bool {
+        !self.in_inline() && {
+            let text = &self.inlines[0].text;
+            text.is_empty() || text.ends_with('\n')
+        }
+    }
+
+    /// Appends inline markdown, keeping a single space between words.
+    fn push_inline(&mut self, mut text: &str) {
+        let line_start = self.at_line_start();
+        let current = self.current();
+        if line_start || current.ends_with(' ') || current.ends_with('\n') {
+            text = text.trim_start_matches(' ');
+        }
+        current.push_str(text);
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let mut collapsed = String::with_capacity(text.len());
+        let mut space = false;
+        for ch in text.chars() {
+            if ch.is_ascii_whitespace() {
+                space = true;
+                continue;
+            }
+            if space {
+                collapsed.push(' ');
+                space = false;
+            }
+            collapsed.push(ch);
+        }
+        if space {
+            collapsed.push(' ');
+        }
+
+        let line_start = self.at_line_start();
+        let text = if line_start {
+            collapsed.trim_start_matches(' ')
+        } else {
+            &collapsed
+        };
+        let escaped = escape_text(text, line_start, self.options.gfm);
+        self.push_inline(&escaped);
+    }
+
+    fn open_inline(&mut self, wrap: Wrap) {
+        self.inlines.push(Inline {
+            wrap,
+            text: String::new(),
+        });
+    }
+
+    fn close_inline(&mut self) {
+        let Inline { wrap, text } = self.inlines.pop().unwrap();
+        let wrapped = match wrap {
+            Wrap::Block => unreachable!("the block is never closed"),
+            Wrap::Emphasis(delimiter) => {
+                // Delimiters next to whitespace don't count as emphasis.
+                outside_spaces(&text, |text| format!("{delimiter}{text}{delimiter}"))
+            }
+            Wrap::Link { href, title } => {
+                let link = |text: &str| {
+                    let mut link = format!("[{}](", text);
+                    write_destination(&href, &mut link);
+                    write_title(title.as_deref(), &mut link);
+                    link.push(')');
+                    link
+                };
+                match text.trim_matches(' ') {
+                    "" => link(""),
+                    _ => outside_spaces(&text, link),
+                }
+            }
+            Wrap::Html(end) => text + &end,
+        };
+        self.push_inline(&wrapped);
+    }
+
+    /// Underscores can't delimit emphasis inside a word, so emphasis right
+    /// next to letters or digits uses asterisks instead.
+    fn use_intraword_delimiter(&mut self, element: Element) {
+        let is_word = |ch: Option<char>| ch.is_some_and(char::is_alphanumeric);
+        let before = self.inlines[self.inlines.len() - 2]
+            .text
+            .chars()
+            .next_back();
+        let after = element
+            .next_sibling()
+            .and_then(|next| next.as_text())
+            .and_then(|text| text.text().chars().next());
+        if let Some(Inline {
+            wrap: Wrap::Emphasis(delimiter),
+            ..
+        }) = self.inlines.last_mut()
+        {
+            if delimiter.starts_with('_') && (is_word(before) || is_word(after)) {
+                *delimiter = delimiter.replace('_', "*");
+            }
+        }
+    }
+
+    /// Writes the collected inline content as a paragraph or heading.
+    fn flush(&mut self) {
+        while self.in_inline() {
+            self.close_inline();
+        }
+        let text = std::mem::take(&mut self.inlines[0].text);
+        let mut lines: Vec<&str> = text
+            .split('\n')
+            .map(|line| line.trim_matches(' '))
+            .collect();
+        // Every line but the last ends in a hard break. One at the very end
+        // has no effect.
+        while lines.len() > 1 && lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+            let last = lines.last_mut().unwrap();
+            *last = last.strip_suffix('\\').unwrap_or(last).trim_end();
+        }
+        match self.heading {
+            Some(level) => {
+                // Headings can't hold line breaks.
+                let last = lines.len() - 1;
+                let content: Vec<&str> = lines
+                    .iter()
+                    .enumerate()
+                    .map(|(index, line)| match index < last {
+                        true => line.strip_suffix('\\').unwrap_or(line).trim_end(),
+                        false => line,
+                    })
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                let heading = format!("{} {}", "#".repeat(level), content.join(" "));
+                self.write_block(&[heading.trim_end()]);
+            }
+            None => {
+                if lines.iter().any(|line| !line.is_empty()) {
+                    self.write_block(&lines);
+                }
+            }
+        }
+    }
+
+    fn write_block(&mut self, lines: &[&str]) {
+        if self.blank && !self.output.is_empty() {
+            self.write_line("", false);
+        }
+        for line in lines {
+            self.write_line(line, false);
+        }
+        self.end_block();
+    }
+
+    /// Decides how the next block is separated from the one just written.
+    fn end_block(&mut self) {
+        self.blank = !matches!(
+            self.containers.last(),
+            Some(Container::Item { tight: true, .. })
+        );
+        self.previous_list = None;
+    }
+
+    /// Writes a line with the prefixes of the containers it is in. An item's
+    /// marker goes on its first line that isn't blank, or on a blank line if
+    /// `marker` is set.
+    fn write_line(&mut self, line: &str, marker: bool) {
+        let start = self.output.len();
+        for container in &mut self.containers {
+            match container {
+                Container::Quote => self.output.push_str("> "),
+                Container::Item {
+                    marker: text, open, ..
+                } => {
+                    if *open && (marker || !line.is_empty()) {
+                        self.output.push_str(text);
+                        *open = false;
+                    } else {
+                        self.output.extend(std::iter::repeat_n(' ', text.len()));
+                    }
+                }
+            }
+        }
+        self.output.push_str(line);
+        if line.is_empty() {
+            let trimmed = self.output[start..].trim_end().len();
+            self.output.truncate(start + trimmed);
+        }
+        self.output.push('\n');
+    }
+
+    fn enter_list(&mut self, element: Element) {
+        let ordered = element.tag_name() == "ol";
+        let (primary, alternate) = if ordered {
+            ('.', ')')
+        } else {
+            let alternate = if self.options.bullet == '-' { '*' } else { '-' };
+            (self.options.bullet, alternate)
+        };
+        let marker = match self.previous_list {
+            Some((depth, marker)) if depth == self.containers.len() && marker == primary => {
+                alternate
+            }
+            _ => primary,
+        };
+        let tight = !element.children().any(|item| {
+            item.as_element()
+                .is_some_and(|item| item.tag_name() == "li")
+                && item.children().any(|child| {
+                    child
+                        .as_element()
+                        .is_some_and(|child| child.tag_name() == "p")
+                })
+        });
+        let next = element
+            .get_attribute("start")
+            .and_then(|start| start.trim().parse().ok())
+            .unwrap_or(1);
+        self.lists.push(List {
+            ordered,
+            next,
+            tight,
+            marker,
+            first: true,
+        });
+    }
+
+    fn leave_list(&mut self) {
+        let list = self.lists.pop().unwrap();
+        self.end_block();
+        self.previous_list = Some((self.containers.len(), list.marker));
+    }
+
+    fn enter_item(&mut self, element: Element) {
+        let list = self.lists.last_mut().unwrap();
+        let marker = if list.ordered {
+            let number = list.next;
+            list.next += 1;
+            format!("{}{} ", number, list.marker)
+        } else {
+            format!("{} ", list.marker)
+        };
+        if !list.first {
+            self.blank = !list.tight;
+        }
+        list.first = false;
+        let tight = list.tight;
+        self.containers.push(Container::Item {
+            marker,
+            open: true,
+            tight,
+        });
+
+        if self.options.gfm {
+            if let Some(checkbox) = task_checkbox(element) {
+                let task = if checkbox.get_attribute("checked").is_some() {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+                self.current().push_str(task);
+            }
+        }
+    }
+
+    fn leave_item(&mut self) {
+        if let Some(Container::Item { open: true, .. }) = self.containers.last() {
+            // An empty item still needs its marker.
+            if self.blank && !self.output.is_empty() {
+                self.write_line("", false);
+            }
+            self.write_line("", true);
+        }
+        self.containers.pop();
+        self.end_block();
+    }
+
+    fn write_code_block(&mut self, element: Element) {
+        let code = element
+            .children()
+            .filter_map(|child| child.as_element())
+            .find(|child| child.tag_name() == "code");
+        let language = code
+            .into_iter()
+            .chain(Some(element))
+            .flat_map(|element| element.class_list())
+            .find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+            })
+            .filter(|language| !language.contains('`'))
+            .unwrap_or("");
+        let content = element.text_content();
+        let content = content.strip_suffix('\n').unwrap_or(&content);
+        let fence = backtick_fence(content, 3);
+        let opening = format!("{}{}", fence, language);
+        let mut lines = vec![opening.as_str()];
+        if !content.is_empty() {
+            lines.extend(content.split('\n'));
+        }
+        lines.push(&fence);
+        self.write_block(&lines);
+    }
+
+    fn write_html_block(&mut self, node: Node) {
+        let html = serializer::outer_html(node);
+        let lines: Vec<&str> = html.lines().collect();
+        self.write_block(&lines);
+    }
+
+    fn write_code_span(&mut self, element: Element) {
+        let content = element.text_content().replace(['\n', '\r'], " ");
+        if content.is_empty() {
+            return;
+        }
+        let fence = backtick_fence(&content, 1);
+        let padded = content.starts_with('`')
+            || content.ends_with('`')
+            || (content.starts_with(' ') && content.ends_with(' ') && content.trim() != "");
+        let space = if padded { " " } else { "" };
+        let span = format!("{fence}{space}{content}{space}{fence}");
+        self.push_inline(&span);
+    }
+
+    fn write_image(&mut self, element: Element) {
+        let alt = element.get_attribute("alt").unwrap_or_default();
+        let mut image = format!("![{}](", escape_text(alt, false, self.options.gfm));
+        write_destination(element.get_attribute("src").unwrap_or_default(), &mut image);
+        write_title(element.get_attribute("title"), &mut image);
+        image.push(')');
+        self.push_inline(&image);
+    }
+
+    fn write_inline_html(&mut self, element: Element) {
+        let mut html = String::new();
+        serializer::write_start_tag(element, &mut html);
+        self.push_inline(&html);
+    }
+}
+
+/// Moves spaces at the edges of `text` outside the markup `wrap` puts
+/// around it. Markup around nothing but spaces is left out.
+fn outside_spaces(text: &str, wrap: impl FnOnce(&str) -> String) -> String {
+    let trimmed = text.trim_matches(' ');
+    if trimmed.is_empty() {
+        return text.chars().take(1).collect();
+    }
+    let leading = if text.starts_with(' ') { " " } else { "" };
+    let trailing = if text.ends_with(' ') { " " } else { "" };
+    format!("{}{}{}", leading, wrap(trimmed), trailing)
+}
+
+/// The checkbox that makes a list item a task list item: an `input` of type
+/// `checkbox` before any other content.
+fn task_checkbox<'a>(item: Element<'a>) -> Option<Element<'a>> {
+    let first = item.children().find(|child| {
+        !child
+            .as_text()
+            .is_some_and(|text| text.text().trim().is_empty())
+    })?;
+    first.as_element().filter(|input| {
+        input.tag_name() == "input"
+            && input
+                .get_attribute("type")
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("checkbox"))
+    })
+}
+
+fn is_list(node: Option<Node>) -> bool {
+    node.and_then(|node| node.as_element())
+        .is_some_and(|element| matches!(element.tag_name(), "ul" | "ol"))
+}
+
+fn heading_level(name: &str) -> Option<usize> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+impl<'a> Visitor<'a> for Converter<'_> {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        let name = element.tag_name();
+        if HIDDEN.contains(&name) {
+            return Visit::SkipChildren;
+        }
+
+        // Inline elements.
+        match name {
+            "br" => {
+                if !self.at_line_start() {
+                    self.current().push_str("\\\n");
+                }
+                return Visit::Continue;
+            }
+            "em" | "i" => {
+                self.open_inline(Wrap::Emphasis(self.options.emphasis.to_string()));
+                return Visit::Continue;
+            }
+            "strong" | "b" => {
+                self.open_inline(Wrap::Emphasis(self.options.emphasis.to_string().repeat(2)));
+                return Visit::Continue;
+            }
+            "del" | "s" | "strike" if self.options.gfm => {
+                self.open_inline(Wrap::Emphasis("~~".to_string()));
+                return Visit::Continue;
+            }
+            "a" if element.get_attribute("href").is_some() => {
+                self.open_inline(Wrap::Link {
+                    href: element.get_attribute("href").unwrap().to_string(),
+                    title: element.get_attribute("title").map(str::to_string),
+                });
+                return Visit::Continue;
+            }
+            "code" => {
+                self.write_code_span(element);
+                return Visit::SkipChildren;
+            }
+            "img" => {
+                self.write_image(element);
+                return Visit::SkipChildren;
+            }
+            "input"
+                if self.options.gfm
+                    && element
+                        .parent()
+                        .and_then(|parent| parent.as_element())
+                        .filter(|parent| parent.tag_name() == "li" && is_list(parent.parent()))
+                        .and_then(task_checkbox)
+                        == Some(element) =>
+            {
+                // Written as a task list marker when the item was entered.
+                return Visit::SkipChildren;
+            }
+            "span" => return Visit::Continue,
+            _ => {}
+        }
+
+        if self.in_inline() || !elements::is_block(name) {
+            if elements::is_block(name) {
+                // Blocks inside inline markup can only be run in.
+                self.push_inline(" ");
+                return Visit::Continue;
+            }
+            if elements::is_void(name)
+                || OPAQUE.contains(&name)
+                || elements::is_raw_text(name)
+                || elements::is_escapable_raw_text(name)
+            {
+                self.push_inline(&serializer::outer_html(element.as_node()));
+                return Visit::SkipChildren;
+            }
+            self.write_inline_html(element);
+            self.open_inline(Wrap::Html(format!("</{}>", name)));
+            return Visit::Continue;
+        }
+
+        // Block elements.
+        self.flush();
+        if let Some(level) = heading_level(name) {
+            self.heading = Some(level);
+            return Visit::Continue;
+        }
+        match name {
+            "p" => Visit::Continue,
+            "blockquote" => {
+                self.containers.push(Container::Quote);
+                Visit::Continue
+            }
+            "ul" | "ol" => {
+                self.enter_list(element);
+                Visit::Continue
+            }
+            "li" if is_list(element.parent()) => {
+                self.enter_item(element);
+                Visit::Continue
+            }
+            "pre" | "listing" => {
+                self.write_code_block(element);
+                Visit::SkipChildren
+            }
+            "hr" => {
+                self.write_block(&["***"]);
+                Visit::SkipChildren
+            }
+            "table" => {
+                let table = match self.options.gfm {
+                    true => table::to_markdown(element, self.options),
+                    false => None,
+                };
+                match table {
+                    Some(table) => {
+                        let lines: Vec<&str> = table.lines().collect();
+                        self.write_block(&lines);
+                    }
+                    None => self.write_html_block(element.as_node()),
+                }
+                Visit::SkipChildren
+            }
+            name if TRANSPARENT.contains(&name) => Visit::Continue,
+            _ => {
+                self.write_html_block(element.as_node());
+                Visit::SkipChildren
+            }
+        }
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        let name = element.tag_name();
+        if HIDDEN.contains(&name) {
+            return Visit::Continue;
+        }
+        if self.in_inline() {
+            let closes = match &self.inlines.last().unwrap().wrap {
+                Wrap::Emphasis(_) => {
+                    matches!(name, "em" | "i" | "strong" | "b" | "del" | "s" | "strike")
+                }
+                Wrap::Link { .. } => name == "a",
+                Wrap::Html(end) => end[2..end.len() - 1] == *name,
+                Wrap::Block => false,
+            };
+            if closes {
+                self.use_intraword_delimiter(element);
+                self.close_inline();
+            } else if elements::is_block(name) {
+                self.push_inline(" ");
+            }
+            return Visit::Continue;
+        }
+        if !elements::is_block(name) || matches!(name, "pre" | "listing" | "hr" | "table") {
+            return Visit::Continue;
+        }
+
+        self.flush();
+        if heading_level(name).is_some() {
+            self.heading = None;
+            return Visit::Continue;
+        }
+        match name {
+            "blockquote" => {
+                self.containers.pop();
+                self.end_block();
+            }
+            "ul" | "ol" => self.leave_list(),
+            "li" if is_list(element.parent()) => self.leave_item(),
+            _ => {}
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        self.push_text(text.text());
+        Visit::Continue
+    }
+}
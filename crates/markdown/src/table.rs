@@ -0,0 +1,151 @@
+use crate::{inline_markdown, MarkdownOptions};
+use ast::{elements, Element, Node};
+
+fn is_named<'a>(node: Node<'a>, names: &[&str]) -> Option<Element<'a>> {
+    node.as_element()
+        .filter(|element| names.contains(&element.tag_name()))
+}
+
+fn rows<'a>(table: Element<'a>) -> Vec<Element<'a>> {
+    let mut rows = Vec::new();
+    for child in table.children() {
+        if let Some(row) = is_named(child, &["tr"]) {
+            rows.push(row);
+        } else if let Some(group) = is_named(child, &["thead", "tbody", "tfoot"]) {
+            rows.extend(group.children().filter_map(|row| is_named(row, &["tr"])));
+        }
+    }
+    rows
+}
+
+fn alignment(cell: Element) -> &'static str {
+    let style = cell.get_attribute("style").unwrap_or_default();
+    let align = cell.get_attribute("align").or_else(|| {
+        style.split(';').find_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            (property.trim().eq_ignore_ascii_case("text-align")).then(|| value.trim())
+        })
+    });
+    match align.map(|align| align.to_ascii_lowercase()).as_deref() {
+        Some("left") => ":---",
+        Some("center") => ":---:",
+        Some("right") => "---:",
+        _ => "---",
+    }
+}
+
+/// Escapes the pipes in a cell that aren't escaped already, such as those in
+/// code spans, so that they don't end the cell.
+fn escape_pipes(cell: &str) -> String {
+    let mut output = String::with_capacity(cell.len());
+    let mut backslashes = 0;
+    for ch in cell.chars() {
+        if ch == '|' && backslashes % 2 == 0 {
+            output.push('\\');
+        }
+        backslashes = if ch == '\\' { backslashes + 1 } else { 0 };
+        output.push(ch);
+    }
+    output
+}
+
+fn convert_cell(cell: Element, options: &MarkdownOptions) -> String {
+    let markdown = inline_markdown(cell.as_node(), options);
+    let markdown = markdown
+        .split('\n')
+        .map(|line| line.trim())
+        .collect::<Vec<_>>();
+    // Hard breaks become `<br>`, since cells are a single line.
+    let last = markdown.len() - 1;
+    let lines: Vec<&str> = markdown
+        .iter()
+        .enumerate()
+        .map(|(index, line)| match index < last {
+            true => line.strip_suffix('\\').unwrap_or(line),
+            false => line,
+        })
+        .collect();
+    escape_pipes(lines.join("<br>").trim())
+}
+
+/// Converts a table to a GFM table. Tables that GFM can't express, because
+/// they have no header row, a caption, cells spanning several rows or
+/// columns, or blocks inside cells, give `None`.
+pub(crate) fn to_markdown(table: Element, options: &MarkdownOptions) -> Option<String> {
+    if table
+        .children()
+        .any(|child| is_named(child, &["caption"]).is_some())
+    {
+        return None;
+    }
+    let rows = rows(table);
+    let header = *rows.first()?;
+    let in_head = header
+        .parent()
+        .and_then(|parent| is_named(parent, &["thead"]))
+        .is_some();
+
+    let mut cells: Vec<Vec<Element>> = Vec::new();
+    for row in &rows {
+        let row_cells: Vec<Element> = row
+            .children()
+            .filter_map(|cell| is_named(cell, &["td", "th"]))
+            .collect();
+        for cell in &row_cells {
+            let spans = ["colspan", "rowspan"].iter().any(|name| {
+                cell.get_attribute(name)
+                    .is_some_and(|span| span.trim().parse::<u32>().map_or(true, |span| span > 1))
+            });
+            let has_blocks = cell.descendants().any(|node| {
+                node.as_element()
+                    .is_some_and(|element| elements::is_block(element.tag_name()))
+            });
+            if spans || has_blocks {
+                return None;
+            }
+        }
+        cells.push(row_cells);
+    }
+    let header_cells = &cells[0];
+    if header_cells.is_empty()
+        || !in_head && header_cells.iter().any(|cell| cell.tag_name() != "th")
+    {
+        return None;
+    }
+
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let mut output = String::new();
+    let mut write_row = |values: Vec<String>| {
+        output.push('|');
+        for index in 0..columns {
+            output.push(' ');
+            output.push_str(values.get(index).map(String::as_str).unwrap_or(""));
+            output.push_str(" |");
+        }
+        output.push('\n');
+    };
+    write_row(
+        header_cells
+            .iter()
+            .map(|&cell| convert_cell(cell, options))
+            .collect(),
+    );
+    write_row(
+        (0..columns)
+            .map(|index| {
+                header_cells
+                    .get(index)
+                    .map_or("---", |&cell| alignment(cell))
+                    .to_string()
+            })
+            .collect(),
+    );
+    for row in &cells[1..] {
+        write_row(
+            row.iter()
+                .map(|&cell| convert_cell(cell, options))
+                .collect(),
+        );
+    }
+    Some(output)
+}
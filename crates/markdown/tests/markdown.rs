@@ -0,0 +1,144 @@
+use markdown::{html_to_markdown, node_to_markdown, MarkdownOptions};
+use parser::parse_html;
+
+fn convert(source: &str) -> String {
+    html_to_markdown(source, &MarkdownOptions::default())
+}
+
+#[test]
+fn converts_headings_and_paragraphs() {
+    assert_eq!(
+        convert("<h1>Title</h1><p>Some <em>text</em>, <strong>bold</strong> text.</p><h3>Sub</h3>"),
+        "# Title\n\nSome *text*, **bold** text.\n\n### Sub\n"
+    );
+}
+
+#[test]
+fn skips_elements_that_are_not_rendered() {
+    let source = "<html><head><title>T</title><style>p {}</style></head><body>\
+                  <div><p>a</p><script>x()</script><div>b</div></div></body></html>";
+    assert_eq!(convert(source), "a\n\nb\n");
+}
+
+#[test]
+fn converts_links_and_images() {
+    assert_eq!(
+        convert("<p><a href=\"https://x.example/a b\" title=\"T\">link</a> <img src=\"i.png\" alt=\"An *image*\"></p>"),
+        "[link](<https://x.example/a b> \"T\") ![An \\*image\\*](i.png)\n"
+    );
+}
+
+#[test]
+fn converts_nested_and_ordered_lists() {
+    assert_eq!(
+        convert("<ol start=\"3\"><li>three<ul><li>nested</li></ul></li><li>four</li></ol>"),
+        "3. three\n   - nested\n4. four\n"
+    );
+    assert_eq!(
+        convert("<ul><li><p>loose</p></li><li><p>items</p></li></ul>"),
+        "- loose\n\n- items\n"
+    );
+}
+
+#[test]
+fn separates_adjacent_lists() {
+    assert_eq!(
+        convert("<ul><li>a</li></ul><ul><li>b</li></ul>"),
+        "- a\n\n* b\n"
+    );
+}
+
+#[test]
+fn converts_block_quotes() {
+    assert_eq!(
+        convert("<blockquote><p>quoted</p><ul><li>item</li></ul></blockquote>"),
+        "> quoted\n>\n> - item\n"
+    );
+}
+
+#[test]
+fn takes_code_block_languages_from_classes() {
+    assert_eq!(
+        convert("<pre><code class=\"hl language-rust\">fn main() {}\n</code></pre>"),
+        "```rust\nfn main() {}\n```\n"
+    );
+    assert_eq!(
+        convert("<pre>```\nfenced\n```</pre>"),
+        "````\n```\nfenced\n```\n````\n"
+    );
+}
+
+#[test]
+fn converts_tables() {
+    let source =
+        "<table><thead><tr><th>Name</th><th style=\"text-align: right\">Age</th></tr></thead>\
+                  <tbody><tr><td>Ann</td><td>31</td></tr><tr><td>a|b</td></tr></tbody></table>";
+    assert_eq!(
+        convert(source),
+        "| Name | Age |\n| --- | ---: |\n| Ann | 31 |\n| a\\|b |  |\n"
+    );
+}
+
+#[test]
+fn falls_back_to_html_for_tables_markdown_cannot_express() {
+    assert_eq!(
+        convert("<table><tr><td>no header</td></tr></table>"),
+        "<table><tbody><tr><td>no header</td></tr></tbody></table>\n"
+    );
+    assert_eq!(
+        convert("<table><tr><th colspan=\"2\">wide</th></tr></table>"),
+        "<table><tbody><tr><th colspan=\"2\">wide</th></tr></tbody></table>\n"
+    );
+}
+
+#[test]
+fn falls_back_to_html_for_other_elements() {
+    assert_eq!(
+        convert("<p>x<sup>2</sup> and <u><em>under</em></u></p><dl><dt>t</dt><dd>d</dd></dl>"),
+        "x<sup>2</sup> and <u>*under*</u>\n\n<dl><dt>t</dt><dd>d</dd></dl>\n"
+    );
+}
+
+#[test]
+fn gfm_extensions_can_be_turned_off() {
+    let source = "<p><del>old</del></p><ul><li><input type=\"checkbox\" checked> done</li></ul>";
+    assert_eq!(convert(source), "~~old~~\n\n- [x] done\n");
+    let options = MarkdownOptions {
+        gfm: false,
+        ..MarkdownOptions::default()
+    };
+    assert_eq!(
+        html_to_markdown(source, &options),
+        "<del>old</del>\n\n- <input type=\"checkbox\" checked=\"\"> done\n"
+    );
+    assert_eq!(
+        html_to_markdown("<table><tr><th>a</th></tr></table>", &options),
+        "<table><tbody><tr><th>a</th></tr></tbody></table>\n"
+    );
+}
+
+#[test]
+fn escapes_markdown_in_text() {
+    assert_eq!(
+        convert("<p># not a heading</p><p>1. not a list</p><p>*stars* and [brackets]</p>"),
+        "\\# not a heading\n\n1\\. not a list\n\n\\*stars\\* and \\[brackets\\]\n"
+    );
+}
+
+#[test]
+fn converts_line_breaks_and_rules() {
+    assert_eq!(
+        convert("<p>one<br>two<br></p><hr><p>three</p>"),
+        "one\\\ntwo\n\n***\n\nthree\n"
+    );
+}
+
+#[test]
+fn converts_single_nodes() {
+    let document = parse_html("<div><p>skipped</p></div><section><h2>Kept</h2></section>");
+    let section = document.root().last_child().unwrap();
+    assert_eq!(
+        node_to_markdown(section, &MarkdownOptions::default()),
+        "## Kept\n"
+    );
+}
@@ -0,0 +1,139 @@
+//! Renders markdown to HTML with pulldown-cmark, converts that HTML back to
+//! markdown, and checks that the result renders to the same HTML.
+
+use ast::elements;
+use markdown::{html_to_markdown, MarkdownOptions};
+use parser::parse_html;
+use pulldown_cmark::{html, Options, Parser};
+
+const DOCUMENTS: &[&str] = &[
+    "# Title\n\nSome *emphasis*, **strong** and ***both***.\n",
+    "## Second level\n\n###### Sixth level with `code`\n",
+    "A paragraph\nwith a soft break.\n\nAnother one with a hard  \nbreak.\n",
+    "Links: [inline](https://example.com \"Title\"), <https://auto.example>, [empty]() \
+     and [with spaces](<a b.html>).\n",
+    "Images: ![alt text](img.png) and ![](empty.png \"titled\").\n",
+    "[![badge](badge.svg)](https://ci.example)\n",
+    "- one\n- two\n- three\n",
+    "1. first\n2. second\n3. third\n",
+    "7. seven\n8. eight\n",
+    "- outer\n  - inner\n    - innermost\n  - inner again\n- outer again\n",
+    "1. ordered\n   - with bullets\n   - inside\n2. and on\n",
+    "- loose\n\n- list\n\n  with two paragraphs\n",
+    "- first list\n\n* second list\n",
+    "> A quote\n> with *emphasis*.\n>\n> > And a nested one.\n",
+    "- > quote in a list\n- item\n",
+    "```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n",
+    "```\nno language\n\n\nblank lines\n```\n",
+    "````\n```\nfence inside\n```\n````\n",
+    "- list\n\n  ```js\n  code in a list\n  ```\n",
+    "Inline `` code with ` backtick `` and ` `` `.\n",
+    "***\n\nAfter a rule.\n",
+    "Escapes: \\*not emphasis\\*, \\_not\\_, \\[not a link\\], 1 \\< 2, \\`tick\\`.\n",
+    "\\# not a heading\n\n1\\. not a list\n\n\\- not a bullet\n\n\\> not a quote\n",
+    "snake_case_name and 2*3*4\n",
+    "AT&T and &amp;copy; stay text.\n",
+    "~~struck~~ text\n",
+    "| Name | Age |\n| :--- | ---: |\n| Ann | 31 |\n| Bob | `a|b` |\n",
+    "| a | b |\n| --- | :-: |\n| *x* | [y](z) |\n| only one |\n",
+    "- [ ] todo\n- [x] done\n",
+    "Text with <sup>super</sup> and <kbd>Ctrl</kbd> inline HTML.\n",
+    "<dl>\n<dt>term</dt>\n<dd>definition</dd>\n</dl>\n",
+    "Unicode: café, naïve, 日本語, emoji 🎉.\n",
+];
+
+fn options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+fn render(markdown: &str) -> String {
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new_ext(markdown, options()));
+    output
+}
+
+fn is_block_tag(tag: &str) -> bool {
+    let name: String = tag
+        .trim_start_matches(['<', '/'])
+        .chars()
+        .take_while(|ch| ch.is_ascii_alphanumeric())
+        .collect();
+    elements::is_block(&name) || name == "br"
+}
+
+/// Serializes the HTML and collapses whitespace, dropping it next to block
+/// tags, where it isn't rendered.
+fn normalize(html: &str) -> String {
+    let serialized = serializer::serialize(&parse_html(html));
+    let mut collapsed = String::new();
+    for word in serialized.split_ascii_whitespace() {
+        if !collapsed.is_empty() {
+            collapsed.push(' ');
+        }
+        collapsed.push_str(word);
+    }
+
+    let mut output = String::new();
+    let mut rest = collapsed.as_str();
+    while let Some(index) = rest.find(' ') {
+        let (before, after) = (&rest[..index], &rest[index + 1..]);
+        output.push_str(before);
+        let previous_tag = output.rfind('<').map(|start| &output[start..]);
+        let after_block = previous_tag.is_some_and(|tag| tag.ends_with('>') && is_block_tag(tag));
+        let before_block = after.starts_with('<') && is_block_tag(after);
+        if !after_block && !before_block {
+            output.push(' ');
+        }
+        rest = after;
+    }
+    output.push_str(rest);
+    output
+}
+
+#[test]
+fn rendered_markdown_survives_conversion() {
+    for &document in DOCUMENTS {
+        let rendered = render(document);
+        let converted = html_to_markdown(&rendered, &MarkdownOptions::default());
+        assert_eq!(
+            normalize(&render(&converted)),
+            normalize(&rendered),
+            "\n{}\nconverted to\n{}",
+            document,
+            converted
+        );
+    }
+}
+
+#[test]
+fn conversion_is_stable() {
+    for &document in DOCUMENTS {
+        let converted = html_to_markdown(&render(document), &MarkdownOptions::default());
+        assert_eq!(
+            html_to_markdown(&render(&converted), &MarkdownOptions::default()),
+            converted,
+            "\n{}",
+            document
+        );
+    }
+}
+
+#[test]
+fn alternative_markers_survive_conversion() {
+    let options = MarkdownOptions {
+        bullet: '*',
+        emphasis: '_',
+        ..MarkdownOptions::default()
+    };
+    for &document in DOCUMENTS {
+        let rendered = render(document);
+        let converted = html_to_markdown(&rendered, &options);
+        assert_eq!(
+            normalize(&render(&converted)),
+            normalize(&rendered),
+            "\n{}\nconverted to\n{}",
+            document,
+            converted
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{preserves_whitespace, MinifyOptions};
+use ast::{elements, walk_mut, Document, Node, NodeId, Visit, VisitorMut};
+
+/// Attributes whose presence alone is what matters. Enumerated attributes
+/// such as `hidden`, where `until-found` means something else, aren't here.
+const BOOLEAN: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+pub(crate) fn clean(document: &mut Document, options: &MinifyOptions) {
+    let mut cleaner = Cleaner {
+        options,
+        preserving: 0,
+    };
+    walk_mut(document, Document::ROOT, &mut cleaner);
+}
+
+struct Cleaner<'o> {
+    options: &'o MinifyOptions,
+    /// How many of the open elements keep their whitespace.
+    preserving: usize,
+}
+
+fn preserves(document: &Document, id: NodeId) -> bool {
+    let element = document.node(id).as_element().unwrap();
+    preserves_whitespace(element.tag_name())
+}
+
+impl VisitorMut for Cleaner<'_> {
+    fn enter_element(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        if preserves(document, id) {
+            self.preserving += 1;
+        }
+        if self.options.collapse_boolean_attributes {
+            let element = document.element_mut(id).unwrap();
+            for &name in BOOLEAN {
+                // Other values are invalid, and left for whatever reads them.
+                if element
+                    .get_attribute(name)
+                    .is_some_and(|value| value.eq_ignore_ascii_case(name))
+                {
+                    element.set_attribute(name, "");
+                }
+            }
+        }
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        if preserves(document, id) {
+            self.preserving -= 1;
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        if !self.options.collapse_whitespace || self.preserving > 0 {
+            return Visit::Continue;
+        }
+        let text = document.node(id);
+
+        let mut value = collapse(&text.text_content());
+        if at_block_boundary(text, false) {
+            value = value.trim_start_matches(' ').to_string();
+        }
+        if at_block_boundary(text, true) {
+            value = value.trim_end_matches(' ').to_string();
+        }
+        if value.is_empty() {
+            document.detach(id);
+        } else {
+            document.set_text_content(id, &value);
+        }
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        if !self.options.remove_comments {
+            return Visit::Continue;
+        }
+        let comment = document.node(id);
+        let previous = comment.previous_sibling().filter(|node| node.is_text());
+        let next = comment.next_sibling().filter(|node| node.is_text());
+        let merge = previous.zip(next).map(|(previous, next)| {
+            let text = previous.text_content() + &next.text_content();
+            (previous.id(), next.id(), text)
+        });
+        document.detach(id);
+
+        // The text around the comment would be read back as a single node, so
+        // it has to become one. The walk continues with the following node.
+        if let Some((previous, next, text)) = merge {
+            document.set_text_content(next, &text);
+            document.detach(previous);
+        }
+        Visit::Continue
+    }
+}
+
+/// Replaces every run of ASCII whitespace with a single space.
+fn collapse(value: &str) -> String {
+    let mut collapsed = String::with_capacity(value.len());
+    let mut in_whitespace = false;
+    for ch in value.chars() {
+        if matches!(ch, ' ' | '\t' | '\n' | '\u{c}' | '\r') {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(ch);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+/// Whether whitespace between `text` and what comes before or after it is
+/// insignificant: the nearest rendered sibling on that side is a block, or
+/// there is none and the parent is a block or the document itself. Text in
+/// elements that aren't rendered, such as `head`, never matters.
+fn at_block_boundary(text: Node, after: bool) -> bool {
+    let parent = text.parent();
+    if parent.and_then(tag_name).is_some_and(elements::is_hidden) {
+        return true;
+    }
+    let mut siblings = std::iter::successors(Some(text), |node| {
+        if after {
+            node.next_sibling()
+        } else {
+            node.previous_sibling()
+        }
+    })
+    .skip(1);
+    match siblings.find(|node| !tag_name(*node).is_some_and(elements::is_hidden)) {
+        Some(sibling) => tag_name(sibling).is_some_and(elements::is_layout_block),
+        None => parent.is_some_and(|parent| {
+            parent.is_document() || tag_name(parent).is_some_and(elements::is_block)
+        }),
+    }
+}
+
+fn tag_name(node: Node<'_>) -> Option<&str> {
+    node.as_element().map(|element| element.tag_name())
+}
@@ -0,0 +1,146 @@
+//! Minifies HTML documents.
+//!
+//! Minifying happens in two steps. First the document is cleaned: comments
+//! are removed, insignificant whitespace is collapsed and boolean attributes
+//! get empty values. Then the cleaned document is written as compactly as
+//! possible, leaving out optional tags and attribute quotes. Parsing the
+//! output gives back exactly the cleaned document.
+//!
+//! The `html`, `head` and `body` start tags are always written, because the
+//! parser doesn't create those elements when they are missing.
+
+mod clean;
+mod optional;
+
+use ast::{elements, walk, Document, Element, Node, Text, Visit, Visitor};
+use parser::parse_html;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinifyOptions {
+    /// Collapse whitespace runs to a single space, and remove whitespace
+    /// around block elements. Whitespace inside `pre`, `textarea` and raw
+    /// text elements is left alone.
+    pub collapse_whitespace: bool,
+    pub remove_comments: bool,
+    /// Leave out end tags, and the `tbody` and `colgroup` start tags, where
+    /// the parser would imply them.
+    pub omit_optional_tags: bool,
+    /// Write attribute values without quotes when that parses the same.
+    pub unquote_attributes: bool,
+    /// Write boolean attributes, such as `disabled`, without a value.
+    pub collapse_boolean_attributes: bool,
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        MinifyOptions {
+            collapse_whitespace: true,
+            remove_comments: true,
+            omit_optional_tags: true,
+            unquote_attributes: true,
+            collapse_boolean_attributes: true,
+        }
+    }
+}
+
+/// Parses and minifies `source`.
+pub fn minify(source: &str, options: &MinifyOptions) -> String {
+    let mut document = parse_html(source);
+    minify_document(&mut document, options)
+}
+
+/// Cleans `document` in place and writes it out minified.
+pub fn minify_document(document: &mut Document, options: &MinifyOptions) -> String {
+    clean::clean(document, options);
+    let mut writer = Writer {
+        options,
+        output: String::new(),
+    };
+    walk(document.root(), &mut writer);
+    writer.output
+}
+
+struct Writer<'o> {
+    options: &'o MinifyOptions,
+    output: String,
+}
+
+impl Writer<'_> {
+    fn write_attribute(&mut self, name: &str, value: &str) {
+        self.output.push(' ');
+        self.output.push_str(name);
+        if value.is_empty() && self.options.collapse_boolean_attributes {
+            return;
+        }
+        self.output.push('=');
+        if self.options.unquote_attributes && can_unquote(value) {
+            escape_unquoted(value, &mut self.output);
+        } else {
+            self.output.push('"');
+            serializer::escape_attribute(value, &mut self.output);
+            self.output.push('"');
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Writer<'_> {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        if self.options.omit_optional_tags && optional::start_tag_omitted(element) {
+            return Visit::Continue;
+        }
+        self.output.push('<');
+        self.output.push_str(element.tag_name());
+        for attribute in element.attributes() {
+            self.write_attribute(attribute.name(), attribute.value());
+        }
+        self.output.push('>');
+        if serializer::starts_with_newline(element) {
+            self.output.push('\n');
+        }
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        if !(self.options.omit_optional_tags && optional::end_tag_omitted(element)) {
+            serializer::write_end_tag(element, &mut self.output);
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        serializer::write_leaf(text.as_node(), &mut self.output);
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, comment: Node<'a>) -> Visit {
+        serializer::write_leaf(comment, &mut self.output);
+        Visit::Continue
+    }
+
+    fn visit_doctype(&mut self, doctype: Node<'a>) -> Visit {
+        serializer::write_leaf(doctype, &mut self.output);
+        Visit::Continue
+    }
+}
+
+/// Whether an attribute value reads the same without quotes.
+fn can_unquote(value: &str) -> bool {
+    !value.is_empty()
+        && !value
+            .chars()
+            .any(|ch| ch.is_whitespace() || matches!(ch, '"' | '\'' | '<' | '>' | '=' | '`'))
+}
+
+fn escape_unquoted(value: &str, output: &mut String) {
+    for ch in value.chars() {
+        match ch {
+            '&' => output.push_str("&amp;"),
+            ch => output.push(ch),
+        }
+    }
+}
+
+/// Elements whose contents are kept exactly, whitespace included.
+fn preserves_whitespace(name: &str) -> bool {
+    matches!(name, "listing" | "plaintext" | "pre" | "textarea") || elements::is_raw_text(name)
+}
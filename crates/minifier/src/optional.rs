@@ -0,0 +1,69 @@
+//! Which tags can be left out, following the optional tags section of the
+//! HTML specification, limited to the tags whose absence the parser fills
+//! back in.
+
+use ast::{elements, Element, Node, QuirksMode};
+
+/// Parents that don't end a `p` element whose end tag is left out.
+const TRANSPARENT: &[&str] = &["a", "audio", "del", "ins", "map", "noscript", "video"];
+
+/// Table children whose start tag closes an open `caption` or `colgroup`.
+const TABLE_CHILDREN: &[&str] = &["caption", "colgroup", "tbody", "tfoot", "thead", "tr"];
+
+fn name<'a>(node: Option<Node<'a>>) -> Option<&'a str> {
+    node.and_then(|node| node.as_element())
+        .map(|element| element.tag_name())
+}
+
+pub(crate) fn end_tag_omitted(element: Element) -> bool {
+    let next = element.next_sibling();
+    let next_name = name(next);
+    let last = next.is_none();
+    match element.tag_name() {
+        "html" | "body" => last,
+        "head" => next_name == Some("body"),
+        "li" => last || next_name == Some("li"),
+        "dt" => matches!(next_name, Some("dt" | "dd")),
+        "dd" => last || matches!(next_name, Some("dt" | "dd")),
+        "p" => match next_name {
+            // In quirks mode, tables can be inside paragraphs.
+            Some("table") => element.as_node().document().quirks_mode() != QuirksMode::Quirks,
+            Some(next_name) => elements::closes_paragraph(next_name),
+            None => {
+                last && !name(element.parent()).is_some_and(|parent| TRANSPARENT.contains(&parent))
+            }
+        },
+        "rt" | "rp" => last || matches!(next_name, Some("rt" | "rp")),
+        "optgroup" => last || next_name == Some("optgroup"),
+        "option" => last || matches!(next_name, Some("option" | "optgroup")),
+        "caption" | "colgroup" => {
+            last || next_name.is_some_and(|name| TABLE_CHILDREN.contains(&name))
+        }
+        "thead" => matches!(next_name, Some("tbody" | "tfoot")),
+        "tbody" => last || matches!(next_name, Some("tbody" | "tfoot")),
+        "tfoot" => last,
+        "tr" => last || next_name == Some("tr"),
+        "td" | "th" => last || matches!(next_name, Some("td" | "th")),
+        _ => false,
+    }
+}
+
+pub(crate) fn start_tag_omitted(element: Element) -> bool {
+    if !element.attributes().is_empty() || name(element.parent()) != Some("table") {
+        return false;
+    }
+    let (first_child, after) = match element.tag_name() {
+        "tbody" => ("tr", &["tbody", "thead", "tfoot"][..]),
+        "colgroup" => ("col", &["colgroup"][..]),
+        _ => return false,
+    };
+    // The parser only creates the element for a child that comes directly
+    // inside the table, not for one following a section that is still open.
+    let previous = element
+        .previous_sibling()
+        .and_then(|node| node.as_element());
+    name(element.first_child()) == Some(first_child)
+        && !previous.is_some_and(|previous| {
+            after.contains(&previous.tag_name()) && end_tag_omitted(previous)
+        })
+}
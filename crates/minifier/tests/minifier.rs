@@ -0,0 +1,174 @@
+use minifier::{minify, minify_document, MinifyOptions};
+use parser::parse_html;
+use serializer::serialize;
+
+const CORPUS: &[&str] = &[
+    "<!DOCTYPE html>\n<html lang=\"en\">\n  <head>\n    <meta charset=\"utf-8\">\n    <title>Test</title>\n  </head>\n  <body>\n    <p>Hello</p>\n  </body>\n</html>\n",
+    "<ul>\n  <li>One</li>\n  <li>Two <b>bold</b>\n    <ul><li>Nested</li></ul>\n  </li>\n  <li><p>Para</p></li>\n</ul>",
+    "<ol start=\"3\"><li><p>a</p><p>b</p></li><li>c</li></ol><p>after</p>",
+    "<dl><dt>Term</dt><dt>Alias</dt><dd>Definition</dd><dd>Another</dd></dl>",
+    "<div><p>One</p><p>Two</p><div>Three</div><p>Four</p></div>",
+    "<p>Paragraph in a <a href=\"#\"><span>link</span></a></p><a href=\"#\"><p>para in link</p></a>",
+    "<table>\n  <caption>Caption</caption>\n  <colgroup><col><col span=\"2\"></colgroup>\n  <thead><tr><th>a</th><th>b</th></tr></thead>\n  <tbody><tr><td>1</td><td>2</td></tr></tbody>\n  <tbody class=\"second\"><tr><td>3</td><td>4</td></tr></tbody>\n  <tfoot><tr><td>5</td><td>6</td></tr></tfoot>\n</table>",
+    "<table><thead><tr><th>a</th></tr></thead><tbody><tr><td>b</td></tr></tbody></table>",
+    "<table><tr><td><table><tr><td>inner</td></tr></table></td><td>outer</td></tr></table>",
+    "<select name=\"s\"><optgroup label=\"A\"><option value=\"1\" selected=\"selected\">One</option><option>Two</option></optgroup><optgroup label=\"B\"><option>Three</option></optgroup></select>",
+    "<ruby>漢<rp>(</rp><rt>kan</rt><rp>)</rp>字<rp>(</rp><rt>ji</rt><rp>)</rp></ruby>",
+    "<pre>\n  keep   all\n    this\n</pre><textarea>  and\n this </textarea><script>if (a < b) {\n  go();\n}</script><style>p > a { color: red }</style>",
+    "<p>Text <!-- a comment --> more <!--x-->text</p><!-- top level -->",
+    "<form><input type=\"checkbox\" checked=\"checked\" disabled=\"\" name=\"a b\" value='say \"hi\"'><input value=\"a=b\" data-x=\"`\" title=\"&lt;tag&gt; &amp; more\"></form>",
+    "<p>a &lt; b &amp;&amp; c &gt; d&nbsp;e</p>",
+    "<p>a</p><table><tr><td>x</td></tr></table>",
+    "<!DOCTYPE html><p>a</p><table><tr><td>x</td></tr></table>",
+    "<p>a <script>x()</script> b <style></style> c</p>",
+    "<body>\n  <div>x</div>\n  <script>go()</script>\n</body>",
+];
+
+fn all_options() -> Vec<MinifyOptions> {
+    let mut all = vec![MinifyOptions::default()];
+    for i in 0..5 {
+        let mut options = MinifyOptions::default();
+        let flag = match i {
+            0 => &mut options.collapse_whitespace,
+            1 => &mut options.remove_comments,
+            2 => &mut options.omit_optional_tags,
+            3 => &mut options.unquote_attributes,
+            _ => &mut options.collapse_boolean_attributes,
+        };
+        *flag = false;
+        all.push(options);
+    }
+    all
+}
+
+#[test]
+fn minified_output_parses_to_the_cleaned_tree() {
+    for options in all_options() {
+        for source in CORPUS {
+            let mut document = parse_html(source);
+            let minified = minify_document(&mut document, &options);
+            assert_eq!(
+                serialize(&parse_html(&minified)),
+                serialize(&document),
+                "{} minified to {} with {:?}",
+                source,
+                minified,
+                options
+            );
+            assert_eq!(minify(&minified, &options), minified);
+        }
+    }
+}
+
+#[test]
+fn minifies_documents() {
+    let source = CORPUS[0];
+    assert_eq!(
+        minify(source, &MinifyOptions::default()),
+        "<!DOCTYPE html><html lang=en><head><meta charset=utf-8><title>Test</title><body><p>Hello"
+    );
+}
+
+#[test]
+fn omits_optional_tags() {
+    let minify = |source| minify(source, &MinifyOptions::default());
+    assert_eq!(
+        minify(CORPUS[1]),
+        "<ul><li>One<li>Two <b>bold</b><ul><li>Nested</ul><li><p>Para</ul>"
+    );
+    assert_eq!(
+        minify(CORPUS[3]),
+        "<dl><dt>Term<dt>Alias<dd>Definition<dd>Another</dl>"
+    );
+    assert_eq!(
+        minify(CORPUS[5]),
+        "<p>Paragraph in a <a href=#><span>link</span></a></p><a href=#><p>para in link</p></a>"
+    );
+    assert_eq!(
+        minify(CORPUS[6]),
+        "<table><caption>Caption<col><col span=2><thead><tr><th>a<th>b<tbody><tr><td>1<td>2\
+         <tbody class=second><tr><td>3<td>4<tfoot><tr><td>5<td>6</table>"
+    );
+    assert_eq!(
+        minify(CORPUS[7]),
+        "<table><thead><tr><th>a<tbody><tr><td>b</table>"
+    );
+    // Only outside quirks mode does a table close the paragraph before it.
+    assert_eq!(
+        minify("<p>a</p><table><tr><td>x</table>"),
+        "<p>a</p><table><tr><td>x</table>"
+    );
+    assert_eq!(
+        minify("<!DOCTYPE html><p>a</p><table><tr><td>x</table>"),
+        "<!DOCTYPE html><p>a<table><tr><td>x</table>"
+    );
+}
+
+#[test]
+fn collapses_whitespace() {
+    let minify = |source| minify(source, &MinifyOptions::default());
+    assert_eq!(
+        minify("<div>\n  <p>\n    Some   text\n    here </p>\n</div>"),
+        "<div><p>Some text here</div>"
+    );
+    assert_eq!(
+        minify("<p>keep <b>inline</b> spacing</p>"),
+        "<p>keep <b>inline</b> spacing"
+    );
+    assert_eq!(
+        minify(CORPUS[11]),
+        "<pre>  keep   all\n    this\n</pre><textarea>  and\n this </textarea>\
+         <script>if (a < b) {\n  go();\n}</script><style>p > a { color: red }</style>"
+    );
+    // Scripts and styles aren't rendered, so the text around them still runs
+    // together.
+    assert_eq!(
+        minify(CORPUS[17]),
+        "<p>a <script>x()</script> b <style></style> c"
+    );
+    assert_eq!(
+        minify(CORPUS[18]),
+        "<body><div>x</div><script>go()</script>"
+    );
+}
+
+#[test]
+fn comments_are_configurable() {
+    let keep = MinifyOptions {
+        remove_comments: false,
+        ..MinifyOptions::default()
+    };
+    assert_eq!(
+        minify(CORPUS[12], &MinifyOptions::default()),
+        "<p>Text more text"
+    );
+    assert_eq!(
+        minify(CORPUS[12], &keep),
+        "<p>Text <!-- a comment --> more <!--x-->text</p><!-- top level -->"
+    );
+}
+
+#[test]
+fn attributes() {
+    assert_eq!(
+        minify(CORPUS[13], &MinifyOptions::default()),
+        "<form><input type=checkbox checked disabled name=\"a b\" value=\"say &quot;hi&quot;\">\
+         <input value=\"a=b\" data-x=\"`\" title=\"<tag> &amp; more\"></form>"
+    );
+    let quoted = MinifyOptions {
+        unquote_attributes: false,
+        collapse_boolean_attributes: false,
+        ..MinifyOptions::default()
+    };
+    assert_eq!(
+        minify(
+            "<div hidden=until-found><input checked=CHECKED disabled=no></div><p hidden=hidden>",
+            &MinifyOptions::default()
+        ),
+        "<div hidden=until-found><input checked disabled=no></div><p hidden=hidden>"
+    );
+    assert_eq!(
+        minify("<input type=\"checkbox\" checked=\"checked\">", &quoted),
+        "<input type=\"checkbox\" checked=\"checked\">"
+    );
+}
@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use parser::parse_html;
+
+/// Builds a document with `sections` sections, each holding a heading, a
+/// paragraph with inline markup and a small list.
+fn large_document(sections: usize) -> String {
+    let mut html = String::from("<html><head><title>Benchmark</title></head><body>");
+    for i in 0..sections {
+        html.push_str(&format!(
+            "<section id=\"s{i}\" class=\"section\"><h2>Section {i}</h2>\
+             <p>Some <b>bold</b> and <i>italic</i> text in section {i}.</p>\
+             <ul><li>one</li><li>two</li><li>three</li></ul></section>"
+        ));
+    }
+    html.push_str("</body></html>");
+    html
+}
+
+fn parse(c: &mut Criterion) {
+    let source = large_document(10_000);
+    c.bench_function("parse large document", |b| {
+        b.iter(|| parse_html(black_box(&source)))
+    });
+}
+
+fn traverse(c: &mut Criterion) {
+    let document = parse_html(&large_document(10_000));
+    c.bench_function("traverse large document", |b| {
+        b.iter(|| black_box(&document).descendants().count())
+    });
+}
+
+criterion_group!(benches, parse, traverse);
+criterion_main!(benches);
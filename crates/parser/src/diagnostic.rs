@@ -0,0 +1,57 @@
+//! Rendering parse errors for people to read.
+
+use crate::ParseError;
+
+/// Renders `error` in `source` as a diagnostic in the style of codespan and
+/// rustc, with the offending source line underlined:
+///
+/// ```text
+/// error[missing-whitespace-between-attributes]: missing whitespace between attributes
+///  --> index.html:1:9
+///   |
+/// 1 | <p a="1"b="2">
+///   |         ^
+/// ```
+///
+/// `name` is the name shown for the source, usually its path. The result ends
+/// with a newline.
+pub fn render(source: &str, name: &str, error: &ParseError) -> String {
+    let start = error.span.start;
+    let end = error.span.end;
+    let line = source
+        .split('\n')
+        .nth(start.line - 1)
+        .unwrap_or("")
+        .trim_end_matches('\r');
+
+    // Spans over several lines are underlined to the end of the first.
+    let line_length = line.chars().count();
+    let until = if end.line == start.line {
+        end.column
+    } else {
+        line_length + 1
+    };
+    let underline_length = until.saturating_sub(start.column).max(1);
+
+    // Tabs are kept in the indentation so the underline lines up however
+    // wide they are shown.
+    let indentation: String = line
+        .chars()
+        .take(start.column - 1)
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    let number = start.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let mut output = format!(
+        "{}[{}]: {}\n",
+        error.severity,
+        error.code,
+        error.code.message()
+    );
+    output += &format!("{gutter}--> {name}:{}:{}\n", start.line, start.column);
+    output += &format!("{gutter} |\n");
+    output += &format!("{number} | {line}\n");
+    output += &format!("{gutter} | {indentation}{}\n", "^".repeat(underline_length));
+    output
+}
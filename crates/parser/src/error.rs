@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fmt;
+
+use ast::{Document, Location, Span};
+use lexer::{ErrorCode, Severity};
+
+/// A problem with the markup that the parser recovered from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub code: ErrorCode,
+    /// The offending source. It is empty for errors at the end of the
+    /// source.
+    pub span: Span,
+    pub severity: Severity,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {} at line {}, column {}",
+            self.severity,
+            self.code,
+            self.code.message(),
+            self.span.start.line,
+            self.span.start.column
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// The result of [`parse_html_with_options`](crate::parse_html_with_options).
+#[derive(Debug, Clone)]
+pub struct Parsed {
+    pub document: Document,
+    /// The parse errors in source order, if they were collected.
+    pub errors: Vec<ParseError>,
+}
+
+/// A limit from [`ParseOptions`](crate::ParseOptions) that the source went
+/// past.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Depth,
+    Attributes,
+    AttributeLength,
+    TextLength,
+    Nodes,
+    Size,
+}
+
+impl Limit {
+    /// The code of the [`ParseError`] recorded when the limit is hit while
+    /// parsing with [`parse_html_with_options`](crate::parse_html_with_options).
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Limit::Depth => ErrorCode::NestingTooDeep,
+            Limit::Attributes => ErrorCode::TooManyAttributes,
+            Limit::AttributeLength => ErrorCode::AttributeTooLong,
+            Limit::TextLength => ErrorCode::TextTooLong,
+            Limit::Nodes => ErrorCode::TooManyNodes,
+            Limit::Size => ErrorCode::SourceTooLong,
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = match self {
+            Limit::Depth => "maximum nesting depth",
+            Limit::Attributes => "maximum number of attributes",
+            Limit::AttributeLength => "maximum attribute length",
+            Limit::TextLength => "maximum text length",
+            Limit::Nodes => "maximum number of nodes",
+            Limit::Size => "maximum source size",
+        };
+        f.write_str(limit)
+    }
+}
+
+/// Returned by [`try_parse_html`](crate::try_parse_html) for a source that
+/// goes past one of the limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitError {
+    pub limit: Limit,
+    /// Where in the source the limit was reached.
+    pub location: Location,
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} exceeded at line {}, column {}",
+            self.limit, self.location.line, self.location.column
+        )
+    }
+}
+
+impl Error for LimitError {}
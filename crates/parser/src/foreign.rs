@@ -0,0 +1,231 @@
+//! Rules for SVG and MathML content inside HTML documents.
+
+use ast::{Element, Namespace};
+use lexer::Attribute;
+
+/// SVG element names, which the tokenizer has lowercased, in their proper
+/// case.
+const SVG_TAG_NAMES: &[&str] = &[
+    "altGlyph",
+    "altGlyphDef",
+    "altGlyphItem",
+    "animateColor",
+    "animateMotion",
+    "animateTransform",
+    "clipPath",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feConvolveMatrix",
+    "feDiffuseLighting",
+    "feDisplacementMap",
+    "feDistantLight",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feImage",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+    "fePointLight",
+    "feSpecularLighting",
+    "feSpotLight",
+    "feTile",
+    "feTurbulence",
+    "foreignObject",
+    "glyphRef",
+    "linearGradient",
+    "radialGradient",
+    "textPath",
+];
+
+/// SVG attribute names in their proper case.
+const SVG_ATTRIBUTE_NAMES: &[&str] = &[
+    "attributeName",
+    "attributeType",
+    "baseFrequency",
+    "baseProfile",
+    "calcMode",
+    "clipPathUnits",
+    "diffuseConstant",
+    "edgeMode",
+    "filterUnits",
+    "glyphRef",
+    "gradientTransform",
+    "gradientUnits",
+    "kernelMatrix",
+    "kernelUnitLength",
+    "keyPoints",
+    "keySplines",
+    "keyTimes",
+    "lengthAdjust",
+    "limitingConeAngle",
+    "markerHeight",
+    "markerUnits",
+    "markerWidth",
+    "maskContentUnits",
+    "maskUnits",
+    "numOctaves",
+    "pathLength",
+    "patternContentUnits",
+    "patternTransform",
+    "patternUnits",
+    "pointsAtX",
+    "pointsAtY",
+    "pointsAtZ",
+    "preserveAlpha",
+    "preserveAspectRatio",
+    "primitiveUnits",
+    "refX",
+    "refY",
+    "repeatCount",
+    "repeatDur",
+    "requiredExtensions",
+    "requiredFeatures",
+    "specularConstant",
+    "specularExponent",
+    "spreadMethod",
+    "startOffset",
+    "stdDeviation",
+    "stitchTiles",
+    "surfaceScale",
+    "systemLanguage",
+    "tableValues",
+    "targetX",
+    "targetY",
+    "textLength",
+    "viewBox",
+    "viewTarget",
+    "xChannelSelector",
+    "yChannelSelector",
+    "zoomAndPan",
+];
+
+/// HTML start tags that end SVG and MathML content.
+const BREAKOUT: &[&str] = &[
+    "b",
+    "big",
+    "blockquote",
+    "body",
+    "br",
+    "center",
+    "code",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "embed",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "hr",
+    "i",
+    "img",
+    "li",
+    "listing",
+    "menu",
+    "meta",
+    "nobr",
+    "ol",
+    "p",
+    "pre",
+    "ruby",
+    "s",
+    "small",
+    "span",
+    "strong",
+    "strike",
+    "sub",
+    "sup",
+    "table",
+    "tt",
+    "u",
+    "ul",
+    "var",
+];
+
+fn find_case(names: &[&'static str], name: &str) -> Option<&'static str> {
+    names
+        .iter()
+        .copied()
+        .find(|proper| proper.eq_ignore_ascii_case(name))
+}
+
+/// The name an element gets in `namespace`.
+pub(crate) fn adjust_tag_name(namespace: Namespace, name: String) -> String {
+    match namespace {
+        Namespace::Svg => find_case(SVG_TAG_NAMES, &name).map_or(name, str::to_string),
+        _ => name,
+    }
+}
+
+/// The name an attribute gets on an element in `namespace`.
+pub(crate) fn adjust_attribute_name(namespace: Namespace, name: String) -> String {
+    match namespace {
+        Namespace::Svg => find_case(SVG_ATTRIBUTE_NAMES, &name).map_or(name, str::to_string),
+        Namespace::MathMl if name == "definitionurl" => "definitionURL".to_string(),
+        _ => name,
+    }
+}
+
+/// Whether a start tag inside SVG or MathML content closes that content and
+/// is inserted as HTML.
+pub(crate) fn is_breakout(name: &str, attributes: &[Attribute]) -> bool {
+    BREAKOUT.contains(&name)
+        || name == "font"
+            && attributes
+                .iter()
+                .any(|attribute| matches!(attribute.name.as_str(), "color" | "face" | "size"))
+}
+
+/// MathML elements whose contents are text, and can hold HTML.
+fn is_mathml_text_integration_point(element: Element) -> bool {
+    element.namespace() == Namespace::MathMl
+        && matches!(element.tag_name(), "mi" | "mo" | "mn" | "ms" | "mtext")
+}
+
+/// SVG and MathML elements whose contents are HTML.
+fn is_html_integration_point(element: Element) -> bool {
+    match element.namespace() {
+        Namespace::Svg => matches!(element.tag_name(), "foreignObject" | "desc" | "title"),
+        Namespace::MathMl => {
+            element.tag_name() == "annotation-xml"
+                && element.get_attribute("encoding").is_some_and(|encoding| {
+                    encoding.eq_ignore_ascii_case("text/html")
+                        || encoding.eq_ignore_ascii_case("application/xhtml+xml")
+                })
+        }
+        Namespace::Html => false,
+    }
+}
+
+/// Whether content inside `element` can be HTML, rather than continuing its
+/// SVG or MathML.
+pub(crate) fn allows_html(element: Element) -> bool {
+    element.namespace() == Namespace::Html
+        || is_html_integration_point(element)
+        || is_mathml_text_integration_point(element)
+}
+
+/// Whether a start tag inside `element` is read by the HTML rules. `svg`
+/// inside `annotation-xml` and anything but `mglyph` and `malignmark` inside
+/// MathML text are, even though they aren't HTML.
+pub(crate) fn uses_html_rules(element: Element, name: &str) -> bool {
+    element.namespace() == Namespace::Html
+        || is_html_integration_point(element)
+        || is_mathml_text_integration_point(element) && !matches!(name, "mglyph" | "malignmark")
+        || element.namespace() == Namespace::MathMl
+            && element.tag_name() == "annotation-xml"
+            && name == "svg"
+}
@@ -1,94 +1,656 @@
-use ast::{Attribute, Document, Element, Node, Text};
-use lexer::{HtmlLexer, Token};
+mod diagnostic;
+mod error;
+mod foreign;
+mod options;
+mod quirks;
+
+use std::collections::HashMap;
+
+use ast::{elements, Attribute, Document, Location, Namespace, NodeId, QuirksMode, Span};
+use lexer::{HtmlLexer, LexerOptions, State, Token};
+
+pub use diagnostic::render;
+pub use error::{Limit, LimitError, ParseError, Parsed};
+pub use lexer::{ErrorCode, Severity};
+pub use options::ParseOptions;
 
 pub fn parse_html(source: &str) -> Document {
-    HtmlParser::new(source).parse()
+    parse_html_with_options(source, &ParseOptions::default()).document
+}
+
+/// Parses `source`, also returning the parse errors in it.
+pub fn parse_html_with_errors(source: &str) -> (Document, Vec<ParseError>) {
+    let parsed = parse_html_with_options(source, &ParseOptions::new().collect_errors(true));
+    (parsed.document, parsed.errors)
+}
+
+/// Parses `source`, truncating whatever goes past the limits in `options`.
+pub fn parse_html_with_options(source: &str, options: &ParseOptions) -> Parsed {
+    parse(source, options, false).0
 }
 
+/// Parses `source`, failing if it goes past any of the limits in `options`.
+pub fn try_parse_html(source: &str, options: &ParseOptions) -> Result<Parsed, LimitError> {
+    match parse(source, options, true) {
+        (_, Some(error)) => Err(error),
+        (parsed, None) => Ok(parsed),
+    }
+}
+
+fn parse(
+    source: &str,
+    options: &ParseOptions,
+    fail_on_limit: bool,
+) -> (Parsed, Option<LimitError>) {
+    let mut end = options.max_size.unwrap_or(source.len()).min(source.len());
+    while !source.is_char_boundary(end) {
+        end -= 1;
+    }
+    let mut parser = HtmlParser::new(&source[..end], options.clone());
+    parser.fail_on_limit = fail_on_limit;
+    parser.parse(end < source.len())
+}
+
+/// Elements that may still be open when the source ends without that being
+/// an error.
+const OPEN_AT_EOF: &[&str] = &[
+    "body", "dd", "dt", "html", "li", "optgroup", "option", "p", "rb", "rp", "rt", "rtc", "tbody",
+    "td", "tfoot", "th", "thead", "tr",
+];
+
 struct HtmlParser {
     lexer: HtmlLexer,
     document: Document,
-    stack: Vec<Node>,
+    stack: Vec<NodeId>,
+    /// How many elements of each name are on the stack, which saves looking
+    /// through it for elements that aren't open at all.
+    open: HashMap<String, usize>,
     token: Token,
+    options: ParseOptions,
+    errors: Vec<ParseError>,
+    /// Fail at the first limit that is exceeded rather than truncating.
+    fail_on_limit: bool,
+    failure: Option<LimitError>,
+    /// Set once the rest of the source is to be ignored.
+    stopped: bool,
+    /// Whether anything other than whitespace and comments has been seen,
+    /// after which a doctype no longer decides the quirks mode.
+    seen_content: bool,
+    /// Set right after a `pre`, `listing` or `textarea` start tag, whose
+    /// first newline is dropped.
+    ignore_newline: bool,
 }
 
 impl HtmlParser {
-    fn new(source: &str) -> HtmlParser {
-        let document = Document::new(Vec::new());
-        let fragment = Node::Document(document.clone());
-
+    fn new(source: &str, options: ParseOptions) -> HtmlParser {
         HtmlParser {
-            lexer: HtmlLexer::new(source),
-            stack: vec![fragment],
-            document,
+            lexer: HtmlLexer::with_options(
+                source,
+                LexerOptions {
+                    collect_errors: options.collect_errors,
+                },
+            ),
+            stack: vec![Document::ROOT],
+            open: HashMap::new(),
+            document: Document::new(),
             token: Token::EOF,
+            options,
+            errors: Vec::new(),
+            fail_on_limit: false,
+            failure: None,
+            stopped: false,
+            seen_content: false,
+            ignore_newline: false,
         }
     }
 
-    fn parse(mut self) -> Document {
+    /// Parses the source, which has been cut short if `truncated`.
+    fn parse(mut self, truncated: bool) -> (Parsed, Option<LimitError>) {
+        let start = self.location();
         self.next();
-        while self.token != Token::EOF {
+        while self.token != Token::EOF && !self.stopped {
             self.handle_token();
         }
+        if truncated && !self.stopped {
+            let end = self.location();
+            self.limit_exceeded(Limit::Size, Span::new(end, end));
+        }
+        if !self.seen_content {
+            let end = self.location();
+            self.missing_doctype(Span::new(end, end));
+        }
+
+        // Elements that were never closed end where the source does.
+        let end = self.location();
+        self.check_closed(1, end);
+        self.pop_to(1, end);
+        self.set_span(Document::ROOT, Span::new(start, end));
+        self.errors.sort_by_key(|error| error.span.start.offset);
+        let parsed = Parsed {
+            document: self.document,
+            errors: self.errors,
+        };
+        (parsed, self.failure)
+    }
+
+    fn limit_exceeded(&mut self, limit: Limit, span: Span) {
+        if self.fail_on_limit {
+            self.failure.get_or_insert(LimitError {
+                limit,
+                location: span.start,
+            });
+            self.stopped = true;
+        } else {
+            self.error(limit.code(), span);
+        }
+    }
+
+    /// Checks that another node can be created. Once the limit is reached,
+    /// parsing stops.
+    fn has_room_for_node(&mut self) -> bool {
+        if self
+            .options
+            .max_nodes
+            .is_some_and(|max| self.document.len() >= max)
+        {
+            self.limit_exceeded(Limit::Nodes, self.token_span());
+            self.stopped = true;
+            return false;
+        }
+        true
+    }
+
+    /// Cuts `value` short to the byte length `max`, reporting whether it was
+    /// too long.
+    fn truncate(value: &mut String, max: Option<usize>) -> bool {
+        let Some(mut end) = max.filter(|&max| value.len() > max) else {
+            return false;
+        };
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        value.truncate(end);
+        true
+    }
+
+    fn error(&mut self, code: ErrorCode, span: Span) {
+        if self.options.collect_errors {
+            self.errors.push(ParseError {
+                code,
+                span,
+                severity: code.severity(),
+            });
+        }
+    }
 
-        self.document.clone()
+    fn set_span(&mut self, id: NodeId, span: Span) {
+        if self.options.track_spans {
+            self.document.set_span(id, Some(span));
+        }
+    }
+
+    fn current(&self) -> NodeId {
+        *self.stack.last().unwrap()
     }
 
-    fn current(&mut self) -> Node {
-        self.stack.last().unwrap().clone()
+    /// Appends a newly created node to the current open element.
+    fn insert(&mut self, node: NodeId) {
+        self.document
+            .append_child(self.current(), node)
+            .expect("open elements can always take new children");
+    }
+
+    fn location(&self) -> Location {
+        convert_location(self.lexer.location())
+    }
+
+    fn token_start(&self) -> Location {
+        convert_location(self.lexer.token_start())
+    }
+
+    /// The span of the current token.
+    fn token_span(&self) -> Span {
+        Span::new(self.token_start(), self.location())
     }
 
     fn next(&mut self) -> &Token {
+        // CDATA sections are only read inside SVG and MathML.
+        let foreign = self
+            .document
+            .node(self.current())
+            .as_element()
+            .is_some_and(|element| element.namespace() != Namespace::Html);
+        self.lexer.set_allow_cdata(foreign);
         self.token = self.lexer.next();
+        if self.options.collect_errors {
+            let errors = self
+                .lexer
+                .take_errors()
+                .into_iter()
+                .map(|error| ParseError {
+                    code: error.code,
+                    span: Span::new(convert_location(error.start), convert_location(error.end)),
+                    severity: error.code.severity(),
+                });
+            self.errors.extend(errors);
+        }
         &self.token
     }
 
     fn handle_token(&mut self) {
+        let ignore_newline = std::mem::take(&mut self.ignore_newline);
+        if !self.seen_content {
+            match &self.token {
+                Token::Character(ch) if ch.is_whitespace() => {}
+                Token::Comment(_) | Token::Doctype { .. } => {}
+                _ => self.missing_doctype(self.token_span()),
+            }
+        }
         match self.token {
-            Token::Character(ch) if ch.is_whitespace() => {
+            Token::Character('\n') if ignore_newline => {
+                self.next();
+            }
+            Token::Character(ch) if ch.is_whitespace() && self.options.strip_whitespace => {
                 self.next();
             }
             Token::Character(_) => self.handle_text(),
-            Token::Doctype => panic!("Does not support doctype"),
+            Token::Doctype { .. } => self.handle_doctype(),
+            Token::Comment(_) => self.handle_comment(),
             Token::Tag { .. } => self.handle_tag(),
-            Token::EOF => return,
+            Token::EOF => {}
         };
     }
 
     fn handle_tag(&mut self) {
-        let name = self.token.tag_name();
+        let name = self.token.tag_name().clone();
 
         if self.token.is_end_tag() {
-            self.stack.pop();
+            // Close the innermost open element with this name, and everything
+            // opened inside it. End tags without an open element are ignored.
+            let position = self.find_open(&[&name], &[]);
+            match position {
+                Some(position) => {
+                    self.check_closed(position + 1, self.token_start());
+                    self.pop_to(position, self.location());
+                }
+                None => self.error(ErrorCode::UnexpectedEndTag, self.token_span()),
+            }
             self.next();
             return;
         }
 
+        let namespace = self.start_tag_namespace(&name);
+        if namespace == Namespace::Html {
+            self.close_implied(&name);
+            let implied = match name.as_str() {
+                "tr" if self.current_is("table") => Some("tbody"),
+                "col" if self.current_is("table") => Some("colgroup"),
+                _ => None,
+            };
+            if let Some(implied) = implied {
+                if !self.has_room_for_node() {
+                    return;
+                }
+                self.insert_implied(implied);
+            }
+        }
+        if !self.has_room_for_node() {
+            return;
+        }
+
         let mut attributes = Vec::new();
+        let mut too_long = false;
         for lexer::Attribute { name, value } in self.token.attributes() {
-            attributes.push(Attribute::new(name.clone(), value.clone()));
+            let mut name = foreign::adjust_attribute_name(namespace, name.clone());
+            let mut value = value.clone();
+            too_long |= Self::truncate(&mut name, self.options.max_attribute_length);
+            too_long |= Self::truncate(&mut value, self.options.max_attribute_length);
+            attributes.push(Attribute::new(name, value));
+        }
+        if too_long {
+            self.limit_exceeded(Limit::AttributeLength, self.token_span());
+        }
+        if let Some(max) = self
+            .options
+            .max_attributes
+            .filter(|&max| attributes.len() > max)
+        {
+            attributes.truncate(max);
+            self.limit_exceeded(Limit::Attributes, self.token_span());
         }
 
-        let element = Element::new(name.clone(), attributes, Vec::new());
+        let element = self.document.create_element_ns(
+            foreign::adjust_tag_name(namespace, name.clone()),
+            namespace,
+            attributes,
+        );
+        let span = Span::new(self.token_start(), self.location());
+        self.set_span(element, span);
+        self.insert(element);
 
-        if self.token.self_closing() {
-            // We don't push it onto the stack if it's self closing.
-            self.current().append_child(Node::Element(element))
-        } else {
-            self.current().append_child(Node::Element(element.clone()));
-            self.stack.push(Node::Element(element.clone()));
+        // SVG and MathML elements can be closed by their start tag.
+        let void = match namespace {
+            Namespace::Html => elements::is_void(&name),
+            _ => self.token.self_closing(),
+        };
+        if self.token.self_closing() && !void {
+            self.error(
+                ErrorCode::NonVoidHtmlElementStartTagWithTrailingSolidus,
+                self.token_span(),
+            );
+        }
+
+        // We don't push it onto the stack if it's self closing or can't have
+        // children anyway.
+        if !self.token.self_closing() && !void {
+            // Past the maximum depth, the element is left empty and what
+            // would have been its contents follow it.
+            let depth = self.stack.len() - 1;
+            if self.options.max_depth.is_some_and(|max| depth >= max) {
+                self.limit_exceeded(Limit::Depth, self.token_span());
+            } else {
+                self.push(element);
+            }
+
+            // The contents of some HTML elements are read as text up to their
+            // end tag.
+            let name = match namespace {
+                Namespace::Html => name.as_str(),
+                _ => "",
+            };
+            match name {
+                "script" => self.lexer.set_state(State::ScriptData),
+                "noscript" if self.options.scripting => self.lexer.set_state(State::RawText),
+                "plaintext" => self.lexer.set_state(State::PlainText),
+                name if elements::is_raw_text(name) => self.lexer.set_state(State::RawText),
+                name if elements::is_escapable_raw_text(name) => {
+                    self.lexer.set_state(State::RcData)
+                }
+                _ => {}
+            }
+            self.ignore_newline = matches!(name, "pre" | "listing" | "textarea");
+        }
+        self.next();
+    }
+
+    /// The namespace of the element a start tag named `name` creates. Inside
+    /// SVG and MathML, elements continue the namespace of the current node,
+    /// except for HTML-only tags, which close that content first.
+    fn start_tag_namespace(&mut self, name: &str) -> Namespace {
+        let current = self.document.node(self.current()).as_element();
+        let Some(current) = current.filter(|&current| !foreign::uses_html_rules(current, name))
+        else {
+            return match name {
+                "svg" => Namespace::Svg,
+                "math" => Namespace::MathMl,
+                _ => Namespace::Html,
+            };
+        };
+        if !foreign::is_breakout(name, self.token.attributes()) {
+            return current.namespace();
+        }
+
+        self.error(ErrorCode::UnexpectedHtmlInForeignContent, self.token_span());
+        let position = self
+            .stack
+            .iter()
+            .rposition(|&id| {
+                self.document
+                    .node(id)
+                    .as_element()
+                    .is_none_or(foreign::allows_html)
+            })
+            .unwrap();
+        self.pop_to(position + 1, self.token_start());
+        Namespace::Html
+    }
+
+    fn current_is(&self, name: &str) -> bool {
+        self.document
+            .node(self.current())
+            .as_element()
+            .is_some_and(|element| element.tag_name() == name)
+    }
+
+    /// Returns the position in the stack of the innermost open element named
+    /// one of `names`, looking no further than an element named one of
+    /// `boundaries`. Names are compared ignoring case, since SVG element
+    /// names keep theirs.
+    fn find_open(&self, names: &[&str], boundaries: &[&str]) -> Option<usize> {
+        if !names.iter().any(|&name| self.open.contains_key(name)) {
+            return None;
+        }
+        let matches =
+            |list: &[&str], name: &str| list.iter().any(|listed| listed.eq_ignore_ascii_case(name));
+        for (position, &id) in self.stack.iter().enumerate().skip(1).rev() {
+            let name = self.document.node(id).as_element().unwrap().tag_name();
+            if matches(names, name) {
+                return Some(position);
+            }
+            if matches(boundaries, name) {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// The key of an element in `open`, which is its lowercased name.
+    fn open_key(&self, element: NodeId) -> String {
+        let name = self.document.node(element).as_element().unwrap().tag_name();
+        name.to_ascii_lowercase()
+    }
+
+    fn push(&mut self, element: NodeId) {
+        *self.open.entry(self.open_key(element)).or_default() += 1;
+        self.stack.push(element);
+    }
+
+    /// Closes the elements from `position` in the stack upwards, ending their
+    /// spans at `end`.
+    fn pop_to(&mut self, position: usize, end: Location) {
+        for element in self.stack.split_off(position) {
+            let name = self.open_key(element);
+            let count = self.open.get_mut(&name).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.open.remove(&name);
+            }
+            if let Some(span) = self.document.node(element).span() {
+                self.set_span(element, Span::new(span.start, end));
+            }
+        }
+    }
+
+    /// Reports an error for each element from `position` in the stack upwards
+    /// that is about to be closed without an end tag it couldn't omit.
+    /// The errors point at the start tags of the elements, or at `location`
+    /// when spans aren't tracked.
+    fn check_closed(&mut self, position: usize, location: Location) {
+        let unclosed: Vec<_> = self.stack[position.min(self.stack.len())..]
+            .iter()
+            .map(|&id| self.document.node(id))
+            .filter(|node| !OPEN_AT_EOF.contains(&node.as_element().unwrap().tag_name()))
+            .map(|node| node.span().unwrap_or(Span::new(location, location)))
+            .collect();
+        for span in unclosed {
+            self.error(ErrorCode::UnclosedElement, span);
+        }
+    }
+
+    /// Closes the open elements whose end tags may be omitted before a start
+    /// tag named `name`.
+    fn close_implied(&mut self, name: &str) {
+        const SCOPE: &[&str] = &[
+            "annotation-xml",
+            "applet",
+            "button",
+            "caption",
+            "desc",
+            "foreignobject",
+            "html",
+            "marquee",
+            "mi",
+            "mn",
+            "mo",
+            "ms",
+            "mtext",
+            "object",
+            "table",
+            "td",
+            "template",
+            "th",
+            "title",
+        ];
+        const TABLE_SECTIONS: &[&str] = &["table", "tbody", "tfoot", "thead"];
+
+        let position = match name {
+            "li" => self
+                .find_open(&["li"], &[SCOPE, &["ol", "ul"]].concat())
+                .or_else(|| self.find_open(&["p"], SCOPE)),
+            "dd" | "dt" => self
+                .find_open(&["dd", "dt"], &[SCOPE, &["dl"]].concat())
+                .or_else(|| self.find_open(&["p"], SCOPE)),
+            "body" => self.find_open(&["head"], &["html"]),
+            "option" => self.find_open(&["option"], &["select", "datalist", "optgroup"]),
+            "optgroup" => self
+                .find_open(&["optgroup"], &["select"])
+                .or_else(|| self.find_open(&["option"], &["select", "datalist"])),
+            "rp" | "rt" => self.find_open(&["rp", "rt"], &["ruby"]),
+            "td" | "th" => self.find_open(&["td", "th"], &["tr", "table"]),
+            "tr" => match self.find_open(&["tr"], TABLE_SECTIONS) {
+                Some(position) => Some(position),
+                None => self
+                    .find_open(TABLE_SECTIONS, &[])
+                    .map(|position| position + 1),
+            },
+            "caption" | "colgroup" | "tbody" | "tfoot" | "thead" => {
+                self.find_open(&["table"], &[]).map(|position| position + 1)
+            }
+            "col" => self
+                .find_open(&["colgroup", "table"], &[])
+                .map(|position| position + 1),
+            // Quirks mode documents can have tables inside paragraphs.
+            "table" if self.document.quirks_mode() == QuirksMode::Quirks => None,
+            name if elements::closes_paragraph(name) => self.find_open(&["p"], SCOPE),
+            _ => None,
+        };
+        if let Some(position) = position {
+            self.pop_to(position, self.token_start());
+        }
+    }
+
+    /// Inserts an element whose start tag was left out, such as the `tbody`
+    /// around rows written directly inside a `table`.
+    fn insert_implied(&mut self, name: &str) {
+        let element = self.document.create_element(name.to_string(), Vec::new());
+        let start = self.token_start();
+        self.set_span(element, Span::new(start, start));
+        self.insert(element);
+        self.push(element);
+    }
+
+    /// The first content came without a doctype before it.
+    fn missing_doctype(&mut self, span: Span) {
+        self.error(ErrorCode::MissingDoctype, span);
+        if !self.options.iframe_srcdoc {
+            self.document.set_quirks_mode(QuirksMode::Quirks);
+        }
+        self.seen_content = true;
+    }
+
+    fn handle_doctype(&mut self) {
+        if !self.has_room_for_node() {
+            return;
+        }
+        if let Token::Doctype {
+            name,
+            public_id,
+            system_id,
+            force_quirks,
+        } = &self.token
+        {
+            let doctype =
+                self.document
+                    .create_doctype(name.clone(), public_id.clone(), system_id.clone());
+            let span = Span::new(self.token_start(), self.location());
+            let legacy_compat = system_id
+                .as_deref()
+                .is_none_or(|id| id == "about:legacy-compat");
+            let conforming = name == "html" && public_id.is_none() && legacy_compat;
+            let mode = quirks::quirks_mode(
+                name,
+                public_id.as_deref(),
+                system_id.as_deref(),
+                *force_quirks,
+            );
+
+            // Only the doctype that starts the document decides its mode.
+            if self.seen_content {
+                self.error(ErrorCode::UnexpectedDoctype, span);
+            } else {
+                if !conforming {
+                    self.error(ErrorCode::NonConformingDoctype, span);
+                }
+                if !self.options.iframe_srcdoc {
+                    self.document.set_quirks_mode(mode);
+                }
+                self.seen_content = true;
+            }
+            self.set_span(doctype, span);
+            self.insert(doctype);
+        }
+        self.next();
+    }
+
+    fn handle_comment(&mut self) {
+        if let Token::Comment(value) = &self.token {
+            if self.options.keep_comments {
+                let mut value = value.clone();
+                if !self.has_room_for_node() {
+                    return;
+                }
+                if Self::truncate(&mut value, self.options.max_text_length) {
+                    self.limit_exceeded(Limit::TextLength, self.token_span());
+                }
+                let comment = self.document.create_comment(value);
+                let span = Span::new(self.token_start(), self.location());
+                self.set_span(comment, span);
+                self.insert(comment);
+            }
         }
         self.next();
     }
 
     fn handle_text(&mut self) {
-        let mut text = Text::new(String::new());
+        let start = self.token_start();
+        let mut end = self.location();
+        let mut text = String::new();
         text.push(self.token.character());
 
         while let Token::Character(ch) = self.next() {
             text.push(*ch);
+            end = self.location();
         }
+        if !self.seen_content && !text.chars().all(char::is_whitespace) {
+            self.missing_doctype(Span::new(start, end));
+        }
+        if !self.has_room_for_node() {
+            return;
+        }
+        if Self::truncate(&mut text, self.options.max_text_length) {
+            self.limit_exceeded(Limit::TextLength, Span::new(start, end));
+        }
+
+        let text = self.document.create_text(text);
+        self.set_span(text, Span::new(start, end));
+        self.insert(text);
+    }
+}
 
-        self.current().append_child(Node::Text(text.clone()));
+fn convert_location(location: lexer::Location) -> Location {
+    Location {
+        offset: location.offset,
+        line: location.line,
+        column: location.column,
     }
 }
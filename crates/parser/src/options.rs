@@ -0,0 +1,142 @@
+/// Settings for [`parse_html_with_options`](crate::parse_html_with_options).
+///
+/// The fields can be set directly, or with the builder methods of the same
+/// name:
+///
+/// ```
+/// use parser::ParseOptions;
+///
+/// let options = ParseOptions::new().collect_errors(true).max_depth(512);
+/// assert!(options.collect_errors);
+/// ```
+///
+/// The `max_*` limits guard against hostile input.
+/// [`parse_html_with_options`](crate::parse_html_with_options) truncates what
+/// goes past a limit, recording a [`ParseError`](crate::ParseError) for it,
+/// while [`try_parse_html`](crate::try_parse_html) fails with a
+/// [`LimitError`](crate::LimitError).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Parse as a browser with scripting enabled would, which reads the
+    /// contents of `noscript` as text. Off by default.
+    pub scripting: bool,
+    /// Parse the document as the `srcdoc` of an `iframe`, which is never in
+    /// quirks mode. Off by default.
+    pub iframe_srcdoc: bool,
+    /// Drop the whitespace at the start of every run of text, which removes
+    /// whitespace-only text nodes such as indentation between tags. Off by
+    /// default, so all whitespace is kept as it is in the source.
+    pub strip_whitespace: bool,
+    /// Record parse errors in [`Parsed::errors`](crate::Parsed::errors). Off
+    /// by default. Errors are always recovered from either way.
+    pub collect_errors: bool,
+    /// Keep comments in the tree. On by default.
+    pub keep_comments: bool,
+    /// The deepest elements may be nested. Elements opened deeper than this
+    /// are added to the deepest open element, and their contents after them,
+    /// as siblings. Unlimited by default.
+    pub max_depth: Option<usize>,
+    /// The most attributes an element may have. Any after are dropped.
+    /// Unlimited by default.
+    pub max_attributes: Option<usize>,
+    /// The longest, in bytes, an attribute name or value may be. Longer ones
+    /// are cut short. Unlimited by default.
+    pub max_attribute_length: Option<usize>,
+    /// The longest, in bytes, the contents of a text or comment node may be.
+    /// Longer ones are cut short. Unlimited by default.
+    pub max_text_length: Option<usize>,
+    /// The most nodes the document may have, not counting the document node
+    /// itself. The source after the node that reaches the limit is ignored.
+    /// Unlimited by default.
+    pub max_nodes: Option<usize>,
+    /// The number of bytes of the source to parse. Anything after is ignored.
+    /// Unlimited by default.
+    pub max_size: Option<usize>,
+    /// Record the source span of each node. On by default.
+    pub track_spans: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    pub fn scripting(mut self, scripting: bool) -> ParseOptions {
+        self.scripting = scripting;
+        self
+    }
+
+    pub fn iframe_srcdoc(mut self, iframe_srcdoc: bool) -> ParseOptions {
+        self.iframe_srcdoc = iframe_srcdoc;
+        self
+    }
+
+    pub fn strip_whitespace(mut self, strip_whitespace: bool) -> ParseOptions {
+        self.strip_whitespace = strip_whitespace;
+        self
+    }
+
+    pub fn collect_errors(mut self, collect_errors: bool) -> ParseOptions {
+        self.collect_errors = collect_errors;
+        self
+    }
+
+    pub fn keep_comments(mut self, keep_comments: bool) -> ParseOptions {
+        self.keep_comments = keep_comments;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> ParseOptions {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn max_attributes(mut self, max_attributes: usize) -> ParseOptions {
+        self.max_attributes = Some(max_attributes);
+        self
+    }
+
+    pub fn max_attribute_length(mut self, max_attribute_length: usize) -> ParseOptions {
+        self.max_attribute_length = Some(max_attribute_length);
+        self
+    }
+
+    pub fn max_text_length(mut self, max_text_length: usize) -> ParseOptions {
+        self.max_text_length = Some(max_text_length);
+        self
+    }
+
+    pub fn max_nodes(mut self, max_nodes: usize) -> ParseOptions {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    pub fn max_size(mut self, max_size: usize) -> ParseOptions {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn track_spans(mut self, track_spans: bool) -> ParseOptions {
+        self.track_spans = track_spans;
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            scripting: false,
+            iframe_srcdoc: false,
+            strip_whitespace: false,
+            collect_errors: false,
+            keep_comments: true,
+            max_depth: None,
+            max_attributes: None,
+            max_attribute_length: None,
+            max_text_length: None,
+            max_nodes: None,
+            max_size: None,
+            track_spans: true,
+        }
+    }
+}
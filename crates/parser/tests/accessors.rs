@@ -0,0 +1,43 @@
+use parser::parse_html;
+
+#[test]
+fn read_parsed_elements() {
+    let document = parse_html(
+        "<div id=\"main\" class=\"card  wide\"><h1>Hello</h1><p>Hello <b>world</b></p></div>",
+    );
+
+    let div = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(div.tag_name(), "div");
+    assert_eq!(div.id(), Some("main"));
+    assert_eq!(div.class_list().collect::<Vec<_>>(), vec!["card", "wide"]);
+
+    let attributes: Vec<(&str, &str)> = div
+        .attributes()
+        .iter()
+        .map(|attribute| (attribute.name(), attribute.value()))
+        .collect();
+    assert_eq!(attributes, vec![("id", "main"), ("class", "card  wide")]);
+
+    let names: Vec<&str> = div
+        .children()
+        .filter_map(|child| child.as_element())
+        .map(|element| element.tag_name())
+        .collect();
+    assert_eq!(names, vec!["h1", "p"]);
+
+    let heading = div.first_child().unwrap();
+    let text = heading.first_child().unwrap().as_text().unwrap();
+    assert_eq!(text.text(), "Hello");
+
+    assert_eq!(div.text_content(), "HelloHello world");
+    assert_eq!(document.root().text_content(), "HelloHello world");
+}
+
+#[test]
+fn elements_without_id_or_class() {
+    let document = parse_html("<span></span>");
+    let span = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(span.id(), None);
+    assert_eq!(span.class_list().count(), 0);
+    assert_eq!(span.text_content(), "");
+}
@@ -0,0 +1,114 @@
+use parser::{parse_html_with_errors, render, ErrorCode, ParseOptions, Severity};
+
+#[test]
+fn errors_have_codes_spans_and_severity() {
+    let source = "<!DOCTYPE html>\n<p a=\"1\"b=\"2\">&#0;</p></div>";
+    let (document, errors) = parse_html_with_errors(source);
+    assert_eq!(document.root().children().count(), 3);
+
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            let span = error.span;
+            (error.code, &source[span.start.offset..span.end.offset])
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (ErrorCode::MissingWhitespaceBetweenAttributes, "b"),
+            (ErrorCode::NullCharacterReference, "&#0;"),
+            (ErrorCode::UnexpectedEndTag, "</div>"),
+        ]
+    );
+    assert!(errors.iter().all(|error| error.severity == Severity::Error));
+    assert_eq!(
+        errors[0].to_string(),
+        "error[missing-whitespace-between-attributes]: missing whitespace between attributes \
+         at line 2, column 9"
+    );
+}
+
+#[test]
+fn unclosed_elements_point_at_their_start_tags() {
+    let source = "<!DOCTYPE html><div><span class=a>text</div>";
+    let (_, errors) = parse_html_with_errors(source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, ErrorCode::UnclosedElement);
+    let span = errors[0].span;
+    assert_eq!(
+        &source[span.start.offset..span.end.offset],
+        "<span class=a>"
+    );
+}
+
+#[test]
+fn exceeded_limits_are_warnings() {
+    let options = ParseOptions::new().collect_errors(true).max_attributes(1);
+    let errors = parser::parse_html_with_options("<!DOCTYPE html><p a b>", &options).errors;
+    assert_eq!(errors[0].code, ErrorCode::TooManyAttributes);
+    assert_eq!(errors[0].severity, Severity::Warning);
+}
+
+#[test]
+fn codes_match_the_specification() {
+    let code = ErrorCode::EofInTag;
+    assert_eq!(code.as_str(), "eof-in-tag");
+    assert_eq!(code.message(), "end of file in tag");
+    assert_eq!(ErrorCode::from_code("eof-in-tag"), Some(code));
+    assert_eq!(ErrorCode::from_code("eof-in-everything"), None);
+}
+
+#[test]
+fn renders_diagnostics() {
+    let source = "<!DOCTYPE html>\n<p a=\"1\"b=\"2\">\n</p></div>";
+    let (_, errors) = parse_html_with_errors(source);
+    let rendered: Vec<_> = errors
+        .iter()
+        .map(|error| render(source, "index.html", error))
+        .collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "error[missing-whitespace-between-attributes]: missing whitespace between attributes
+ --> index.html:2:9
+  |
+2 | <p a=\"1\"b=\"2\">
+  |         ^
+",
+            "error[unexpected-end-tag]: unexpected end tag
+ --> index.html:3:5
+  |
+3 | </p></div>
+  |     ^^^^^^
+",
+        ]
+    );
+}
+
+#[test]
+fn renders_tabs_and_the_end_of_the_source() {
+    let source = "<!DOCTYPE html>\n\t<p title=\"x\" \"y>\n\n\n\n\n\n\n\n<div";
+    let (_, errors) = parse_html_with_errors(source);
+    let rendered: Vec<_> = errors
+        .iter()
+        .map(|error| render(source, "tabs.html", error))
+        .collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "error[unexpected-character-in-attribute-name]: unexpected character in attribute name
+ --> tabs.html:2:15
+  |
+2 | \t<p title=\"x\" \"y>
+  | \t             ^
+",
+            "error[eof-in-tag]: end of file in tag
+  --> tabs.html:10:5
+   |
+10 | <div
+   |     ^
+",
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+use parser::parse_html;
+
+fn inner_text(source: &str) -> String {
+    parse_html(source).inner_text()
+}
+
+#[test]
+fn collapses_whitespace() {
+    assert_eq!(
+        inner_text("  Hello \n\t <b> big </b>  world  "),
+        "Hello big world"
+    );
+    assert_eq!(inner_text("a&nbsp;&nbsp;b"), "a\u{a0}\u{a0}b");
+}
+
+#[test]
+fn blocks_start_new_lines() {
+    assert_eq!(
+        inner_text(
+            "<div>one</div><div> two <span>2</span></div>three<ul><li>a</li><li>b</li></ul>"
+        ),
+        "one\ntwo 2\nthree\na\nb"
+    );
+    assert_eq!(
+        inner_text("<div><div><div>nested</div></div></div>"),
+        "nested"
+    );
+}
+
+#[test]
+fn paragraphs_are_separated_by_blank_lines() {
+    assert_eq!(
+        inner_text("<h1>Title</h1><p>First</p><p>Second</p><div>After</div>"),
+        "Title\n\nFirst\n\nSecond\n\nAfter"
+    );
+}
+
+#[test]
+fn line_breaks_are_kept() {
+    assert_eq!(inner_text("one<br>two<br><br>three"), "one\ntwo\n\nthree");
+    assert_eq!(inner_text("<p>one <br> two</p>"), "one\ntwo");
+}
+
+#[test]
+fn hidden_elements_are_skipped() {
+    let source = "<html><head><title>T</title><style>p { }</style></head><body>\
+                  <script>var x = 1;</script><p>shown</p><template>t</template>\
+                  <div hidden>secret</div><span>also shown</span></body></html>";
+    assert_eq!(inner_text(source), "shown\n\nalso shown");
+}
+
+#[test]
+fn hidden_elements_give_their_text_content() {
+    let document = parse_html("<script>var x = 1;</script>");
+    let script = document.root().first_child().unwrap();
+    assert_eq!(script.inner_text(), "var x = 1;");
+}
+
+#[test]
+fn preformatted_text_is_kept() {
+    assert_eq!(
+        inner_text("<p>before</p><pre>  a\n    b  </pre>after"),
+        "before\n\n  a\n    b  \nafter"
+    );
+}
+
+#[test]
+fn table_cells_are_separated_by_tabs() {
+    let source = "<table><thead><tr><th>Name</th><th>Age</th></tr></thead>\
+                  <tbody><tr><td>Ann</td><td> 31 </td></tr><tr><td>Bob</td><td>42</td></tr></tbody>\
+                  </table><p>after</p>";
+    assert_eq!(inner_text(source), "Name\tAge\nAnn\t31\nBob\t42\n\nafter");
+}
+
+#[test]
+fn differs_from_text_content() {
+    let document = parse_html("<p>a</p><script>b</script><p>c</p>");
+    assert_eq!(document.root().text_content(), "abc");
+    assert_eq!(document.inner_text(), "a\n\nc");
+}
@@ -0,0 +1,124 @@
+use ast::{Document, QuirksMode};
+use parser::parse_html;
+use serde_json::json;
+
+#[test]
+fn documents_round_trip_through_json() {
+    let document = parse_html(
+        "<html><head><meta charset=\"utf-8\" /></head><body><h1 class=\"title\">Hello</h1></body></html>",
+    );
+    let json = serde_json::to_value(&document).unwrap();
+    let restored: Document = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&restored).unwrap(), json);
+}
+
+#[test]
+fn json_format_is_stable() {
+    let document = parse_html("<p class=\"intro\">Hello</p>");
+    assert_eq!(
+        serde_json::to_value(&document).unwrap(),
+        json!({
+            "version": 2,
+            "type": "document",
+            "quirksMode": "quirks",
+            "nodes": [
+                {
+                    "type": "element",
+                    "name": "p",
+                    "attributes": [{ "name": "class", "value": "intro" }]
+                },
+                { "type": "text", "value": "Hello", "parent": 0 }
+            ]
+        })
+    );
+
+    let paragraph = document.root().first_child().unwrap();
+    assert_eq!(
+        serde_json::to_value(paragraph).unwrap(),
+        json!({
+            "type": "element",
+            "name": "p",
+            "attributes": [{ "name": "class", "value": "intro" }],
+            "nodes": [{ "type": "text", "value": "Hello" }]
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(paragraph.first_child().unwrap()).unwrap(),
+        json!({ "type": "text", "value": "Hello" })
+    );
+}
+
+#[test]
+fn missing_arrays_default_to_empty() {
+    let document: Document = serde_json::from_value(json!({
+        "version": 2,
+        "type": "document",
+        "nodes": [{ "type": "element", "name": "br" }]
+    }))
+    .unwrap();
+    let br = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(br.tag_name(), "br");
+    assert!(br.attributes().is_empty());
+    assert_eq!(br.first_child(), None);
+}
+
+#[test]
+fn rejects_unknown_versions() {
+    let error = serde_json::from_value::<Document>(json!({
+        "version": 1,
+        "type": "document",
+        "children": []
+    }))
+    .unwrap_err();
+    assert!(error.to_string().contains("unsupported document version 1"));
+}
+
+#[test]
+fn quirks_mode_round_trips() {
+    for (source, mode) in [
+        ("<p>x", QuirksMode::Quirks),
+        (
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"\">",
+            QuirksMode::LimitedQuirks,
+        ),
+        ("<!DOCTYPE html><p>x", QuirksMode::NoQuirks),
+    ] {
+        let document = parse_html(source);
+        assert_eq!(document.quirks_mode(), mode, "{}", source);
+        let json = serde_json::to_string(&document).unwrap();
+        let restored: Document = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.quirks_mode(), mode, "{}", json);
+    }
+    let json = serde_json::to_value(parse_html("<!DOCTYPE html>")).unwrap();
+    assert_eq!(json.get("quirksMode"), None);
+}
+
+#[test]
+fn parents_must_be_earlier_elements() {
+    for parent in [0, 1, 5] {
+        let error = serde_json::from_value::<Document>(json!({
+            "version": 2,
+            "type": "document",
+            "nodes": [
+                { "type": "text", "value": "a" },
+                { "type": "element", "name": "p", "parent": parent }
+            ]
+        }))
+        .unwrap_err();
+        let message = format!("node 1 has parent {}, which is not an element", parent);
+        assert!(error.to_string().contains(&message), "{}", error);
+    }
+}
+
+#[test]
+fn deep_documents_round_trip() {
+    const DEPTH: usize = 100_000;
+    let source = format!("{}<b class=x>text</b>", "<div id=a>".repeat(DEPTH));
+    let document = parse_html(&source);
+    let json = serde_json::to_string(&document).unwrap();
+    let restored: Document = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.len(), document.len());
+    let deepest = restored.descendants().last().unwrap();
+    assert_eq!(deepest.ancestors().count(), DEPTH + 2);
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+}
@@ -0,0 +1,148 @@
+use ast::{walk, Visitor};
+use parser::{parse_html, parse_html_with_options, try_parse_html, Limit, ParseOptions};
+
+const DEPTH: usize = 100_000;
+
+fn codes(source: &str, options: ParseOptions) -> Vec<&'static str> {
+    let options = options.collect_errors(true);
+    parse_html_with_options(source, &options)
+        .errors
+        .into_iter()
+        .map(|error| error.code.as_str())
+        .filter(|code| !matches!(*code, "missing-doctype" | "unclosed-element"))
+        .collect()
+}
+
+#[test]
+fn deep_documents_parse_and_walk() {
+    let source = "<div>".repeat(DEPTH) + "deep" + &"</div>".repeat(DEPTH);
+    let document = parse_html(&source);
+    assert_eq!(document.len(), DEPTH + 1);
+
+    let deepest = document.descendants().last().unwrap();
+    assert_eq!(deepest.text_content(), "deep");
+    assert_eq!(deepest.ancestors().count(), DEPTH + 1);
+    assert_eq!(document.root().text_content(), "deep");
+
+    struct Count(usize);
+    impl Visitor<'_> for Count {
+        fn enter_element(&mut self, _element: ast::Element) -> ast::Visit {
+            self.0 += 1;
+            ast::Visit::Continue
+        }
+    }
+    let mut count = Count(0);
+    walk(document.root(), &mut count);
+    assert_eq!(count.0, DEPTH);
+
+    let mut copy = document.clone();
+    let first = copy.root().first_child().unwrap().id();
+    let clone = copy.clone_node(first, true);
+    assert_eq!(copy.node(clone).descendants().count(), DEPTH);
+    drop(copy);
+    drop(document);
+}
+
+#[test]
+fn depth_can_be_limited() {
+    let source = "<div>".repeat(10) + "deep";
+    let document = parse_html_with_options(&source, &ParseOptions::new().max_depth(3)).document;
+    assert_eq!(
+        document.descendants().last().unwrap().ancestors().count(),
+        4
+    );
+    assert_eq!(
+        codes(&source, ParseOptions::new().max_depth(3)),
+        vec!["nesting-too-deep"; 7]
+    );
+}
+
+#[test]
+fn attributes_can_be_limited() {
+    let options = ParseOptions::new().max_attributes(2);
+    let document = parse_html_with_options("<p a b c d>", &options).document;
+    let p = document.root().first_child().unwrap().as_element().unwrap();
+    let names: Vec<_> = p.attributes().iter().map(|a| a.name()).collect();
+    assert_eq!(names, vec!["a", "b"]);
+    assert_eq!(codes("<p a b c d>", options), vec!["too-many-attributes"]);
+}
+
+#[test]
+fn lengths_can_be_limited() {
+    let options = ParseOptions::new()
+        .max_attribute_length(5)
+        .max_text_length(4);
+    let document =
+        parse_html_with_options("<p title=\"abcdef\">héllo<!-- comment --></p>", &options).document;
+    let p = document.root().first_child().unwrap();
+    assert_eq!(
+        p.as_element().unwrap().get_attribute("title"),
+        Some("abcde")
+    );
+    let children: Vec<_> = p.children().collect();
+    assert_eq!(children[0].text_content(), "hél");
+    assert_eq!(children[1].as_comment().unwrap().text(), " com");
+    assert_eq!(
+        codes("<p title=\"abcdef\">héllo<!-- comment --></p>", options),
+        vec!["attribute-too-long", "text-too-long", "text-too-long"]
+    );
+}
+
+#[test]
+fn nodes_can_be_limited() {
+    let options = ParseOptions::new().max_nodes(3);
+    let document = parse_html_with_options("<p>a</p><p>b</p><p>c</p>", &options).document;
+    assert_eq!(document.len(), 3);
+    assert_eq!(document.root().text_content(), "a");
+    assert_eq!(
+        codes("<p>a</p><p>b</p><p>c</p>", options),
+        vec!["too-many-nodes"]
+    );
+}
+
+#[test]
+fn size_can_be_limited() {
+    assert_eq!(
+        codes("<p>hello</p>", ParseOptions::new().max_size(8)),
+        vec!["source-too-long"]
+    );
+    assert!(codes("<p>hello</p>", ParseOptions::new().max_size(12)).is_empty());
+}
+
+#[test]
+fn limits_can_fail_the_parse() {
+    let fails = |source: &str, options: ParseOptions| try_parse_html(source, &options).unwrap_err();
+
+    let error = fails("<div><div>\n<div>", ParseOptions::new().max_depth(2));
+    assert_eq!(error.limit, Limit::Depth);
+    assert_eq!((error.location.line, error.location.column), (2, 1));
+    assert_eq!(
+        error.to_string(),
+        "maximum nesting depth exceeded at line 2, column 1"
+    );
+
+    let limit = |source, options| fails(source, options).limit;
+    assert_eq!(
+        limit("<p a b>", ParseOptions::new().max_attributes(1)),
+        Limit::Attributes
+    );
+    assert_eq!(
+        limit("<p a=abc>", ParseOptions::new().max_attribute_length(2)),
+        Limit::AttributeLength
+    );
+    assert_eq!(
+        limit("<p>abc</p>", ParseOptions::new().max_text_length(2)),
+        Limit::TextLength
+    );
+    assert_eq!(
+        limit("<p>a</p>", ParseOptions::new().max_nodes(1)),
+        Limit::Nodes
+    );
+    assert_eq!(
+        limit("<p>a</p>", ParseOptions::new().max_size(4)),
+        Limit::Size
+    );
+
+    let options = ParseOptions::new().max_depth(2).max_nodes(3).max_size(100);
+    assert!(try_parse_html("<div><p>a</p></div>", &options).is_ok());
+}
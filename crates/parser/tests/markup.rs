@@ -0,0 +1,182 @@
+use parser::parse_html;
+
+#[test]
+fn comments_and_doctype() {
+    let document = parse_html("<!DOCTYPE html><!-- a -- comment --><p><!----></p><!--><?php x ?>");
+    let children: Vec<_> = document.root().children().collect();
+
+    let doctype = children[0].as_doctype().unwrap();
+    assert_eq!(doctype.name(), "html");
+    assert_eq!(doctype.public_id(), None);
+    assert_eq!(children[1].as_comment().unwrap().text(), " a -- comment ");
+    assert_eq!(
+        children[2]
+            .first_child()
+            .unwrap()
+            .as_comment()
+            .unwrap()
+            .text(),
+        ""
+    );
+    assert_eq!(children[3].as_comment().unwrap().text(), "");
+    assert_eq!(children[4].as_comment().unwrap().text(), "?php x ?");
+}
+
+#[test]
+fn doctype_identifiers() {
+    let document = parse_html(
+        "<!doctype HTML PUBLIC \"-//W3C//DTD HTML 4.01//EN\" 'http://www.w3.org/TR/html4/strict.dtd'>",
+    );
+    let doctype = document.root().first_child().unwrap();
+    let doctype = doctype.as_doctype().unwrap();
+    assert_eq!(doctype.name(), "html");
+    assert_eq!(doctype.public_id(), Some("-//W3C//DTD HTML 4.01//EN"));
+    assert_eq!(
+        doctype.system_id(),
+        Some("http://www.w3.org/TR/html4/strict.dtd")
+    );
+}
+
+#[test]
+fn doctype_with_non_ascii_after_the_name() {
+    for source in [
+        "<!DOCTYPE html aaaaa\u{2003}>",
+        "<!DOCTYPE html \u{e9}\u{e9}\u{e9}>",
+        "<!DOCTYPE html publi\u{e9} 'x'>",
+    ] {
+        let document = parse_html(source);
+        let doctype = document.root().first_child().unwrap();
+        let doctype = doctype.as_doctype().unwrap();
+        assert_eq!(doctype.name(), "html");
+        assert_eq!(doctype.public_id(), None);
+    }
+}
+
+#[test]
+fn character_references() {
+    let document = parse_html(
+        "<p title=\"&quot;x&quot; &amp; y\">&lt;a&gt; &#65;&#x42;&#128; &nbsp;&unknown;</p>",
+    );
+    let p = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(p.get_attribute("title"), Some("\"x\" & y"));
+    assert_eq!(p.text_content(), "<a> AB\u{20ac} \u{a0}&unknown;");
+}
+
+#[test]
+fn named_references_follow_the_full_table() {
+    let text = |source: &str| parse_html(source).root().text_content();
+    assert_eq!(
+        text("&notin; &NotEqualTilde; &fjlig;"),
+        "\u{2209} \u{2242}\u{338} fj"
+    );
+    // Legacy names are recognised without a `;`, taking the longest match.
+    assert_eq!(
+        text("&nbsp b &notit; &ampx &copy"),
+        "\u{a0} b \u{ac}it; &x \u{a9}"
+    );
+    assert_eq!(text("&nbspx &nosuch; &amp"), "\u{a0}x &nosuch; &");
+
+    // In attributes, they are only decoded if no name or `=` follows.
+    let document = parse_html("<a href=\"?a=1&copy=2&not;&notx&not-\">");
+    let link = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(
+        link.get_attribute("href"),
+        Some("?a=1&copy=2\u{ac}&notx\u{ac}-")
+    );
+}
+
+#[test]
+fn raw_text_elements() {
+    let document = parse_html(
+        "<script>if (a < b && c) { x = \"<p>\"; }</script><style>a > b {}</style><title>&amp; <b></title>",
+    );
+    let texts: Vec<String> = document
+        .root()
+        .children()
+        .map(|child| child.text_content())
+        .collect();
+    assert_eq!(
+        texts,
+        vec!["if (a < b && c) { x = \"<p>\"; }", "a > b {}", "& <b>"]
+    );
+}
+
+#[test]
+fn void_elements_and_end_tags() {
+    let document =
+        parse_html("<div><br><img src=\"a.png\">text<span><b>bold</span>after</div></p>");
+    let div = document.root().first_child().unwrap();
+    let names: Vec<_> = div
+        .children()
+        .map(|child| match child.as_element() {
+            Some(element) => element.tag_name().to_string(),
+            None => child.text_content(),
+        })
+        .collect();
+    assert_eq!(names, vec!["br", "img", "text", "span", "after"]);
+    assert_eq!(document.root().children().count(), 1);
+}
+
+#[test]
+fn whitespace_before_the_end_of_a_tag() {
+    let document = parse_html("<a href=\"x\"\n  >link</a>");
+    let a = document.root().first_child().unwrap().as_element().unwrap();
+    assert_eq!(a.attributes().len(), 1);
+}
+
+/// Writes the element structure of a node's children, with text in quotes.
+fn outline(node: ast::Node) -> String {
+    node.children()
+        .map(|child| match child.as_element() {
+            Some(element) if element.first_child().is_some() => {
+                format!("{}({})", element.tag_name(), outline(child))
+            }
+            Some(element) => element.tag_name().to_string(),
+            None => format!("{:?}", child.text_content()),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn implied_end_tags() {
+    let outline = |source| outline(parse_html(source).root());
+    assert_eq!(outline("<p>a<p>b<div>c</div>"), r#"p("a") p("b") div("c")"#);
+    assert_eq!(
+        outline("<ul><li>a<li>b<ul><li>c</ul><li>d</ul>"),
+        r#"ul(li("a") li("b" ul(li("c"))) li("d"))"#
+    );
+    assert_eq!(
+        outline("<dl><dt>a<dd>b<dt>c</dl>"),
+        r#"dl(dt("a") dd("b") dt("c"))"#
+    );
+    assert_eq!(
+        outline("<select><optgroup><option>a<option>b<optgroup><option>c</select>"),
+        r#"select(optgroup(option("a") option("b")) optgroup(option("c")))"#
+    );
+    assert_eq!(
+        outline("<ruby>a<rp>(<rt>b<rp>)</ruby>"),
+        r#"ruby("a" rp("(") rt("b") rp(")"))"#
+    );
+    assert_eq!(
+        outline("<html><head><title>a</title><body><p>b"),
+        r#"html(head(title("a")) body(p("b")))"#
+    );
+}
+
+#[test]
+fn implied_table_structure() {
+    let outline = |source| outline(parse_html(source).root());
+    assert_eq!(
+        outline("<table><caption>a<col><tr><td>b<td>c<tr><th>d</table>"),
+        r#"table(caption("a") colgroup(col) tbody(tr(td("b") td("c")) tr(th("d"))))"#
+    );
+    assert_eq!(
+        outline("<table><thead><tr><th>a<tbody><tr><td>b<tfoot><tr><td>c</table>"),
+        r#"table(thead(tr(th("a"))) tbody(tr(td("b"))) tfoot(tr(td("c"))))"#
+    );
+    assert_eq!(
+        outline("<table><tr><td><table><tr><td>a</table><td>b</table>"),
+        r#"table(tbody(tr(td(table(tbody(tr(td("a"))))) td("b"))))"#
+    );
+}
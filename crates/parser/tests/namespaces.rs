@@ -0,0 +1,110 @@
+use ast::{Document, Namespace};
+use parser::{parse_html, parse_html_with_errors, ErrorCode};
+
+/// The names and namespaces of the elements in `document`, in order.
+fn elements(document: &Document) -> Vec<(String, Namespace)> {
+    document
+        .descendants()
+        .filter_map(|node| node.as_element())
+        .map(|element| (element.tag_name().to_string(), element.namespace()))
+        .collect()
+}
+
+#[test]
+fn svg_and_math_elements_get_their_namespaces() {
+    let document =
+        parse_html("<p>a<svg><circle/><g><rect></rect></g></svg><math><mi>x</mi></math>");
+    assert_eq!(
+        elements(&document),
+        vec![
+            ("p".to_string(), Namespace::Html),
+            ("svg".to_string(), Namespace::Svg),
+            ("circle".to_string(), Namespace::Svg),
+            ("g".to_string(), Namespace::Svg),
+            ("rect".to_string(), Namespace::Svg),
+            ("math".to_string(), Namespace::MathMl),
+            ("mi".to_string(), Namespace::MathMl),
+        ]
+    );
+}
+
+#[test]
+fn svg_names_keep_their_case() {
+    let document =
+        parse_html("<svg viewbox='0 0 1 1'><clippath><foreignobject></foreignobject></clippath>");
+    let svg = document
+        .descendants()
+        .find_map(|node| node.as_element())
+        .unwrap();
+    assert_eq!(svg.get_attribute("viewBox"), Some("0 0 1 1"));
+    let names: Vec<_> = elements(&document)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, ["svg", "clipPath", "foreignObject"]);
+}
+
+#[test]
+fn self_closing_foreign_elements_are_empty() {
+    let (document, errors) = parse_html_with_errors("<!DOCTYPE html><svg><path/><g/></svg>");
+    assert!(errors.is_empty(), "{:?}", errors);
+    let svg = document.root().children().nth(1).unwrap();
+    assert_eq!(svg.children().count(), 2);
+}
+
+#[test]
+fn html_tags_break_out_of_foreign_content() {
+    let (document, errors) = parse_html_with_errors("<!DOCTYPE html><svg><g><p>text</p>");
+    assert_eq!(
+        elements(&document),
+        vec![
+            ("svg".to_string(), Namespace::Svg),
+            ("g".to_string(), Namespace::Svg),
+            ("p".to_string(), Namespace::Html),
+        ]
+    );
+    let p = document.root().children().nth(2).unwrap();
+    assert_eq!(p.as_element().unwrap().tag_name(), "p");
+    assert_eq!(errors[0].code, ErrorCode::UnexpectedHtmlInForeignContent);
+}
+
+#[test]
+fn integration_points_hold_html() {
+    let document = parse_html(
+        "<svg><foreignObject><div><svg></svg></div></foreignObject></svg><math><mtext><b>x</b>",
+    );
+    assert_eq!(
+        elements(&document),
+        vec![
+            ("svg".to_string(), Namespace::Svg),
+            ("foreignObject".to_string(), Namespace::Svg),
+            ("div".to_string(), Namespace::Html),
+            ("svg".to_string(), Namespace::Svg),
+            ("math".to_string(), Namespace::MathMl),
+            ("mtext".to_string(), Namespace::MathMl),
+            ("b".to_string(), Namespace::Html),
+        ]
+    );
+}
+
+#[test]
+fn foreign_raw_text_names_hold_markup() {
+    let document = parse_html("<svg><style><rect/></style><title>a &amp; <g/></title></svg>");
+    let names: Vec<_> = elements(&document)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, ["svg", "style", "rect", "title", "g"]);
+}
+
+#[test]
+fn cdata_sections_are_text_in_foreign_content() {
+    let (document, errors) =
+        parse_html_with_errors("<!DOCTYPE html><svg><text><![CDATA[a < b]]></text></svg>");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(document.root().text_content(), "a < b");
+
+    let (document, errors) = parse_html_with_errors("<!DOCTYPE html><p><![CDATA[x]]></p>");
+    assert_eq!(errors[0].code, ErrorCode::CdataInHtmlContent);
+    assert_eq!(document.root().text_content(), "");
+}
@@ -0,0 +1,189 @@
+use ast::{Document, QuirksMode};
+use parser::{parse_html, parse_html_with_options, ErrorCode, ParseOptions};
+
+fn parse(source: &str, options: ParseOptions) -> Document {
+    parse_html_with_options(source, &options).document
+}
+
+fn codes(source: &str) -> Vec<&'static str> {
+    let options = ParseOptions::new().collect_errors(true);
+    parse_html_with_options(source, &options)
+        .errors
+        .into_iter()
+        .map(|error| error.code.as_str())
+        .collect()
+}
+
+#[test]
+fn noscript_is_text_with_scripting() {
+    let source = "<noscript><p>fallback</p></noscript>";
+
+    let document = parse(source, ParseOptions::new());
+    let noscript = document.root().first_child().unwrap();
+    assert!(noscript.first_child().unwrap().is_element());
+
+    let document = parse(source, ParseOptions::new().scripting(true));
+    let noscript = document.root().first_child().unwrap();
+    let text = noscript.first_child().unwrap();
+    assert!(text.is_text());
+    assert_eq!(text.text_content(), "<p>fallback</p>");
+}
+
+#[test]
+fn quirks_mode_follows_the_doctype() {
+    let quirks_mode = |source| parse_html(source).quirks_mode();
+    assert_eq!(quirks_mode("<!DOCTYPE html><p>"), QuirksMode::NoQuirks);
+    assert_eq!(quirks_mode("<p>"), QuirksMode::Quirks);
+    assert_eq!(quirks_mode(""), QuirksMode::Quirks);
+    assert_eq!(
+        quirks_mode("<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01 Transitional//EN\">"),
+        QuirksMode::Quirks
+    );
+    assert_eq!(
+        quirks_mode(
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \
+             \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">"
+        ),
+        QuirksMode::LimitedQuirks
+    );
+    assert_eq!(quirks_mode("<p><!DOCTYPE html>"), QuirksMode::Quirks);
+}
+
+#[test]
+fn srcdoc_documents_are_never_quirky() {
+    let document = parse("<p>", ParseOptions::new().iframe_srcdoc(true));
+    assert_eq!(document.quirks_mode(), QuirksMode::NoQuirks);
+}
+
+#[test]
+fn tables_stay_in_paragraphs_in_quirks_mode() {
+    let document = parse_html("<p><table></table>");
+    let p = document.root().first_child().unwrap();
+    assert_eq!(p.children().count(), 1);
+
+    let document = parse_html("<!DOCTYPE html><p><table></table>");
+    assert_eq!(document.root().children().count(), 3);
+}
+
+#[test]
+fn errors_are_collected_only_when_asked() {
+    let parsed = parse_html_with_options("<p a a>", &ParseOptions::new());
+    assert!(parsed.errors.is_empty());
+
+    assert_eq!(
+        codes("<!DOCTYPE html><p a=1 a=2 b=\"x\"c>"),
+        vec![
+            "missing-whitespace-between-attributes",
+            "duplicate-attribute"
+        ]
+    );
+}
+
+#[test]
+fn tree_errors() {
+    assert_eq!(codes("<p>text"), vec!["missing-doctype"]);
+    assert_eq!(
+        codes("<!DOCTYPE html></div><div><span></div><br/><div/>"),
+        vec![
+            "unexpected-end-tag",
+            "unclosed-element",
+            "non-void-html-element-start-tag-with-trailing-solidus"
+        ]
+    );
+    assert_eq!(
+        codes("<!DOCTYPE html><ul><li>a<li>b</ul><div>"),
+        vec!["unclosed-element"]
+    );
+}
+
+#[test]
+fn error_locations() {
+    let options = ParseOptions::new().collect_errors(true);
+    let errors = parse_html_with_options("<!DOCTYPE html>\n<p a\"b>", &options).errors;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].code,
+        ErrorCode::UnexpectedCharacterInAttributeName
+    );
+    assert_eq!(errors[0].span.start.line, 2);
+}
+
+#[test]
+fn malformed_input_is_recovered_from() {
+    let sources = [
+        "<",
+        "</",
+        "</>",
+        "<a",
+        "<a b",
+        "<a b=",
+        "<a b='",
+        "<!",
+        "<!-",
+        "<!--",
+        "<!-- -",
+        "<!DOCTYPE",
+        "<!DOCTYPE html PUBLIC",
+        "&#",
+        "&#x;",
+        "&#1114112;",
+        "&notanentity;",
+        "\0",
+        "<script><!--",
+        "<a/ b>",
+        "<?xml?>",
+    ];
+    let options = ParseOptions::new().collect_errors(true);
+    for source in sources {
+        let parsed = parse_html_with_options(source, &options);
+        assert!(!parsed.errors.is_empty(), "no errors for {source:?}");
+    }
+}
+
+#[test]
+fn comments_can_be_dropped() {
+    let document = parse(
+        "<p>a<!-- b -->c</p>",
+        ParseOptions::new().keep_comments(false),
+    );
+    let p = document.root().first_child().unwrap();
+    assert!(p.children().all(|child| child.is_text()));
+    assert_eq!(p.text_content(), "ac");
+}
+
+#[test]
+fn depth_is_limited() {
+    let document = parse(
+        "<div><div><div><p>deep</p></div></div></div>",
+        ParseOptions::new().max_depth(2),
+    );
+    let inner = document
+        .root()
+        .first_child()
+        .unwrap()
+        .first_child()
+        .unwrap();
+    let names: Vec<_> = inner
+        .children()
+        .map(|child| match child.as_element() {
+            Some(element) => element.tag_name().to_string(),
+            None => child.text_content(),
+        })
+        .collect();
+    assert_eq!(names, vec!["div", "p", "deep"]);
+}
+
+#[test]
+fn size_is_limited() {
+    let document = parse("<p>héllo</p>", ParseOptions::new().max_size(5));
+    assert_eq!(document.root().text_content(), "h");
+}
+
+#[test]
+fn spans_can_be_left_out() {
+    let document = parse("<p>a</p>", ParseOptions::new().track_spans(false));
+    assert!(document
+        .root()
+        .descendants()
+        .all(|node| node.span().is_none()));
+}
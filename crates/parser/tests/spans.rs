@@ -0,0 +1,55 @@
+use ast::{Location, Span};
+use parser::parse_html;
+
+fn location(offset: usize, line: usize, column: usize) -> Location {
+    Location {
+        offset,
+        line,
+        column,
+    }
+}
+
+#[test]
+fn nodes_record_where_they_came_from() {
+    let source = "<div>\n  <p>héllo</p>\n<br/></div>";
+    let document = parse_html(source);
+
+    let div = document.root().first_child().unwrap();
+    assert_eq!(
+        div.span(),
+        Some(Span::new(location(0, 1, 1), location(33, 3, 12)))
+    );
+
+    let indentation = div.first_child().unwrap();
+    let span = indentation.span().unwrap();
+    assert_eq!(&source[span.start.offset..span.end.offset], "\n  ");
+
+    let p = indentation.next_sibling().unwrap();
+    assert_eq!(
+        p.span(),
+        Some(Span::new(location(8, 2, 3), location(21, 2, 15)))
+    );
+
+    let text = p.first_child().unwrap();
+    let span = text.span().unwrap();
+    assert_eq!(&source[span.start.offset..span.end.offset], "héllo");
+    assert_eq!(span.end.column, 11);
+
+    let br = p.next_sibling().unwrap().next_sibling().unwrap();
+    let span = br.span().unwrap();
+    assert_eq!(&source[span.start.offset..span.end.offset], "<br/>");
+
+    assert_eq!(
+        document.root().span(),
+        Some(Span::new(location(0, 1, 1), location(33, 3, 12)))
+    );
+}
+
+#[test]
+fn unclosed_elements_end_with_the_source() {
+    let document = parse_html("<ul><li>one");
+    let list = document.root().first_child().unwrap();
+    assert_eq!(list.span().unwrap().end, location(11, 1, 12));
+    let item = list.first_child().unwrap();
+    assert_eq!(item.span().unwrap().end, location(11, 1, 12));
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+use std::thread;
+
+use ast::{Document, Element, Node, NodeId, Text};
+use parser::parse_html;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn ast_types_are_send_and_sync() {
+    assert_send_sync::<Document>();
+    assert_send_sync::<NodeId>();
+    assert_send_sync::<Node>();
+    assert_send_sync::<Element>();
+    assert_send_sync::<Text>();
+}
+
+#[test]
+fn documents_can_be_shared_across_threads() {
+    let document = Arc::new(parse_html(
+        "<ul><li>one</li><li>two</li><li>three</li><li>four</li></ul>",
+    ));
+    let list = document.root().first_child().unwrap().id();
+
+    let counts: Vec<usize> = thread::scope(|scope| {
+        let handles: Vec<_> = document
+            .node(list)
+            .children()
+            .map(|item| {
+                let id = item.id();
+                let document = Arc::clone(&document);
+                scope.spawn(move || document.node(id).descendants().count())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert_eq!(counts, vec![1, 1, 1, 1]);
+}
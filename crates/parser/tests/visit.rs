@@ -0,0 +1,122 @@
+use ast::{
+    walk, walk_mut, Document, Element, NodeId, Text, TreeWalker, Visit, Visitor, VisitorMut,
+};
+use parser::parse_html;
+
+#[derive(Default)]
+struct Trace(Vec<String>);
+
+impl<'a> Visitor<'a> for Trace {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        self.0.push(format!("<{}>", element.tag_name()));
+        if element.tag_name() == "skip" {
+            Visit::SkipChildren
+        } else {
+            Visit::Continue
+        }
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        self.0.push(format!("</{}>", element.tag_name()));
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        self.0.push(text.text().to_string());
+        if text.text() == "stop" {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        }
+    }
+}
+
+#[test]
+fn visitor_enters_and_leaves_in_order() {
+    let document = parse_html("<div><p>a</p><skip><b>hidden</b></skip><i>b</i></div>");
+    let mut trace = Trace::default();
+    walk(document.root(), &mut trace);
+    assert_eq!(
+        trace.0,
+        vec!["<div>", "<p>", "a", "</p>", "<skip>", "</skip>", "<i>", "b", "</i>", "</div>"]
+    );
+}
+
+#[test]
+fn visitor_can_stop_early() {
+    let document = parse_html("<div><p>stop</p><p>never</p></div>");
+    let mut trace = Trace::default();
+    walk(document.root(), &mut trace);
+    assert_eq!(trace.0, vec!["<div>", "<p>", "stop"]);
+}
+
+#[test]
+fn walk_stays_inside_the_subtree() {
+    let document = parse_html("<div><p>a</p></div><span>b</span>");
+    let mut trace = Trace::default();
+    walk(document.root().first_child().unwrap(), &mut trace);
+    assert_eq!(trace.0, vec!["<div>", "<p>", "a", "</p>", "</div>"]);
+}
+
+/// Removes every `script` element and upper-cases all text.
+struct Rewrite;
+
+impl VisitorMut for Rewrite {
+    fn enter_element(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        let element = document.node(id).as_element().unwrap();
+        if element.tag_name() == "script" {
+            document.detach(id);
+            return Visit::SkipChildren;
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        let value = document.node(id).text_content().to_uppercase();
+        document.set_text_content(id, &value);
+        Visit::Continue
+    }
+}
+
+#[test]
+fn mutable_visitor_can_remove_nodes() {
+    let mut document =
+        parse_html("<div><script>x</script><p>a</p><script>y</script><p>b</p></div>");
+    walk_mut(&mut document, Document::ROOT, &mut Rewrite);
+
+    let div = document.root().first_child().unwrap();
+    let names: Vec<&str> = div
+        .children()
+        .map(|child| child.as_element().unwrap().tag_name())
+        .collect();
+    assert_eq!(names, vec!["p", "p"]);
+    assert_eq!(div.text_content(), "AB");
+}
+
+#[test]
+fn tree_walker_moves_around() {
+    let document = parse_html("<ul><li>a</li><li>b</li></ul>");
+    let list = document.root().first_child().unwrap();
+    let mut walker = TreeWalker::new(list);
+
+    assert_eq!(walker.parent_node(), None);
+    let first = walker.first_child().unwrap();
+    let second = walker.next_sibling().unwrap();
+    assert_eq!(walker.next_sibling(), None);
+    assert_eq!(walker.previous_sibling(), Some(first));
+    assert_eq!(walker.parent_node(), Some(list));
+
+    let mut order = Vec::new();
+    while let Some(node) = walker.next_node() {
+        order.push(node);
+    }
+    assert_eq!(order.len(), 4);
+    assert_eq!(order[2], second);
+    assert_eq!(walker.current().text_content(), "b");
+
+    let mut reverse = Vec::new();
+    while let Some(node) = walker.previous_node() {
+        reverse.push(node);
+    }
+    assert_eq!(reverse, vec![second, order[1], first, list]);
+}
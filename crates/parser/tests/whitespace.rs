@@ -0,0 +1,38 @@
+use parser::{parse_html, parse_html_with_options, ParseOptions};
+
+fn texts(document: &ast::Document) -> Vec<String> {
+    document
+        .descendants()
+        .filter_map(|node| node.as_text())
+        .map(|text| text.text().to_string())
+        .collect()
+}
+
+#[test]
+fn whitespace_is_kept_by_default() {
+    let document = parse_html("<div>\n  <p>a <b>b</b> c</p>\n</div>");
+    assert_eq!(texts(&document), vec!["\n  ", "a ", "b", " c", "\n"]);
+    let p = document
+        .root()
+        .first_child()
+        .unwrap()
+        .children()
+        .nth(1)
+        .unwrap();
+    assert_eq!(p.text_content(), "a b c");
+}
+
+#[test]
+fn whitespace_can_be_stripped() {
+    let options = ParseOptions::new().strip_whitespace(true);
+    let document =
+        parse_html_with_options("<div>\n  <p>a <b>b</b> c</p>\n</div>", &options).document;
+    assert_eq!(texts(&document), vec!["a ", "b", "c"]);
+}
+
+#[test]
+fn newline_after_pre_is_dropped() {
+    let document =
+        parse_html("<pre>\nfirst\n</pre><textarea>\n\nsecond</textarea><div>\nthird</div>");
+    assert_eq!(texts(&document), vec!["first\n", "\nsecond", "\nthird"]);
+}
@@ -0,0 +1,204 @@
+use crate::Policy;
+use ast::{elements, walk_mut, Document, NodeId, Visit, VisitorMut};
+use parser::parse_html;
+
+/// Attributes whose values are URLs.
+const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "data",
+    "formaction",
+    "href",
+    "longdesc",
+    "manifest",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+/// How many times a tree is parsed again from its markup before giving up
+/// on keeping any elements.
+const MAX_REPARSES: usize = 4;
+
+pub(crate) fn clean(document: &mut Document, policy: &Policy) {
+    walk_mut(document, Document::ROOT, &mut Cleaner { policy });
+
+    // Unwrapping can leave elements where the parser never puts them, such
+    // as a div inside a p once a button between them is gone. Markup for
+    // such a tree reads back as a different one, which is how mutation XSS
+    // gets past sanitizers. The tree is replaced by the one its markup
+    // parses to, cleaned again, until the two agree.
+    for _ in 0..MAX_REPARSES {
+        let html = serializer::serialize(document);
+        let reparsed = parse_html(&html);
+        if serializer::serialize(&reparsed) == html {
+            return;
+        }
+        *document = reparsed;
+        walk_mut(document, Document::ROOT, &mut Cleaner { policy });
+    }
+    // Text alone always reads back the same.
+    walk_mut(
+        document,
+        Document::ROOT,
+        &mut Cleaner {
+            policy: &Policy::text_only(),
+        },
+    );
+}
+
+struct Cleaner<'p> {
+    policy: &'p Policy,
+}
+
+/// Elements whose contents browsers don't read as markup, so they can't be
+/// checked and are never kept.
+fn is_opaque(name: &str) -> bool {
+    elements::is_raw_text(name) || matches!(name, "noscript" | "plaintext")
+}
+
+impl Cleaner<'_> {
+    fn clean_attributes(&self, document: &mut Document, id: NodeId) {
+        let element = document.element_mut(id).unwrap();
+        let name = element.tag_name().to_string();
+
+        let removed: Vec<String> = element
+            .attributes()
+            .iter()
+            .map(|attribute| attribute.name())
+            .filter(|attribute| {
+                attribute.starts_with("on") || !self.policy.allows_attribute(&name, attribute)
+            })
+            .map(str::to_string)
+            .collect();
+        for attribute in removed {
+            element.remove_attribute(&attribute);
+        }
+
+        for &attribute in URL_ATTRIBUTES {
+            if element
+                .get_attribute(attribute)
+                .is_some_and(|url| !self.allows_url(url))
+            {
+                element.remove_attribute(attribute);
+            }
+        }
+        if let Some(srcset) = element.get_attribute("srcset") {
+            let candidates: Vec<&str> = srcset
+                .split(',')
+                .filter(|candidate| {
+                    let url = candidate.split_ascii_whitespace().next().unwrap_or("");
+                    !url.is_empty() && self.allows_url(url)
+                })
+                .collect();
+            if candidates.is_empty() {
+                element.remove_attribute("srcset");
+            } else {
+                let srcset = candidates.join(",");
+                element.set_attribute("srcset", &srcset);
+            }
+        }
+
+        if self.policy.add_noopener
+            && matches!(name.as_str(), "a" | "area")
+            && element.get_attribute("href").is_some()
+        {
+            let rel = element.get_attribute("rel").unwrap_or_default();
+            if !rel
+                .split_ascii_whitespace()
+                .any(|token| token.eq_ignore_ascii_case("noopener"))
+            {
+                let rel = match rel.trim() {
+                    "" => "noopener".to_string(),
+                    rel => format!("{} noopener", rel),
+                };
+                element.set_attribute("rel", &rel);
+            }
+        }
+    }
+
+    fn allows_url(&self, url: &str) -> bool {
+        match scheme(url) {
+            Some(scheme) => self.policy.url_schemes.contains(&scheme),
+            None => true,
+        }
+    }
+}
+
+/// The lowercased scheme of a URL, read the way browsers read it: leading
+/// and trailing control characters and spaces are ignored, as are tabs and
+/// newlines anywhere. Relative URLs have none.
+fn scheme(url: &str) -> Option<String> {
+    let url = url.trim_matches(|ch: char| ch <= ' ');
+    let mut scheme = String::new();
+    for ch in url.chars().filter(|&ch| !matches!(ch, '\t' | '\n' | '\r')) {
+        match ch {
+            ':' if !scheme.is_empty() => return Some(scheme),
+            'a'..='z' | 'A'..='Z' => scheme.push(ch.to_ascii_lowercase()),
+            '0'..='9' | '+' | '-' | '.' if !scheme.is_empty() => scheme.push(ch),
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Whether a comment can be written back without ending early or running
+/// into the markup that follows it.
+fn is_safe_comment(text: &str) -> bool {
+    !text.contains("--") && !text.contains(['<', '>']) && !text.ends_with('-')
+}
+
+impl VisitorMut for Cleaner<'_> {
+    fn enter_element(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        let name = document.node(id).as_element().unwrap().tag_name();
+        if is_opaque(name) || self.policy.remove_contents.contains(name) {
+            document.detach(id);
+            return Visit::SkipChildren;
+        }
+        if self.policy.elements.contains(name) {
+            self.clean_attributes(document, id);
+        }
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        let element = document.node(id);
+        let parent = match element.parent() {
+            Some(parent) => parent.id(),
+            // Removed on the way in.
+            None => return Visit::Continue,
+        };
+        if self
+            .policy
+            .elements
+            .contains(element.as_element().unwrap().tag_name())
+        {
+            return Visit::Continue;
+        }
+
+        // The children have been cleaned already, and take the element's
+        // place.
+        let children: Vec<NodeId> = element.children().map(|child| child.id()).collect();
+        for child in children {
+            document
+                .insert_before(parent, child, Some(id))
+                .expect("children can be moved next to their parent");
+        }
+        document.detach(id);
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        let comment = document.node(id).as_comment().unwrap();
+        if !self.policy.keep_comments || !is_safe_comment(comment.text()) {
+            document.detach(id);
+        }
+        Visit::Continue
+    }
+
+    fn visit_doctype(&mut self, document: &mut Document, id: NodeId) -> Visit {
+        document.detach(id);
+        Visit::Continue
+    }
+}
@@ -0,0 +1,29 @@
+//! Cleans untrusted HTML so that it can be shown on a page.
+//!
+//! The input is parsed, everything the [`Policy`] doesn't allow is removed
+//! from the tree, and the tree is written back out. Since the output is
+//! produced by the serializer rather than patched from the input, text is
+//! always escaped and attribute values are always quoted, so no markup can
+//! appear in the output other than the elements and attributes that were
+//! kept. The output also parses back to the tree it was written from, so a
+//! browser reads it the same way the sanitizer checked it.
+
+mod clean;
+mod policy;
+
+use ast::Document;
+use parser::parse_html;
+
+pub use policy::Policy;
+
+/// Parses `source` and returns it cleaned by `policy`.
+pub fn sanitize(source: &str, policy: &Policy) -> String {
+    let mut document = parse_html(source);
+    sanitize_document(&mut document, policy);
+    serializer::serialize(&document)
+}
+
+/// Removes everything `policy` doesn't allow from `document`, in place.
+pub fn sanitize_document(document: &mut Document, policy: &Policy) {
+    clean::clean(document, policy);
+}
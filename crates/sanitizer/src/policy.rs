@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+
+/// Elements kept by the default policy.
+const ELEMENTS: &[&str] = &[
+    "a",
+    "abbr",
+    "acronym",
+    "article",
+    "aside",
+    "b",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "br",
+    "caption",
+    "center",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "small",
+    "span",
+    "strike",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "tt",
+    "u",
+    "ul",
+    "var",
+    "wbr",
+];
+
+/// Attributes kept on any allowed element by the default policy.
+const GENERIC_ATTRIBUTES: &[&str] = &["dir", "lang", "title"];
+
+/// Attributes kept on specific elements by the default policy.
+const ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "hreflang", "rel"]),
+    ("bdo", &["dir"]),
+    ("blockquote", &["cite"]),
+    ("col", &["align", "char", "charoff", "span"]),
+    ("colgroup", &["align", "char", "charoff", "span"]),
+    ("data", &["value"]),
+    ("del", &["cite", "datetime"]),
+    ("details", &["open"]),
+    ("img", &["align", "alt", "height", "src", "srcset", "width"]),
+    ("ins", &["cite", "datetime"]),
+    ("ol", &["reversed", "start", "type"]),
+    ("q", &["cite"]),
+    ("table", &["align", "char", "charoff", "summary"]),
+    ("tbody", &["align", "char", "charoff"]),
+    (
+        "td",
+        &["align", "char", "charoff", "colspan", "headers", "rowspan"],
+    ),
+    ("tfoot", &["align", "char", "charoff"]),
+    (
+        "th",
+        &[
+            "abbr", "align", "char", "charoff", "colspan", "headers", "rowspan", "scope",
+        ],
+    ),
+    ("thead", &["align", "char", "charoff"]),
+    ("time", &["datetime"]),
+    ("tr", &["align", "char", "charoff"]),
+    ("ul", &["type"]),
+];
+
+/// URL schemes allowed by the default policy.
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// Elements removed together with everything inside them by the default
+/// policy.
+const REMOVE_CONTENTS: &[&str] = &[
+    "applet",
+    "embed",
+    "frame",
+    "frameset",
+    "iframe",
+    "math",
+    "noembed",
+    "noframes",
+    "noscript",
+    "object",
+    "plaintext",
+    "script",
+    "style",
+    "svg",
+    "template",
+    "xmp",
+];
+
+/// Decides what a sanitized document may contain.
+///
+/// Elements that aren't allowed are replaced by their children, unless they
+/// are in `remove_contents`, in which case their children go too. Elements
+/// that browsers read as raw text, such as `script`, `style` and `noscript`,
+/// are always removed with their contents, because what's inside them can't
+/// be checked. Attributes are kept only if they are allowed for the element
+/// or for every element, and never if they are event handlers such as
+/// `onclick`.
+///
+/// The default policy keeps text formatting, lists, tables, links and images,
+/// and allows `http`, `https`, `mailto` and `tel` URLs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    /// Names of the elements that are kept.
+    pub elements: HashSet<String>,
+    /// Attributes kept on any allowed element.
+    pub generic_attributes: HashSet<String>,
+    /// Attributes kept on specific elements, keyed by element name.
+    pub attributes: HashMap<String, HashSet<String>>,
+    /// Schemes allowed in URL attributes such as `href` and `src`. Relative
+    /// URLs are always allowed.
+    pub url_schemes: HashSet<String>,
+    /// Elements that are removed along with their contents.
+    pub remove_contents: HashSet<String>,
+    /// Keep comments that can be written back safely.
+    pub keep_comments: bool,
+    /// Add `noopener` to the `rel` of links, so pages they open can't reach
+    /// back through `window.opener`.
+    pub add_noopener: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            elements: set(ELEMENTS),
+            generic_attributes: set(GENERIC_ATTRIBUTES),
+            attributes: ATTRIBUTES
+                .iter()
+                .map(|&(element, attributes)| (element.to_string(), set(attributes)))
+                .collect(),
+            url_schemes: set(URL_SCHEMES),
+            remove_contents: set(REMOVE_CONTENTS),
+            keep_comments: false,
+            add_noopener: true,
+        }
+    }
+}
+
+fn set(names: &[&str]) -> HashSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+impl Policy {
+    pub fn new() -> Policy {
+        Policy::default()
+    }
+
+    /// A policy that allows nothing but text.
+    pub fn text_only() -> Policy {
+        Policy {
+            elements: HashSet::new(),
+            generic_attributes: HashSet::new(),
+            attributes: HashMap::new(),
+            ..Policy::default()
+        }
+    }
+
+    pub fn allow_element(mut self, name: &str) -> Policy {
+        let name = name.to_ascii_lowercase();
+        self.remove_contents.remove(&name);
+        self.elements.insert(name);
+        self
+    }
+
+    /// Stops keeping an element. Its children are kept in its place.
+    pub fn deny_element(mut self, name: &str) -> Policy {
+        self.elements.remove(&name.to_ascii_lowercase());
+        self
+    }
+
+    /// Removes an element together with its contents.
+    pub fn remove_contents(mut self, name: &str) -> Policy {
+        let name = name.to_ascii_lowercase();
+        self.elements.remove(&name);
+        self.remove_contents.insert(name);
+        self
+    }
+
+    pub fn allow_attribute(mut self, element: &str, name: &str) -> Policy {
+        self.attributes
+            .entry(element.to_ascii_lowercase())
+            .or_default()
+            .insert(name.to_ascii_lowercase());
+        self
+    }
+
+    pub fn deny_attribute(mut self, element: &str, name: &str) -> Policy {
+        if let Some(attributes) = self.attributes.get_mut(&element.to_ascii_lowercase()) {
+            attributes.remove(&name.to_ascii_lowercase());
+        }
+        self
+    }
+
+    pub fn allow_generic_attribute(mut self, name: &str) -> Policy {
+        self.generic_attributes.insert(name.to_ascii_lowercase());
+        self
+    }
+
+    pub fn allow_url_scheme(mut self, scheme: &str) -> Policy {
+        self.url_schemes.insert(scheme.to_ascii_lowercase());
+        self
+    }
+
+    pub fn deny_url_scheme(mut self, scheme: &str) -> Policy {
+        self.url_schemes.remove(&scheme.to_ascii_lowercase());
+        self
+    }
+
+    pub fn keep_comments(mut self, keep_comments: bool) -> Policy {
+        self.keep_comments = keep_comments;
+        self
+    }
+
+    pub fn add_noopener(mut self, add_noopener: bool) -> Policy {
+        self.add_noopener = add_noopener;
+        self
+    }
+
+    pub(crate) fn allows_attribute(&self, element: &str, name: &str) -> bool {
+        self.generic_attributes.contains(name)
+            || self
+                .attributes
+                .get(element)
+                .is_some_and(|attributes| attributes.contains(name))
+    }
+}
@@ -0,0 +1,158 @@
+use sanitizer::{sanitize, Policy};
+
+fn clean(source: &str) -> String {
+    sanitize(source, &Policy::default())
+}
+
+#[test]
+fn keeps_allowed_markup() {
+    let source = "<p>Some <b>bold</b> and <em title=\"x\">em</em> text</p><ul><li>one</li></ul>";
+    assert_eq!(clean(source), source);
+}
+
+#[test]
+fn unwraps_disallowed_elements() {
+    assert_eq!(
+        clean("<form action=\"/x\"><p>Name <input name=\"n\"></p><button>Go</button></form>"),
+        "<p>Name </p>Go"
+    );
+    assert_eq!(clean("<html><body><p>x</p></body></html>"), "<p>x</p>");
+}
+
+#[test]
+fn unwrapping_never_nests_what_the_parser_would_not() {
+    // Without the button, the inner div would close the p when parsed.
+    assert_eq!(
+        clean("<div><p><option><button><div>"),
+        "<div><p></p><div></div></div>"
+    );
+    assert_eq!(
+        clean("<ul><li><button><li>x"),
+        "<ul><li></li><li>x</li></ul>"
+    );
+}
+
+#[test]
+fn removes_scripts_and_styles_with_their_contents() {
+    assert_eq!(
+        clean("<p>a<script>alert(1)</script>b<style>p { color: red }</style>c</p>"),
+        "<p>abc</p>"
+    );
+}
+
+#[test]
+fn raw_text_elements_are_removed_even_when_allowed() {
+    let policy = Policy::default()
+        .allow_element("script")
+        .allow_element("noscript");
+    assert_eq!(
+        sanitize("<script>alert(1)</script><noscript>x</noscript>ok", &policy),
+        "ok"
+    );
+}
+
+#[test]
+fn filters_attributes_per_element() {
+    assert_eq!(
+        clean("<p class=\"c\" lang=\"en\"><img src=\"a.png\" alt=\"A\" style=\"x\"></p>"),
+        "<p lang=\"en\"><img src=\"a.png\" alt=\"A\"></p>"
+    );
+    let policy = Policy::default()
+        .allow_attribute("p", "class")
+        .deny_attribute("img", "alt");
+    assert_eq!(
+        sanitize("<p class=\"c\"><img src=\"a.png\" alt=\"A\"></p>", &policy),
+        "<p class=\"c\"><img src=\"a.png\"></p>"
+    );
+}
+
+#[test]
+fn event_handlers_are_always_removed() {
+    let policy = Policy::default().allow_generic_attribute("onclick");
+    assert_eq!(
+        sanitize("<b onclick=\"alert(1)\" onmouseover=\"x\">b</b>", &policy),
+        "<b>b</b>"
+    );
+}
+
+#[test]
+fn filters_url_schemes() {
+    assert_eq!(
+        clean("<a href=\"https://example.com\">a</a><a href=\"javascript:alert(1)\">b</a>"),
+        "<a href=\"https://example.com\" rel=\"noopener\">a</a><a>b</a>"
+    );
+    assert_eq!(
+        clean("<img src=\"/relative.png\"><img src=\"data:image/png;base64,AAAA\">"),
+        "<img src=\"/relative.png\"><img>"
+    );
+    let policy = Policy::default().allow_url_scheme("data");
+    assert_eq!(
+        sanitize("<img src=\"data:image/png;base64,AAAA\">", &policy),
+        "<img src=\"data:image/png;base64,AAAA\">"
+    );
+}
+
+#[test]
+fn filters_srcset_candidates() {
+    assert_eq!(
+        clean("<img srcset=\"a.png 1x, javascript:alert(1) 2x, https://x/b.png 3x\">"),
+        "<img srcset=\"a.png 1x, https://x/b.png 3x\">"
+    );
+    assert_eq!(clean("<img srcset=\"javascript:alert(1)\">"), "<img>");
+}
+
+#[test]
+fn adds_noopener_to_links() {
+    assert_eq!(
+        clean("<a href=\"/x\" rel=\"nofollow\">x</a>"),
+        "<a href=\"/x\" rel=\"nofollow noopener\">x</a>"
+    );
+    assert_eq!(
+        clean("<a href=\"/x\" rel=\"NoOpener\">x</a>"),
+        "<a href=\"/x\" rel=\"NoOpener\">x</a>"
+    );
+    assert_eq!(clean("<a name=\"x\">x</a>"), "<a>x</a>");
+    let policy = Policy::default().add_noopener(false);
+    assert_eq!(
+        sanitize("<a href=\"/x\">x</a>", &policy),
+        "<a href=\"/x\">x</a>"
+    );
+}
+
+#[test]
+fn removes_comments_and_doctypes() {
+    assert_eq!(clean("<!DOCTYPE html><!-- note --><p>x</p>"), "<p>x</p>");
+    let policy = Policy::default().keep_comments(true);
+    assert_eq!(
+        sanitize("<!-- note --><p>x</p><!--a<b-->", &policy),
+        "<!-- note --><p>x</p>"
+    );
+}
+
+#[test]
+fn removes_contents_of_configured_elements() {
+    let policy = Policy::default().remove_contents("table");
+    assert_eq!(
+        sanitize("<p>a</p><table><tr><td>b</td></tr></table>", &policy),
+        "<p>a</p>"
+    );
+}
+
+#[test]
+fn text_only_policy_keeps_text() {
+    assert_eq!(
+        sanitize(
+            "<p>Hello <b>world</b> &amp; <script>x</script>friends</p>",
+            &Policy::text_only()
+        ),
+        "Hello world &amp; friends"
+    );
+}
+
+#[test]
+fn escapes_text_from_unwrapped_elements() {
+    assert_eq!(
+        clean("<textarea><img src=x onerror=alert(1)></textarea>"),
+        "&lt;img src=x onerror=alert(1)&gt;"
+    );
+}
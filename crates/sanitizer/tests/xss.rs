@@ -0,0 +1,318 @@
+use ast::Document;
+use parser::parse_html;
+use sanitizer::{sanitize, Policy};
+
+/// Known XSS vectors, including filter evasion tricks from the OWASP cheat
+/// sheet, mutation XSS that relies on markup being read differently the
+/// second time, and namespace confusion between HTML, SVG and MathML.
+const VECTORS: &[&str] = &[
+    // Scripts.
+    "<script>alert(1)</script>",
+    "<SCRIPT SRC=//evil.example/x.js></SCRIPT>",
+    "<script/xss src=//evil.example/x.js></script>",
+    "<<script>alert(1);//<</script>",
+    "<script>alert(1)",
+    "<scr<script>ipt>alert(1)</scr</script>ipt>",
+    "<ScRiPt>alert(1)</sCrIpT>",
+    // Event handlers.
+    "<img src=x onerror=alert(1)>",
+    "<img src=x ONERROR=alert(1)>",
+    "<img src=x onerror=\"alert(1)\"//>",
+    "<body onload=alert(1)>",
+    "<div/onmouseover='alert(1)'>x</div>",
+    "<b \"\"\"><script>alert(1)</script>\">",
+    "<p title=\"</p><img src=x onerror=alert(1)>\">x</p>",
+    "<details open ontoggle=alert(1)>",
+    "<input autofocus onfocus=alert(1)>",
+    "<video><source onerror=alert(1)>",
+    "<marquee onstart=alert(1)>",
+    // URLs.
+    "<a href=\"javascript:alert(1)\">x</a>",
+    "<a href=\"JaVaScRiPt:alert(1)\">x</a>",
+    "<a href=\" javascript:alert(1)\">x</a>",
+    "<a href=\"\x01javascript:alert(1)\">x</a>",
+    "<a href=\"jav\tascript:alert(1)\">x</a>",
+    "<a href=\"jav&#x09;ascript:alert(1)\">x</a>",
+    "<a href=\"jav&#x0A;ascript:alert(1)\">x</a>",
+    "<a href=\"&#106;&#97;&#118;&#97;&#115;&#99;&#114;&#105;&#112;&#116;&#58;alert(1)\">x</a>",
+    "<a href=\"&#x6A;&#x61;&#x76;&#x61;&#x73;&#x63;&#x72;&#x69;&#x70;&#x74;&#x3A;alert(1)\">x</a>",
+    "<a href=\"javascript&colon;alert(1)\">x</a>",
+    "<a href=\"vbscript:msgbox(1)\">x</a>",
+    "<a href=\"data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==\">x</a>",
+    "<img src=\"javascript:alert(1)\">",
+    "<img srcset=\"javascript:alert(1) 1x\">",
+    "<blockquote cite=\"javascript:alert(1)\">x</blockquote>",
+    "<a href=javascript:alert(1)>x</a>",
+    "<form action=\"javascript:alert(1)\"><button>x</button></form>",
+    "<button formaction=\"javascript:alert(1)\">x</button>",
+    "<iframe src=\"javascript:alert(1)\"></iframe>",
+    "<object data=\"javascript:alert(1)\"></object>",
+    "<embed src=\"javascript:alert(1)\">",
+    "<meta http-equiv=\"refresh\" content=\"0;url=javascript:alert(1)\">",
+    "<base href=\"javascript:alert(1)//\">",
+    "<link rel=stylesheet href=\"javascript:alert(1)\">",
+    "<table background=\"javascript:alert(1)\"><tr><td>x</td></tr></table>",
+    // Styles.
+    "<style>@import 'javascript:alert(1)';</style>",
+    "<div style=\"background-image: url(javascript:alert(1))\">x</div>",
+    "<div style=\"width: expression(alert(1))\">x</div>",
+    "<style>*{x:expression(alert(1))}</style>",
+    // Mutation XSS.
+    "<noscript><p title=\"</noscript><img src=x onerror=alert(1)>\"></noscript>",
+    "<noscript><style></noscript><img src=x onerror=alert(1)></style></noscript>",
+    "<xmp><p title=\"</xmp><img src=x onerror=alert(1)>\"></xmp>",
+    "<noembed><img title=\"</noembed><img src=x onerror=alert(1)>\"></noembed>",
+    "<noframes><img title=\"</noframes><img src=x onerror=alert(1)>\"></noframes>",
+    "<iframe><img title=\"</iframe><img src=x onerror=alert(1)>\"></iframe>",
+    "<textarea><img title=\"</textarea><img src=x onerror=alert(1)>\"></textarea>",
+    "<title><img title=\"</title><img src=x onerror=alert(1)>\"></title>",
+    "<plaintext><img src=x onerror=alert(1)>",
+    "<!--<img src=x onerror=alert(1)>-->",
+    "<!--><img src=x onerror=alert(1)>-->",
+    "<!-- --!><img src=x onerror=alert(1)>-->",
+    "<a href=\"x\"><a href=\"javascript:alert(1)\">x</a></a>",
+    "<p><form><p></form><img src=x onerror=alert(1)>",
+    "<table><td><form></td><script>alert(1)</script>",
+    "<template><script>alert(1)</script></template>",
+    "<listing>&lt;img src=x onerror=alert(1)&gt;</listing>",
+    "<a title=\"&lt;/a&gt;&lt;img src=x onerror=alert(1)&gt;\">x</a>",
+    "<img alt=\"`<img src=x onerror=alert(1)>`\">",
+    // Namespace confusion.
+    "<svg><script>alert(1)</script></svg>",
+    "<svg onload=alert(1)>",
+    "<svg><a xlink:href=\"javascript:alert(1)\"><text>x</text></a></svg>",
+    "<svg><style><img src=x onerror=alert(1)></style></svg>",
+    "<svg></p><style><a id=\"</style><img src=x onerror=alert(1)>\">",
+    "<svg><p><style><img src=x onerror=alert(1)></style></p></svg>",
+    "<math><mtext><table><mglyph><style><img src=x onerror=alert(1)></style></mglyph></table></mtext></math>",
+    "<math><mi><table><mglyph><svg><mtext><textarea><path id=\"</textarea><img src=x onerror=alert(1)>\"></path></textarea></mtext></svg></mglyph></table></mi></math>",
+    "<form><math><mtext></form><form><mglyph><style></math><img src=x onerror=alert(1)>",
+    "<math><annotation-xml encoding=\"text/html\"><style><img src=x onerror=alert(1)></style></annotation-xml></math>",
+    "<svg><foreignObject><p><img src=x onerror=alert(1)></p></foreignObject></svg>",
+    "<math href=\"javascript:alert(1)\">x</math>",
+    "<svg><animate attributeName=href values=\"javascript:alert(1)\"/></svg>",
+    "<svg><set attributeName=onmouseover to=alert(1)></set></svg>",
+];
+
+/// Schemes that can run script when followed.
+fn is_dangerous_url(url: &str) -> bool {
+    let url: String = url
+        .trim_matches(|ch: char| ch <= ' ')
+        .chars()
+        .filter(|ch| !matches!(ch, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .to_ascii_lowercase();
+    ["javascript:", "vbscript:", "data:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+/// Checks that a document parsed from sanitized output holds nothing that
+/// could run script.
+fn assert_harmless(document: &Document, vector: &str) {
+    let policy = Policy::default();
+    for node in document.descendants() {
+        assert!(
+            node.as_comment().is_none() && node.as_doctype().is_none(),
+            "{:?} kept a comment or doctype",
+            vector
+        );
+        let element = match node.as_element() {
+            Some(element) => element,
+            None => continue,
+        };
+        assert!(
+            policy.elements.contains(element.tag_name()),
+            "{:?} kept a <{}>",
+            vector,
+            element.tag_name()
+        );
+        for attribute in element.attributes() {
+            assert!(
+                !attribute.name().starts_with("on"),
+                "{:?} kept {}",
+                vector,
+                attribute.name()
+            );
+            assert!(
+                !is_dangerous_url(attribute.value()),
+                "{:?} kept the URL in {}",
+                vector,
+                attribute.name()
+            );
+            for candidate in attribute.value().split(',') {
+                assert!(
+                    !is_dangerous_url(candidate),
+                    "{:?} kept a URL in {}",
+                    vector,
+                    attribute.name()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn vectors_are_neutralized() {
+    for vector in VECTORS {
+        let output = sanitize(vector, &Policy::default());
+        let lowercase = output.to_ascii_lowercase();
+        for needle in ["<script", "<style", "<svg", "<math", "<iframe"] {
+            assert!(
+                !lowercase.contains(needle),
+                "{:?} became {:?}",
+                vector,
+                output
+            );
+        }
+        assert!(!has_handler(&lowercase), "{:?} became {:?}", vector, output);
+        assert_harmless(&parse_html(&output), vector);
+    }
+}
+
+/// Whether the output has something that looks like an event handler
+/// attribute inside a tag.
+fn has_handler(output: &str) -> bool {
+    let mut in_tag = false;
+    let mut quote = None;
+    let bytes = output.as_bytes();
+    for (index, &byte) in bytes.iter().enumerate() {
+        match (in_tag, quote, byte) {
+            (false, _, b'<') => in_tag = true,
+            (true, None, b'>') => in_tag = false,
+            (true, None, b'"') => quote = Some(b'"'),
+            (true, Some(q), byte) if byte == q => quote = None,
+            (true, None, b' ') if output[index + 1..].starts_with("on") => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+#[test]
+fn output_is_stable_when_sanitized_again() {
+    for vector in VECTORS {
+        let output = sanitize(vector, &Policy::default());
+        assert_eq!(
+            sanitize(&output, &Policy::default()),
+            output,
+            "{:?} changed when its output was sanitized again",
+            vector
+        );
+    }
+}
+
+#[test]
+fn output_parses_to_the_sanitized_tree() {
+    for vector in VECTORS {
+        let mut document = parse_html(vector);
+        sanitizer::sanitize_document(&mut document, &Policy::default());
+        let output = serializer::serialize(&document);
+        assert_eq!(
+            serializer::serialize(&parse_html(&output)),
+            output,
+            "{:?} parses differently once written out",
+            vector
+        );
+    }
+}
+
+#[test]
+fn vectors_are_neutralized_with_comments_kept() {
+    let policy = Policy::default().keep_comments(true);
+    for vector in VECTORS {
+        let output = sanitize(vector, &policy);
+        let document = parse_html(&output);
+        for node in document.descendants() {
+            if let Some(comment) = node.as_comment() {
+                assert!(
+                    !comment.text().contains('<'),
+                    "{:?} kept the comment {:?}",
+                    vector,
+                    comment.text()
+                );
+            }
+        }
+        assert_eq!(sanitize(&output, &policy), output, "{:?}", vector);
+    }
+}
+
+/// A small xorshift generator, so generated inputs are the same every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+/// Markup built from tags whose start tags close other elements, or that
+/// stop them from doing so, and that the default policy partly unwraps.
+fn generated_inputs(count: usize) -> Vec<String> {
+    const PIECES: &[&str] = &[
+        "<p>",
+        "</p>",
+        "<div>",
+        "</div>",
+        "<li>",
+        "</li>",
+        "<ul>",
+        "<button>",
+        "</button>",
+        "<option>",
+        "<optgroup>",
+        "<select>",
+        "<math>",
+        "<mtext>",
+        "<table>",
+        "</table>",
+        "<tr>",
+        "<td>",
+        "<caption>",
+        "<h1>",
+        "<dl>",
+        "<dt>",
+        "<dd>",
+        "<ruby>",
+        "<rt>",
+        "<form>",
+        "<svg>",
+        "<desc>",
+        "<object>",
+        "<b>",
+        "x",
+    ];
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    (0..count)
+        .map(|_| {
+            let length = 1 + rng.next() % 8;
+            (0..length).map(|_| rng.pick(PIECES)).collect()
+        })
+        .collect()
+}
+
+#[test]
+fn generated_output_is_stable() {
+    for input in generated_inputs(5_000) {
+        let output = sanitize(&input, &Policy::default());
+        assert_eq!(
+            serializer::serialize(&parse_html(&output)),
+            output,
+            "{:?} parses differently once written out",
+            input
+        );
+        assert_eq!(
+            sanitize(&output, &Policy::default()),
+            output,
+            "{:?} changed when its output was sanitized again",
+            input
+        );
+    }
+}
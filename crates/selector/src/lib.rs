@@ -0,0 +1,196 @@
+//! CSS selectors for finding elements in a document.
+//!
+//! ```
+//! use selector::SelectorList;
+//!
+//! let document = parser::parse_html("<ul><li class=done>a<li>b</ul>");
+//! let selectors = SelectorList::parse("ul > li:not(.done)").unwrap();
+//! let texts: Vec<_> = selectors
+//!     .select(document.root())
+//!     .map(|element| element.text_content())
+//!     .collect();
+//! assert_eq!(texts, vec!["b"]);
+//! ```
+//!
+//! Selectors Level 4 is supported apart from namespaces, pseudo-elements and
+//! the pseudo-classes that depend on user interaction, layout or a browsing
+//! context. The supported pseudo-classes are `:root`, `:empty`,
+//! `:first-child`, `:last-child`, `:only-child`, `:first-of-type`,
+//! `:last-of-type`, `:only-of-type`, `:nth-child()`, `:nth-last-child()`,
+//! `:nth-of-type()`, `:nth-last-of-type()`, `:not()`, `:is()`, `:where()`,
+//! `:has()`, `:link`, `:any-link`, `:checked`, `:disabled` and `:enabled`.
+
+mod matching;
+mod parse;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use ast::{Descendants, Element, Node};
+
+/// A comma separated list of selectors, which matches an element when any
+/// of them does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorList {
+    selectors: Vec<Selector>,
+}
+
+impl SelectorList {
+    pub fn parse(source: &str) -> Result<SelectorList, SelectorError> {
+        parse::parse(source)
+    }
+
+    /// Whether `element` matches any of the selectors.
+    pub fn matches(&self, element: Element) -> bool {
+        self.selectors
+            .iter()
+            .any(|selector| matching::matches(selector, element))
+    }
+
+    /// Iterates over the elements below `root` that match, in document
+    /// order. `root` itself is not included.
+    pub fn select<'a, 's>(&'s self, root: Node<'a>) -> Select<'a, 's> {
+        Select {
+            descendants: root.descendants(),
+            selectors: self,
+        }
+    }
+}
+
+impl FromStr for SelectorList {
+    type Err = SelectorError;
+
+    fn from_str(source: &str) -> Result<SelectorList, SelectorError> {
+        SelectorList::parse(source)
+    }
+}
+
+/// The iterator returned by [`SelectorList::select`].
+pub struct Select<'a, 's> {
+    descendants: Descendants<'a>,
+    selectors: &'s SelectorList,
+}
+
+impl<'a> Iterator for Select<'a, '_> {
+    type Item = Element<'a>;
+
+    fn next(&mut self) -> Option<Element<'a>> {
+        self.descendants
+            .by_ref()
+            .filter_map(|node| node.as_element())
+            .find(|&element| self.selectors.matches(element))
+    }
+}
+
+/// Why a selector could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorError {
+    pub message: String,
+    /// The byte offset in the selector where the problem was found.
+    pub position: usize,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for SelectorError {}
+
+/// Compound selectors joined by combinators, e.g. `ul > li.item a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Selector {
+    compounds: Vec<Compound>,
+    /// `combinators[i]` relates `compounds[i]` to `compounds[i + 1]`.
+    combinators: Vec<Combinator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+    NextSibling,
+    SubsequentSibling,
+}
+
+/// Simple selectors that all have to match the same element.
+type Compound = Vec<Simple>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Simple {
+    Type(String),
+    Universal,
+    Id(String),
+    Class(String),
+    Attribute(AttributeSelector),
+    Pseudo(Pseudo),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AttributeSelector {
+    name: String,
+    /// The operator and value, unless only the attribute's presence is
+    /// tested.
+    test: Option<(Operator, String)>,
+    case_insensitive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// `=`
+    Equals,
+    /// `~=`
+    Includes,
+    /// `|=`
+    DashMatch,
+    /// `^=`
+    Prefix,
+    /// `$=`
+    Suffix,
+    /// `*=`
+    Substring,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pseudo {
+    Root,
+    Empty,
+    NthChild(Nth),
+    NthLastChild(Nth),
+    NthOfType(Nth),
+    NthLastOfType(Nth),
+    OnlyChild,
+    OnlyOfType,
+    Not(SelectorList),
+    /// `:is()` and `:where()`, which only differ in specificity.
+    Is(SelectorList),
+    /// `:has()`, with each selector relative to the element.
+    Has(Vec<(Combinator, Selector)>),
+    AnyLink,
+    Checked,
+    Disabled,
+    Enabled,
+}
+
+/// The `An+B` pattern of the `:nth-*` pseudo-classes, which matches the
+/// elements at 1-based positions `A * n + B` for any `n >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Nth {
+    a: i64,
+    b: i64,
+}
+
+impl Nth {
+    const FIRST: Nth = Nth { a: 0, b: 1 };
+
+    fn matches(self, position: usize) -> bool {
+        let offset = position as i64 - self.b;
+        if self.a == 0 {
+            offset == 0
+        } else {
+            offset % self.a == 0 && offset / self.a >= 0
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use ast::{Element, Node};
+
+use crate::{AttributeSelector, Combinator, Operator, Pseudo, Selector, Simple};
+
+pub(crate) fn matches(selector: &Selector, element: Element) -> bool {
+    matches_from(selector, selector.compounds.len() - 1, element, None)
+}
+
+/// Whether `element` matches `selector` up to and including the compound at
+/// `index`, with the leftmost compound related to the `anchor` element if
+/// there is one.
+fn matches_from(
+    selector: &Selector,
+    index: usize,
+    element: Element,
+    anchor: Option<(Combinator, Element)>,
+) -> bool {
+    if !selector.compounds[index]
+        .iter()
+        .all(|simple| matches_simple(simple, element))
+    {
+        return false;
+    }
+    if index == 0 {
+        return match anchor {
+            Some((combinator, anchor)) => related(combinator, anchor, element),
+            None => true,
+        };
+    }
+
+    let next = |candidate| matches_from(selector, index - 1, candidate, anchor);
+    match selector.combinators[index - 1] {
+        Combinator::Descendant => ancestors(element).any(next),
+        Combinator::Child => parent(element).is_some_and(next),
+        Combinator::NextSibling => previous_siblings(element).next().is_some_and(next),
+        Combinator::SubsequentSibling => previous_siblings(element).any(next),
+    }
+}
+
+/// Whether `element` is related to `anchor` as `combinator` says, e.g. is
+/// its child for `>`.
+fn related(combinator: Combinator, anchor: Element, element: Element) -> bool {
+    match combinator {
+        Combinator::Descendant => ancestors(element).any(|ancestor| ancestor == anchor),
+        Combinator::Child => parent(element) == Some(anchor),
+        Combinator::NextSibling => previous_siblings(element).next() == Some(anchor),
+        Combinator::SubsequentSibling => {
+            previous_siblings(element).any(|sibling| sibling == anchor)
+        }
+    }
+}
+
+fn matches_simple(simple: &Simple, element: Element) -> bool {
+    match simple {
+        Simple::Type(name) => element.tag_name().eq_ignore_ascii_case(name),
+        Simple::Universal => true,
+        Simple::Id(id) => element.id() == Some(id.as_str()),
+        Simple::Class(class) => element.class_list().any(|name| name == class),
+        Simple::Attribute(attribute) => matches_attribute(attribute, element),
+        Simple::Pseudo(pseudo) => matches_pseudo(pseudo, element),
+    }
+}
+
+fn matches_attribute(selector: &AttributeSelector, element: Element) -> bool {
+    let Some(attribute) = element
+        .attributes()
+        .iter()
+        .find(|attribute| attribute.name().eq_ignore_ascii_case(&selector.name))
+    else {
+        return false;
+    };
+    let Some((operator, expected)) = &selector.test else {
+        return true;
+    };
+
+    let (value, expected) = if selector.case_insensitive {
+        (
+            attribute.value().to_ascii_lowercase(),
+            expected.to_ascii_lowercase(),
+        )
+    } else {
+        (attribute.value().to_string(), expected.clone())
+    };
+    match operator {
+        Operator::Equals => value == expected,
+        Operator::Includes => value.split_ascii_whitespace().any(|word| word == expected),
+        Operator::DashMatch => {
+            value == expected
+                || value
+                    .strip_prefix(&expected)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        }
+        Operator::Prefix => !expected.is_empty() && value.starts_with(&expected),
+        Operator::Suffix => !expected.is_empty() && value.ends_with(&expected),
+        Operator::Substring => !expected.is_empty() && value.contains(&expected),
+    }
+}
+
+fn matches_pseudo(pseudo: &Pseudo, element: Element) -> bool {
+    let name = element.tag_name();
+    let same_type = |sibling: &Element| sibling.tag_name() == name;
+    match pseudo {
+        Pseudo::Root => element.parent().is_some_and(|parent| parent.is_document()),
+        Pseudo::Empty => element.children().all(|child| {
+            child.is_comment() || child.as_text().is_some_and(|text| text.text().is_empty())
+        }),
+        Pseudo::NthChild(nth) => nth.matches(previous_siblings(element).count() + 1),
+        Pseudo::NthLastChild(nth) => nth.matches(next_siblings(element).count() + 1),
+        Pseudo::NthOfType(nth) => {
+            nth.matches(previous_siblings(element).filter(same_type).count() + 1)
+        }
+        Pseudo::NthLastOfType(nth) => {
+            nth.matches(next_siblings(element).filter(same_type).count() + 1)
+        }
+        Pseudo::OnlyChild => {
+            previous_siblings(element).next().is_none() && next_siblings(element).next().is_none()
+        }
+        Pseudo::OnlyOfType => {
+            !previous_siblings(element).any(|sibling| same_type(&sibling))
+                && !next_siblings(element).any(|sibling| same_type(&sibling))
+        }
+        Pseudo::Not(selectors) => !selectors.matches(element),
+        Pseudo::Is(selectors) => selectors.matches(element),
+        Pseudo::Has(relative) => relative
+            .iter()
+            .any(|(combinator, selector)| has(*combinator, selector, element)),
+        Pseudo::AnyLink => matches!(name, "a" | "area") && element.get_attribute("href").is_some(),
+        Pseudo::Checked => match name {
+            "input" => {
+                let kind = element.get_attribute("type").unwrap_or("");
+                (kind.eq_ignore_ascii_case("checkbox") || kind.eq_ignore_ascii_case("radio"))
+                    && element.get_attribute("checked").is_some()
+            }
+            "option" => element.get_attribute("selected").is_some(),
+            _ => false,
+        },
+        Pseudo::Disabled => is_form_control(name) && is_disabled(element),
+        Pseudo::Enabled => is_form_control(name) && !is_disabled(element),
+    }
+}
+
+/// Whether an element matching `selector` is related to `scope` as
+/// `combinator` says.
+fn has(combinator: Combinator, selector: &Selector, scope: Element) -> bool {
+    let last = selector.compounds.len() - 1;
+    let anchor = Some((combinator, scope));
+    let matches = |node: Node| {
+        node.as_element()
+            .is_some_and(|element| matches_from(selector, last, element, anchor))
+    };
+    match combinator {
+        Combinator::Descendant | Combinator::Child => scope.descendants().any(matches),
+        Combinator::NextSibling | Combinator::SubsequentSibling => next_siblings(scope)
+            .any(|sibling| matches(sibling.as_node()) || sibling.descendants().any(matches)),
+    }
+}
+
+fn is_form_control(name: &str) -> bool {
+    matches!(
+        name,
+        "button" | "fieldset" | "input" | "optgroup" | "option" | "select" | "textarea"
+    )
+}
+
+/// Whether a form control is disabled, itself or by a disabled `fieldset` or
+/// `optgroup` around it.
+fn is_disabled(element: Element) -> bool {
+    if element.get_attribute("disabled").is_some() {
+        return true;
+    }
+    match element.tag_name() {
+        "option" => parent(element)
+            .is_some_and(|parent| parent.tag_name() == "optgroup" && is_disabled(parent)),
+        "fieldset" | "optgroup" => false,
+        _ => ancestors(element).any(|ancestor| {
+            ancestor.tag_name() == "fieldset" && ancestor.get_attribute("disabled").is_some()
+        }),
+    }
+}
+
+fn parent<'a>(element: Element<'a>) -> Option<Element<'a>> {
+    element.parent().and_then(|parent| parent.as_element())
+}
+
+fn ancestors<'a>(element: Element<'a>) -> impl Iterator<Item = Element<'a>> {
+    element.ancestors().filter_map(|node| node.as_element())
+}
+
+fn previous_siblings<'a>(element: Element<'a>) -> impl Iterator<Item = Element<'a>> {
+    std::iter::successors(element.previous_sibling(), |node| node.previous_sibling())
+        .filter_map(|node| node.as_element())
+}
+
+fn next_siblings<'a>(element: Element<'a>) -> impl Iterator<Item = Element<'a>> {
+    std::iter::successors(element.next_sibling(), |node| node.next_sibling())
+        .filter_map(|node| node.as_element())
+}
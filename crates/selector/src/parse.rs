@@ -0,0 +1,421 @@
+use crate::{
+    AttributeSelector, Combinator, Compound, Nth, Operator, Pseudo, Selector, SelectorError,
+    SelectorList, Simple,
+};
+
+pub(crate) fn parse(source: &str) -> Result<SelectorList, SelectorError> {
+    let mut parser = Parser {
+        source,
+        position: 0,
+    };
+    let list = parser.selector_list()?;
+    if let Some(ch) = parser.peek() {
+        return Err(parser.error(format!("unexpected `{ch}`")));
+    }
+    Ok(list)
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    /// The byte offset of the next character.
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.source[self.position..].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.position += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SelectorError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{expected}`")))
+        }
+    }
+
+    /// Skips whitespace, returning whether there was any.
+    fn whitespace(&mut self) -> bool {
+        let start = self.position;
+        while self.peek().is_some_and(is_whitespace) {
+            self.bump();
+        }
+        self.position > start
+    }
+
+    fn error(&self, message: String) -> SelectorError {
+        SelectorError {
+            message,
+            position: self.position,
+        }
+    }
+
+    /// Parses selectors separated by commas, up to the end of the source or
+    /// a closing parenthesis.
+    fn selector_list(&mut self) -> Result<SelectorList, SelectorError> {
+        let mut selectors = Vec::new();
+        loop {
+            self.whitespace();
+            selectors.push(self.selector()?);
+            if !self.eat(',') {
+                return Ok(SelectorList { selectors });
+            }
+        }
+    }
+
+    /// Parses the selectors of `:has()`, each of which may start with a
+    /// combinator.
+    fn relative_selector_list(&mut self) -> Result<Vec<(Combinator, Selector)>, SelectorError> {
+        let mut selectors = Vec::new();
+        loop {
+            self.whitespace();
+            let combinator = self.combinator().unwrap_or(Combinator::Descendant);
+            self.whitespace();
+            selectors.push((combinator, self.selector()?));
+            if !self.eat(',') {
+                return Ok(selectors);
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, SelectorError> {
+        let mut compounds = vec![self.compound()?];
+        let mut combinators = Vec::new();
+        loop {
+            let whitespace = self.whitespace();
+            let combinator = match self.combinator() {
+                Some(combinator) => {
+                    self.whitespace();
+                    combinator
+                }
+                None if whitespace && !matches!(self.peek(), None | Some(',' | ')')) => {
+                    Combinator::Descendant
+                }
+                None => break,
+            };
+            combinators.push(combinator);
+            compounds.push(self.compound()?);
+        }
+        Ok(Selector {
+            compounds,
+            combinators,
+        })
+    }
+
+    fn combinator(&mut self) -> Option<Combinator> {
+        let combinator = match self.peek()? {
+            '>' => Combinator::Child,
+            '+' => Combinator::NextSibling,
+            '~' => Combinator::SubsequentSibling,
+            _ => return None,
+        };
+        self.bump();
+        Some(combinator)
+    }
+
+    fn compound(&mut self) -> Result<Compound, SelectorError> {
+        let mut compound = Vec::new();
+        if self.eat('*') {
+            compound.push(Simple::Universal);
+        } else if self.at_identifier() {
+            compound.push(Simple::Type(self.identifier()?.to_ascii_lowercase()));
+        }
+        if self.peek() == Some('|') {
+            return Err(self.error("namespaces are not supported".to_string()));
+        }
+
+        loop {
+            let simple = match self.peek() {
+                Some('#') => {
+                    self.bump();
+                    Simple::Id(self.identifier()?)
+                }
+                Some('.') => {
+                    self.bump();
+                    Simple::Class(self.identifier()?)
+                }
+                Some('[') => {
+                    self.bump();
+                    Simple::Attribute(self.attribute()?)
+                }
+                Some(':') => {
+                    self.bump();
+                    Simple::Pseudo(self.pseudo()?)
+                }
+                _ => break,
+            };
+            compound.push(simple);
+        }
+
+        if compound.is_empty() {
+            return Err(match self.peek() {
+                Some(ch) => self.error(format!("expected a selector, found `{ch}`")),
+                None => self.error("expected a selector".to_string()),
+            });
+        }
+        Ok(compound)
+    }
+
+    fn attribute(&mut self) -> Result<AttributeSelector, SelectorError> {
+        self.whitespace();
+        let name = self.identifier()?.to_ascii_lowercase();
+        self.whitespace();
+
+        let operator = match self.peek() {
+            Some(']') => {
+                self.bump();
+                return Ok(AttributeSelector {
+                    name,
+                    test: None,
+                    case_insensitive: false,
+                });
+            }
+            Some('=') => None,
+            Some('~') => Some(Operator::Includes),
+            Some('|') => Some(Operator::DashMatch),
+            Some('^') => Some(Operator::Prefix),
+            Some('$') => Some(Operator::Suffix),
+            Some('*') => Some(Operator::Substring),
+            _ => return Err(self.error("expected an attribute operator or `]`".to_string())),
+        };
+        if let Some(operator) = operator {
+            self.bump();
+            self.expect('=')?;
+            self.finish_attribute(name, operator)
+        } else {
+            self.bump();
+            self.finish_attribute(name, Operator::Equals)
+        }
+    }
+
+    fn finish_attribute(
+        &mut self,
+        name: String,
+        operator: Operator,
+    ) -> Result<AttributeSelector, SelectorError> {
+        self.whitespace();
+        let value = match self.peek() {
+            Some(quote @ ('"' | '\'')) => self.string(quote)?,
+            _ => self.identifier()?,
+        };
+        self.whitespace();
+        let case_insensitive = match self.peek() {
+            Some('i' | 'I') => {
+                self.bump();
+                true
+            }
+            Some('s' | 'S') => {
+                self.bump();
+                false
+            }
+            _ => false,
+        };
+        self.whitespace();
+        self.expect(']')?;
+        Ok(AttributeSelector {
+            name,
+            test: Some((operator, value)),
+            case_insensitive,
+        })
+    }
+
+    fn pseudo(&mut self) -> Result<Pseudo, SelectorError> {
+        if self.peek() == Some(':') {
+            return Err(self.error("pseudo-elements are not supported".to_string()));
+        }
+        let start = self.position;
+        let name = self.identifier()?.to_ascii_lowercase();
+        let unknown = || SelectorError {
+            message: format!("unsupported pseudo-class `:{name}`"),
+            position: start,
+        };
+
+        if !self.eat('(') {
+            return Ok(match name.as_str() {
+                "root" => Pseudo::Root,
+                "empty" => Pseudo::Empty,
+                "first-child" => Pseudo::NthChild(Nth::FIRST),
+                "last-child" => Pseudo::NthLastChild(Nth::FIRST),
+                "only-child" => Pseudo::OnlyChild,
+                "first-of-type" => Pseudo::NthOfType(Nth::FIRST),
+                "last-of-type" => Pseudo::NthLastOfType(Nth::FIRST),
+                "only-of-type" => Pseudo::OnlyOfType,
+                "link" | "any-link" => Pseudo::AnyLink,
+                "checked" => Pseudo::Checked,
+                "disabled" => Pseudo::Disabled,
+                "enabled" => Pseudo::Enabled,
+                _ => return Err(unknown()),
+            });
+        }
+
+        let pseudo = match name.as_str() {
+            "not" => Pseudo::Not(self.selector_list()?),
+            "is" | "where" => Pseudo::Is(self.selector_list()?),
+            "has" => Pseudo::Has(self.relative_selector_list()?),
+            "nth-child" => Pseudo::NthChild(self.nth()?),
+            "nth-last-child" => Pseudo::NthLastChild(self.nth()?),
+            "nth-of-type" => Pseudo::NthOfType(self.nth()?),
+            "nth-last-of-type" => Pseudo::NthLastOfType(self.nth()?),
+            _ => return Err(unknown()),
+        };
+        self.whitespace();
+        self.expect(')')?;
+        Ok(pseudo)
+    }
+
+    /// Parses an `An+B` argument, or `odd` or `even`.
+    fn nth(&mut self) -> Result<Nth, SelectorError> {
+        let start = self.position;
+        while self.peek().is_some_and(|ch| ch != ')') {
+            self.bump();
+        }
+        let argument: String = self.source[start..self.position]
+            .chars()
+            .filter(|&ch| !is_whitespace(ch))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        parse_nth(&argument).ok_or_else(|| SelectorError {
+            message: format!("invalid `An+B` argument `{}`", argument),
+            position: start,
+        })
+    }
+
+    fn at_identifier(&self) -> bool {
+        match self.peek() {
+            Some('-') => self
+                .peek_second()
+                .is_some_and(|ch| ch == '-' || ch == '\\' || is_name_start(ch)),
+            Some('\\') => true,
+            Some(ch) => is_name_start(ch),
+            None => false,
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, SelectorError> {
+        if !self.at_identifier() {
+            return Err(match self.peek() {
+                Some(ch) => self.error(format!("expected a name, found `{ch}`")),
+                None => self.error("expected a name".to_string()),
+            });
+        }
+        let mut name = String::new();
+        while let Some(ch) = self.peek() {
+            if ch == '\\' {
+                self.bump();
+                name.push(self.escape()?);
+            } else if is_name(ch) {
+                self.bump();
+                name.push(ch);
+            } else {
+                break;
+            }
+        }
+        Ok(name)
+    }
+
+    fn string(&mut self, quote: char) -> Result<String, SelectorError> {
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some(ch) if ch == quote => return Ok(value),
+                Some('\\') => match self.peek() {
+                    // An escaped newline continues the string.
+                    Some('\n') => {
+                        self.bump();
+                    }
+                    _ => value.push(self.escape()?),
+                },
+                Some(ch) => value.push(ch),
+                None => return Err(self.error("unterminated string".to_string())),
+            }
+        }
+    }
+
+    /// Reads what follows a backslash: up to six hex digits and an optional
+    /// space, or any other single character.
+    fn escape(&mut self) -> Result<char, SelectorError> {
+        let start = self.position;
+        while self.position - start < 6 && self.peek().is_some_and(|ch| ch.is_ascii_hexdigit()) {
+            self.bump();
+        }
+        if self.position == start {
+            return self
+                .bump()
+                .ok_or_else(|| self.error("expected an escaped character".to_string()));
+        }
+        let value = u32::from_str_radix(&self.source[start..self.position], 16).unwrap();
+        if self.peek().is_some_and(is_whitespace) {
+            self.bump();
+        }
+        Ok(match char::from_u32(value) {
+            Some(ch) if value != 0 => ch,
+            _ => '\u{fffd}',
+        })
+    }
+}
+
+fn parse_nth(argument: &str) -> Option<Nth> {
+    match argument {
+        "odd" => return Some(Nth { a: 2, b: 1 }),
+        "even" => return Some(Nth { a: 2, b: 0 }),
+        _ => {}
+    }
+    let Some((a, b)) = argument.split_once('n') else {
+        return Some(Nth {
+            a: 0,
+            b: parse_integer(argument)?,
+        });
+    };
+    let a = match a {
+        "" | "+" => 1,
+        "-" => -1,
+        a => parse_integer(a)?,
+    };
+    let b = match b {
+        "" => 0,
+        b if b.starts_with(['+', '-']) => parse_integer(b)?,
+        _ => return None,
+    };
+    Some(Nth { a, b })
+}
+
+/// Parses an integer with an optional sign.
+fn parse_integer(value: &str) -> Option<i64> {
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r' | '\u{c}')
+}
+
+fn is_name_start(ch: char) -> bool {
+    ch.is_ascii_alphabetic() || ch == '_' || !ch.is_ascii()
+}
+
+fn is_name(ch: char) -> bool {
+    is_name_start(ch) || ch.is_ascii_digit() || ch == '-'
+}
@@ -0,0 +1,166 @@
+use parser::parse_html;
+use selector::SelectorList;
+
+const PAGE: &str = "<!DOCTYPE html>\
+    <html lang=en-US><head><title>Page</title></head><body>\
+    <nav id=menu><ul>\
+    <li class=\"item first\"><a href=/>Home</a></li>\
+    <li class=item><a href=/docs data-kind=Guide>Docs</a></li>\
+    <li class=\"item last\"><a>Blog</a></li>\
+    </ul></nav>\
+    <main><h1>Title</h1><p>One</p><p>Two <em>three</em></p><div></div><p>Four</p></main>\
+    <form><fieldset disabled><input name=a></fieldset><input name=b type=checkbox checked>\
+    <select><optgroup disabled><option>x</option></optgroup><option selected>y</option></select></form>\
+    </body></html>";
+
+/// Returns a short description of each element matching `selectors`: its
+/// text, or its tag name if it has none.
+fn select(selectors: &str) -> Vec<String> {
+    let document = parse_html(PAGE);
+    let selectors = SelectorList::parse(selectors).unwrap();
+    selectors
+        .select(document.root())
+        .map(|element| {
+            let text = element.text_content();
+            if text.is_empty() {
+                element.tag_name().to_string()
+            } else {
+                text
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn type_id_and_class() {
+    assert_eq!(select("h1"), vec!["Title"]);
+    assert_eq!(select("H1"), vec!["Title"]);
+    assert_eq!(select("#menu a"), vec!["Home", "Docs", "Blog"]);
+    assert_eq!(select("li.item.last"), vec!["Blog"]);
+    assert_eq!(select(".first, .last"), vec!["Home", "Blog"]);
+    assert_eq!(select("main > *").len(), 5);
+}
+
+#[test]
+fn attributes() {
+    assert_eq!(select("[href]"), vec!["Home", "Docs"]);
+    assert_eq!(select("a[href=\"/docs\"]"), vec!["Docs"]);
+    assert_eq!(select("[data-kind=guide]"), Vec::<String>::new());
+    assert_eq!(select("[data-kind=guide i]"), vec!["Docs"]);
+    assert_eq!(select("[class~=first]"), vec!["Home"]);
+    assert_eq!(select("[lang|=en]").len(), 1);
+    assert_eq!(select("[href^='/d']"), vec!["Docs"]);
+    assert_eq!(select("[href$=cs]"), vec!["Docs"]);
+    assert_eq!(select("[href*=o]"), vec!["Docs"]);
+    assert_eq!(select("[href^='']"), Vec::<String>::new());
+}
+
+#[test]
+fn combinators() {
+    assert_eq!(select("nav a"), vec!["Home", "Docs", "Blog"]);
+    assert_eq!(select("ul > a"), Vec::<String>::new());
+    assert_eq!(select("h1 + p"), vec!["One"]);
+    assert_eq!(select("h1 ~ p"), vec!["One", "Two three", "Four"]);
+    assert_eq!(select("main p em"), vec!["three"]);
+    assert_eq!(select("body>main>p>em"), vec!["three"]);
+}
+
+#[test]
+fn structural_pseudo_classes() {
+    assert_eq!(select("li:first-child"), vec!["Home"]);
+    assert_eq!(select("li:last-child"), vec!["Blog"]);
+    assert_eq!(select("li:nth-child(2)"), vec!["Docs"]);
+    assert_eq!(select("li:nth-child(odd)"), vec!["Home", "Blog"]);
+    assert_eq!(select("li:nth-child(2n)"), vec!["Docs"]);
+    assert_eq!(select("li:nth-child(-n + 2)"), vec!["Home", "Docs"]);
+    assert_eq!(select("li:nth-last-child(1)"), vec!["Blog"]);
+    assert_eq!(select("main p:first-of-type"), vec!["One"]);
+    assert_eq!(select("main p:last-of-type"), vec!["Four"]);
+    assert_eq!(select("main p:nth-of-type(2)"), vec!["Two three"]);
+    assert_eq!(select("main > :only-of-type"), vec!["Title", "div"]);
+    assert_eq!(select("em:only-child"), vec!["three"]);
+    assert_eq!(select("main :empty"), vec!["div"]);
+    assert_eq!(select(":root").len(), 1);
+}
+
+#[test]
+fn logical_pseudo_classes() {
+    assert_eq!(select("li:not(.first, .last)"), vec!["Docs"]);
+    assert_eq!(select(":is(h1, em)"), vec!["Title", "three"]);
+    assert_eq!(select(":where(nav) :is(.last) a"), vec!["Blog"]);
+    assert_eq!(select("p:has(em)"), vec!["Two three"]);
+    assert_eq!(select("li:has(> a[href])"), vec!["Home", "Docs"]);
+    assert_eq!(select("h1:has(+ p)"), vec!["Title"]);
+    assert_eq!(select("p:has(~ div)"), vec!["One", "Two three"]);
+}
+
+#[test]
+fn link_and_form_pseudo_classes() {
+    assert_eq!(select(":link"), vec!["Home", "Docs"]);
+    assert_eq!(select(":checked"), vec!["input", "y"]);
+    assert_eq!(select("input:disabled"), vec!["input"]);
+    assert_eq!(select("option:disabled"), vec!["x"]);
+    assert_eq!(select("input:enabled").len(), 1);
+}
+
+#[test]
+fn escapes_and_strings() {
+    let document = parse_html("<p id=\"a.b\" class=\"x:y\" title='say \"hi\"'>text</p>");
+    let count = |selectors: &str| {
+        SelectorList::parse(selectors)
+            .unwrap()
+            .select(document.root())
+            .count()
+    };
+    assert_eq!(count("#a\\.b"), 1);
+    assert_eq!(count(".x\\:y"), 1);
+    assert_eq!(count("#a\\2e b"), 1);
+    assert_eq!(count("[title='say \"hi\"']"), 1);
+    assert_eq!(count("[title=\"say \\\"hi\\\"\"]"), 1);
+}
+
+#[test]
+fn matches_single_elements() {
+    let document = parse_html("<div><p class=a>x</p></div>");
+    let p = document.descendants().nth(1).unwrap().as_element().unwrap();
+    assert!(SelectorList::parse("div > .a").unwrap().matches(p));
+    assert!(!SelectorList::parse("div + .a").unwrap().matches(p));
+    let selectors: SelectorList = "p".parse().unwrap();
+    assert!(selectors.matches(p));
+}
+
+#[test]
+fn reports_invalid_selectors() {
+    let error = |selectors: &str| {
+        let error = SelectorList::parse(selectors).unwrap_err();
+        (error.message, error.position)
+    };
+    assert_eq!(error(""), ("expected a selector".to_string(), 0));
+    assert_eq!(error("a,"), ("expected a selector".to_string(), 2));
+    assert_eq!(error("a >"), ("expected a selector".to_string(), 3));
+    assert_eq!(
+        error("[href"),
+        ("expected an attribute operator or `]`".to_string(), 5)
+    );
+    assert_eq!(
+        error("a::before"),
+        ("pseudo-elements are not supported".to_string(), 2)
+    );
+    assert_eq!(
+        error("a:hover"),
+        ("unsupported pseudo-class `:hover`".to_string(), 2)
+    );
+    assert_eq!(
+        error(":nth-child(x)"),
+        ("invalid `An+B` argument `x`".to_string(), 11)
+    );
+    assert_eq!(
+        error("svg|a"),
+        ("namespaces are not supported".to_string(), 3)
+    );
+    assert_eq!(error("a)"), ("unexpected `)`".to_string(), 1));
+    assert_eq!(
+        SelectorList::parse("[x='y").unwrap_err().to_string(),
+        "unterminated string at position 5"
+    );
+}
@@ -0,0 +1,177 @@
+//! Turns an [`ast::Document`] back into HTML, following the HTML fragment
+//! serialization algorithm.
+
+mod xhtml;
+
+use ast::{elements, walk, Document, Element, Namespace, Node, Text, Visit, Visitor};
+
+pub use xhtml::{serialize_xhtml, Xhtml, XhtmlError, XhtmlErrorKind, XhtmlOptions};
+
+/// Serializes a whole document.
+pub fn serialize(document: &Document) -> String {
+    inner_html(document.root())
+}
+
+/// Serializes a node including its own tags.
+pub fn outer_html(node: Node) -> String {
+    let mut serializer = Serializer::default();
+    walk(node, &mut serializer);
+    serializer.output
+}
+
+/// Serializes the children of a node.
+pub fn inner_html(node: Node) -> String {
+    let mut serializer = Serializer::default();
+    for child in node.children() {
+        walk(child, &mut serializer);
+    }
+    serializer.output
+}
+
+/// Writes `value` escaped for use as text content.
+pub fn escape_text(value: &str, output: &mut String) {
+    for ch in value.chars() {
+        match ch {
+            '&' => output.push_str("&amp;"),
+            '\u{a0}' => output.push_str("&nbsp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            ch => output.push(ch),
+        }
+    }
+}
+
+/// Writes `value` escaped for use inside a double quoted attribute value.
+pub fn escape_attribute(value: &str, output: &mut String) {
+    for ch in value.chars() {
+        match ch {
+            '&' => output.push_str("&amp;"),
+            '\u{a0}' => output.push_str("&nbsp;"),
+            '"' => output.push_str("&quot;"),
+            ch => output.push(ch),
+        }
+    }
+}
+
+/// Writes the start tag of an element, with all its attributes.
+pub fn write_start_tag(element: Element, output: &mut String) {
+    output.push('<');
+    output.push_str(element.tag_name());
+    for attribute in element.attributes() {
+        output.push(' ');
+        output.push_str(attribute.name());
+        output.push_str("=\"");
+        escape_attribute(attribute.value(), output);
+        output.push('"');
+    }
+    output.push('>');
+    if starts_with_newline(element) {
+        output.push('\n');
+    }
+}
+
+/// Whether the element is a `pre`, `listing` or `textarea` whose text starts
+/// with a newline. The parser drops a newline directly after those start tags,
+/// so an extra one has to be written to keep it.
+pub fn starts_with_newline(element: Element) -> bool {
+    element.namespace() == Namespace::Html
+        && matches!(element.tag_name(), "pre" | "listing" | "textarea")
+        && element
+            .first_child()
+            .and_then(|child| child.as_text())
+            .is_some_and(|text| text.text().starts_with('\n'))
+}
+
+/// Writes the end tag of an element. Void HTML elements have none.
+pub fn write_end_tag(element: Element, output: &mut String) {
+    if element.namespace() != Namespace::Html || !elements::is_void(element.tag_name()) {
+        output.push_str("</");
+        output.push_str(element.tag_name());
+        output.push('>');
+    }
+}
+
+/// Writes a comment, doctype or text node. Text is escaped unless it is the
+/// contents of an element such as `script` that is read as raw text.
+pub fn write_leaf(node: Node, output: &mut String) {
+    if let Some(text) = node.as_text() {
+        write_text(text, output);
+    } else if let Some(comment) = node.as_comment() {
+        output.push_str("<!--");
+        output.push_str(comment.text());
+        output.push_str("-->");
+    } else if let Some(doctype) = node.as_doctype() {
+        output.push_str("<!DOCTYPE ");
+        output.push_str(doctype.name());
+        match (doctype.public_id(), doctype.system_id()) {
+            (Some(public_id), system_id) => {
+                output.push_str(" PUBLIC \"");
+                output.push_str(public_id);
+                output.push('"');
+                if let Some(system_id) = system_id {
+                    output.push_str(" \"");
+                    output.push_str(system_id);
+                    output.push('"');
+                }
+            }
+            (None, Some(system_id)) => {
+                output.push_str(" SYSTEM \"");
+                output.push_str(system_id);
+                output.push('"');
+            }
+            (None, None) => {}
+        }
+        output.push('>');
+    }
+}
+
+fn write_text(text: Text, output: &mut String) {
+    let raw = text
+        .parent()
+        .and_then(|parent| parent.as_element())
+        .is_some_and(|parent| {
+            parent.namespace() == Namespace::Html && is_raw_text_container(parent.tag_name())
+        });
+    if raw {
+        output.push_str(text.text());
+    } else {
+        escape_text(text.text(), output);
+    }
+}
+
+/// Elements whose text children are written without escaping.
+pub fn is_raw_text_container(name: &str) -> bool {
+    elements::is_raw_text(name) || name == "plaintext"
+}
+
+#[derive(Default)]
+struct Serializer {
+    output: String,
+}
+
+impl<'a> Visitor<'a> for Serializer {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        write_start_tag(element, &mut self.output);
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        write_end_tag(element, &mut self.output);
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        write_leaf(text.as_node(), &mut self.output);
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, comment: Node<'a>) -> Visit {
+        write_leaf(comment, &mut self.output);
+        Visit::Continue
+    }
+
+    fn visit_doctype(&mut self, doctype: Node<'a>) -> Visit {
+        write_leaf(doctype, &mut self.output);
+        Visit::Continue
+    }
+}
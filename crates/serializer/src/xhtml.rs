@@ -0,0 +1,465 @@
+//! Serializes a document as XHTML, the XML syntax of HTML.
+//!
+//! HTML documents can hold things XML has no way to write, such as attribute
+//! names with quotes in them or comments containing `--`. Those are left out
+//! or changed so that the output is well-formed, and reported as
+//! [`XhtmlError`]s. The one exception is a document without exactly one root
+//! element: it is written as it is, which is only well-formed as a fragment,
+//! and reported as [`XhtmlErrorKind::RootElement`].
+
+use std::fmt;
+
+use ast::{elements, walk, Document, Element, Namespace, Node, NodeId, Text, Visit, Visitor};
+
+use crate::is_raw_text_container;
+
+const XLINK: &str = "http://www.w3.org/1999/xlink";
+
+/// Options for [`serialize_xhtml`].
+#[derive(Debug, Clone, Default)]
+pub struct XhtmlOptions {
+    /// Start the output with an `<?xml ...?>` declaration.
+    pub xml_declaration: bool,
+}
+
+/// A document serialized as XHTML, with whatever couldn't be written as is.
+#[derive(Debug, Clone)]
+pub struct Xhtml {
+    pub output: String,
+    pub errors: Vec<XhtmlError>,
+}
+
+/// Something in a document that XML can't represent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XhtmlError {
+    pub kind: XhtmlErrorKind,
+    /// The node it was found in.
+    pub node: NodeId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum XhtmlErrorKind {
+    /// An element name that isn't an XML name. The element's contents are
+    /// written without it.
+    InvalidElementName(String),
+    /// An attribute name that isn't an XML name, or has a prefix other than
+    /// `xml` or `xlink`. The attribute is left out.
+    InvalidAttributeName { element: String, attribute: String },
+    /// An `xmlns` or `xmlns:xlink` attribute with the wrong namespace. The
+    /// attribute is left out.
+    NamespaceMismatch { element: String, value: String },
+    /// A character XML doesn't allow, which is left out.
+    InvalidCharacter(char),
+    /// A comment containing `--` or ending in `-`, which is written with
+    /// spaces between the dashes.
+    CommentText,
+    /// The document doesn't have exactly one element at its top level, or
+    /// has text outside of it. The top level is still written as it is.
+    RootElement,
+    /// A doctype name that isn't an XML name. The doctype is left out.
+    InvalidDoctypeName(String),
+    /// A public identifier with characters XML doesn't allow in one, or a
+    /// system identifier containing both kinds of quotes. The identifier is
+    /// left out.
+    InvalidDoctypeIdentifier(String),
+}
+
+impl fmt::Display for XhtmlErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XhtmlErrorKind::InvalidElementName(name) => {
+                write!(f, "`{}` is not a valid element name", name)
+            }
+            XhtmlErrorKind::InvalidAttributeName { element, attribute } => write!(
+                f,
+                "`{}` on `{}` is not a valid attribute name",
+                attribute, element
+            ),
+            XhtmlErrorKind::NamespaceMismatch { element, value } => {
+                write!(f, "`{}` can't declare namespace `{}`", element, value)
+            }
+            XhtmlErrorKind::InvalidCharacter(ch) => {
+                write!(f, "U+{:04X} is not allowed in XML", *ch as u32)
+            }
+            XhtmlErrorKind::CommentText => f.write_str("comment text is not allowed in XML"),
+            XhtmlErrorKind::RootElement => {
+                f.write_str("the document needs exactly one root element")
+            }
+            XhtmlErrorKind::InvalidDoctypeName(name) => {
+                write!(f, "`{}` is not a valid doctype name", name)
+            }
+            XhtmlErrorKind::InvalidDoctypeIdentifier(id) => {
+                write!(f, "`{}` is not a valid doctype identifier", id)
+            }
+        }
+    }
+}
+
+impl fmt::Display for XhtmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl std::error::Error for XhtmlError {}
+
+/// Serializes a document as XHTML. Elements get the namespace declarations
+/// they need, empty void and foreign elements are closed with `/>`, and the
+/// text of elements such as `script` is put in CDATA sections when it
+/// contains markup.
+pub fn serialize_xhtml(document: &Document, options: &XhtmlOptions) -> Xhtml {
+    let mut serializer = XhtmlSerializer::default();
+    if options.xml_declaration {
+        serializer
+            .output
+            .push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    }
+
+    let root = document.root();
+    let elements = root.children().filter(Node::is_element).count();
+    let stray_text = root
+        .children()
+        .filter_map(|child| child.as_text())
+        .any(|text| !text.text().chars().all(is_xml_whitespace));
+    if elements != 1 || stray_text {
+        serializer.error(XhtmlErrorKind::RootElement, root.id());
+    }
+    for child in root.children() {
+        walk(child, &mut serializer);
+    }
+    Xhtml {
+        output: serializer.output,
+        errors: serializer.errors,
+    }
+}
+
+fn is_xml_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r')
+}
+
+fn is_xml_char(ch: char) -> bool {
+    matches!(ch, '\t' | '\n' | '\r' | '\u{20}'..='\u{fffd}' | '\u{10000}'..)
+}
+
+fn is_name_start(ch: char) -> bool {
+    matches!(ch,
+        'A'..='Z' | '_' | 'a'..='z'
+        | '\u{c0}'..='\u{d6}' | '\u{d8}'..='\u{f6}' | '\u{f8}'..='\u{2ff}'
+        | '\u{370}'..='\u{37d}' | '\u{37f}'..='\u{1fff}' | '\u{200c}'..='\u{200d}'
+        | '\u{2070}'..='\u{218f}' | '\u{2c00}'..='\u{2fef}' | '\u{3001}'..='\u{d7ff}'
+        | '\u{f900}'..='\u{fdcf}' | '\u{fdf0}'..='\u{fffd}' | '\u{10000}'..='\u{effff}')
+}
+
+fn is_name_char(ch: char) -> bool {
+    is_name_start(ch)
+        || matches!(ch,
+            '-' | '.' | '0'..='9' | '\u{b7}' | '\u{300}'..='\u{36f}' | '\u{203f}'..='\u{2040}')
+}
+
+/// Whether `name` is an XML name without a prefix.
+fn is_local_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_name_start) && chars.all(is_name_char)
+}
+
+/// Whether `name` is an XML name, which unlike element names here may have
+/// any number of colons.
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch == ':' || is_name_start(ch))
+        && chars.all(|ch| ch == ':' || is_name_char(ch))
+}
+
+fn is_pubid_char(ch: char) -> bool {
+    matches!(ch,
+        ' ' | '\r' | '\n' | 'a'..='z' | 'A'..='Z' | '0'..='9'
+        | '-' | '\'' | '(' | ')' | '+' | ',' | '.' | '/' | ':' | '='
+        | '?' | ';' | '!' | '*' | '#' | '@' | '$' | '_' | '%')
+}
+
+/// The quote a system identifier can be written in, if it has one.
+fn system_quote(system_id: &str) -> Option<char> {
+    if !system_id.chars().all(is_xml_char) {
+        None
+    } else if !system_id.contains('"') {
+        Some('"')
+    } else if !system_id.contains('\'') {
+        Some('\'')
+    } else {
+        None
+    }
+}
+
+/// The namespace declarations in effect inside an element.
+#[derive(Clone, Copy, Default)]
+struct Scope {
+    namespace: Option<Namespace>,
+    xlink: bool,
+    /// Whether the element itself was written, rather than left out for
+    /// having an invalid name.
+    written: bool,
+}
+
+#[derive(Default)]
+struct XhtmlSerializer {
+    output: String,
+    errors: Vec<XhtmlError>,
+    scopes: Vec<Scope>,
+}
+
+impl XhtmlSerializer {
+    fn error(&mut self, kind: XhtmlErrorKind, node: NodeId) {
+        self.errors.push(XhtmlError { kind, node });
+    }
+
+    /// Writes `value` escaped, as text or an attribute value, leaving out
+    /// characters XML doesn't allow.
+    fn escape(&mut self, value: &str, attribute: bool, node: NodeId) {
+        for ch in value.chars() {
+            match ch {
+                '&' => self.output.push_str("&amp;"),
+                '<' => self.output.push_str("&lt;"),
+                '>' if !attribute => self.output.push_str("&gt;"),
+                '"' if attribute => self.output.push_str("&quot;"),
+                // XML parsers normalize these, so they are kept as
+                // references.
+                '\r' => self.output.push_str("&#13;"),
+                '\t' | '\n' if attribute => {
+                    self.output
+                        .push_str(if ch == '\t' { "&#9;" } else { "&#10;" })
+                }
+                ch if !is_xml_char(ch) => self.error(XhtmlErrorKind::InvalidCharacter(ch), node),
+                ch => self.output.push(ch),
+            }
+        }
+    }
+
+    /// Writes text as it is inside a CDATA section, if it has to be escaped,
+    /// so that scripts and styles read the same in both syntaxes.
+    fn write_cdata(&mut self, value: &str, node: NodeId) {
+        let value: String = value
+            .chars()
+            .filter(|&ch| {
+                let valid = is_xml_char(ch);
+                if !valid {
+                    self.error(XhtmlErrorKind::InvalidCharacter(ch), node);
+                }
+                valid
+            })
+            .collect();
+        if !value.contains(['<', '&']) && !value.contains("]]>") {
+            self.output.push_str(&value);
+            return;
+        }
+        self.output.push_str("<![CDATA[");
+        self.output
+            .push_str(&value.replace("]]>", "]]]]><![CDATA[>"));
+        self.output.push_str("]]>");
+    }
+
+    fn write_attributes(&mut self, element: Element, scope: &mut Scope) {
+        let id = element.as_node().id();
+        let mut attributes = Vec::new();
+        for attribute in element.attributes() {
+            let (name, value) = (attribute.name(), attribute.value());
+            let expected = match name {
+                "xmlns" => Some(element.namespace().uri()),
+                "xmlns:xlink" => Some(XLINK),
+                _ => None,
+            };
+            if let Some(expected) = expected {
+                // The declarations are written where they are needed.
+                if value != expected {
+                    let kind = XhtmlErrorKind::NamespaceMismatch {
+                        element: element.tag_name().to_string(),
+                        value: value.to_string(),
+                    };
+                    self.error(kind, id);
+                }
+                continue;
+            }
+            let valid = match name.split_once(':') {
+                Some(("xlink", local)) => {
+                    scope.xlink |= is_local_name(local);
+                    is_local_name(local)
+                }
+                Some(("xml", local)) => matches!(local, "lang" | "space" | "base"),
+                Some(_) => false,
+                None => is_local_name(name),
+            };
+            if valid {
+                attributes.push((name, value));
+            } else {
+                let kind = XhtmlErrorKind::InvalidAttributeName {
+                    element: element.tag_name().to_string(),
+                    attribute: name.to_string(),
+                };
+                self.error(kind, id);
+            }
+        }
+
+        let parent = self.scopes.last().copied().unwrap_or_default();
+        if parent.namespace != Some(element.namespace()) {
+            self.output.push_str(" xmlns=\"");
+            self.output.push_str(element.namespace().uri());
+            self.output.push('"');
+        }
+        if scope.xlink && !parent.xlink {
+            self.output.push_str(" xmlns:xlink=\"");
+            self.output.push_str(XLINK);
+            self.output.push('"');
+        }
+        scope.xlink |= parent.xlink;
+        for (name, value) in attributes {
+            self.output.push(' ');
+            self.output.push_str(name);
+            self.output.push_str("=\"");
+            self.escape(value, true, id);
+            self.output.push('"');
+        }
+    }
+}
+
+/// Empty elements are closed in their start tag, except for HTML elements
+/// that aren't void, which older HTML parsers wouldn't read as closed.
+fn self_closes(element: Element) -> bool {
+    element.first_child().is_none()
+        && (element.namespace() != Namespace::Html || elements::is_void(element.tag_name()))
+}
+
+impl<'a> Visitor<'a> for XhtmlSerializer {
+    fn enter_element(&mut self, element: Element<'a>) -> Visit {
+        let parent = self.scopes.last().copied().unwrap_or_default();
+        if !is_local_name(element.tag_name()) {
+            let kind = XhtmlErrorKind::InvalidElementName(element.tag_name().to_string());
+            self.error(kind, element.as_node().id());
+            self.scopes.push(Scope {
+                written: false,
+                ..parent
+            });
+            return Visit::Continue;
+        }
+
+        let mut scope = Scope {
+            namespace: Some(element.namespace()),
+            xlink: false,
+            written: true,
+        };
+        self.output.push('<');
+        self.output.push_str(element.tag_name());
+        self.write_attributes(element, &mut scope);
+        self.output
+            .push_str(if self_closes(element) { "/>" } else { ">" });
+        self.scopes.push(scope);
+        Visit::Continue
+    }
+
+    fn leave_element(&mut self, element: Element<'a>) -> Visit {
+        let scope = self.scopes.pop().unwrap_or_default();
+        if scope.written && !self_closes(element) {
+            self.output.push_str("</");
+            self.output.push_str(element.tag_name());
+            self.output.push('>');
+        }
+        Visit::Continue
+    }
+
+    fn visit_text(&mut self, text: Text<'a>) -> Visit {
+        let raw = text
+            .parent()
+            .and_then(|parent| parent.as_element())
+            .is_some_and(|parent| {
+                parent.namespace() == Namespace::Html && is_raw_text_container(parent.tag_name())
+            });
+        let id = text.as_node().id();
+        if raw {
+            self.write_cdata(text.text(), id);
+        } else {
+            self.escape(text.text(), false, id);
+        }
+        Visit::Continue
+    }
+
+    fn visit_comment(&mut self, comment: Node<'a>) -> Visit {
+        let text = comment.as_comment().unwrap().text();
+        let mut fixed = String::with_capacity(text.len());
+        for ch in text.chars() {
+            if ch == '-' && fixed.ends_with('-') {
+                fixed.push(' ');
+            }
+            fixed.push(ch);
+        }
+        if fixed.ends_with('-') {
+            fixed.push(' ');
+        }
+        if fixed.len() != text.len() {
+            self.error(XhtmlErrorKind::CommentText, comment.id());
+        }
+        self.output.push_str("<!--");
+        for ch in fixed.chars() {
+            if is_xml_char(ch) {
+                self.output.push(ch);
+            } else {
+                self.error(XhtmlErrorKind::InvalidCharacter(ch), comment.id());
+            }
+        }
+        self.output.push_str("-->");
+        Visit::Continue
+    }
+
+    fn visit_doctype(&mut self, doctype: Node<'a>) -> Visit {
+        let data = doctype.as_doctype().unwrap();
+        let id = doctype.id();
+        if !is_xml_name(data.name()) {
+            self.error(
+                XhtmlErrorKind::InvalidDoctypeName(data.name().to_string()),
+                id,
+            );
+            return Visit::Continue;
+        }
+        let public_id = data.public_id().filter(|public_id| {
+            let valid = public_id.chars().all(is_pubid_char);
+            if !valid {
+                let kind = XhtmlErrorKind::InvalidDoctypeIdentifier(public_id.to_string());
+                self.error(kind, id);
+            }
+            valid
+        });
+        let system_id = data.system_id().and_then(|system_id| {
+            let quote = system_quote(system_id);
+            if quote.is_none() {
+                let kind = XhtmlErrorKind::InvalidDoctypeIdentifier(system_id.to_string());
+                self.error(kind, id);
+            }
+            quote.map(|quote| (system_id, quote))
+        });
+
+        self.output.push_str("<!DOCTYPE ");
+        self.output.push_str(data.name());
+        // XML needs a system identifier after a public one. Public
+        // identifiers can't contain `"`, so they are always written in it.
+        match (public_id, system_id) {
+            (Some(public_id), system_id) => {
+                let (system_id, quote) = system_id.unwrap_or(("", '"'));
+                self.output.push_str(" PUBLIC \"");
+                self.output.push_str(public_id);
+                self.output.push_str("\" ");
+                self.output.push(quote);
+                self.output.push_str(system_id);
+                self.output.push(quote);
+            }
+            (None, Some((system_id, quote))) => {
+                self.output.push_str(" SYSTEM ");
+                self.output.push(quote);
+                self.output.push_str(system_id);
+                self.output.push(quote);
+            }
+            (None, None) => {}
+        }
+        self.output.push('>');
+        Visit::Continue
+    }
+}
@@ -0,0 +1,62 @@
+use parser::parse_html;
+use serializer::{inner_html, outer_html, serialize};
+
+#[test]
+fn serializes_documents() {
+    let source = "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>A &amp; B</title></head>\
+                  <body><!-- note --><p class=\"x\">Hello <b>world</b><br>&lt;tag&gt;</p></body></html>";
+    assert_eq!(serialize(&parse_html(source)), source);
+}
+
+#[test]
+fn escapes_text_and_attributes() {
+    let document = parse_html("<p title=\"&quot;a&quot; &amp; b\">1 &lt; 2 &amp;&nbsp;3</p>");
+    assert_eq!(
+        serialize(&document),
+        "<p title=\"&quot;a&quot; &amp; b\">1 &lt; 2 &amp;&nbsp;3</p>"
+    );
+    assert_eq!(
+        serialize(&parse_html("<p>&notin; &nbsp b</p>")),
+        "<p>\u{2209} &nbsp; b</p>"
+    );
+}
+
+#[test]
+fn raw_text_is_not_escaped() {
+    let source = "<script>if (a < b && c) { x = \"</p>\"; }</script><style>a > b { }</style>";
+    assert_eq!(serialize(&parse_html(source)), source);
+}
+
+#[test]
+fn serializes_single_nodes() {
+    let document = parse_html("<ul><li>one</li><li>two</li></ul>");
+    let list = document.root().first_child().unwrap();
+    assert_eq!(outer_html(list.first_child().unwrap()), "<li>one</li>");
+    assert_eq!(inner_html(list), "<li>one</li><li>two</li>");
+}
+
+#[test]
+fn serializes_doctype_identifiers() {
+    let source = "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\">";
+    assert_eq!(serialize(&parse_html(source)), source);
+}
+
+#[test]
+fn keeps_leading_newlines_in_pre() {
+    let document = parse_html("<pre>\n\nindented</pre><textarea>\nnot kept</textarea>");
+    assert_eq!(
+        serialize(&document),
+        "<pre>\n\nindented</pre><textarea>not kept</textarea>"
+    );
+    assert_eq!(
+        serialize(&parse_html(&serialize(&document))),
+        serialize(&document)
+    );
+}
+
+#[test]
+fn serializes_deep_documents() {
+    let depth = 100_000;
+    let source = "<div>".repeat(depth) + &"</div>".repeat(depth);
+    assert_eq!(serialize(&parse_html(&source)), source);
+}
@@ -0,0 +1,213 @@
+use ast::{Attribute, Document};
+use parser::parse_html;
+use serializer::{serialize, serialize_xhtml, Xhtml, XhtmlErrorKind, XhtmlOptions};
+
+fn xhtml(source: &str) -> Xhtml {
+    serialize_xhtml(&parse_html(source), &XhtmlOptions::default())
+}
+
+fn kinds(xhtml: &Xhtml) -> Vec<XhtmlErrorKind> {
+    xhtml
+        .errors
+        .iter()
+        .map(|error| error.kind.clone())
+        .collect()
+}
+
+#[test]
+fn declares_namespaces_and_closes_void_elements() {
+    let result =
+        xhtml("<!DOCTYPE html><html><body><p>a<br>b</p><img src=x.png><div></div></body></html>");
+    assert_eq!(
+        result.output,
+        "<!DOCTYPE html><html xmlns=\"http://www.w3.org/1999/xhtml\"><body><p>a<br/>b</p>\
+         <img src=\"x.png\"/><div></div></body></html>"
+    );
+    assert!(result.errors.is_empty());
+}
+
+#[test]
+fn foreign_elements_declare_their_namespaces() {
+    let result = xhtml(
+        "<div><svg viewBox='0 0 1 1'><use xlink:href='#a'/><foreignObject><p>x</p></foreignObject></svg>\
+         <math><mi>y</mi></math></div>",
+    );
+    assert_eq!(
+        result.output,
+        "<div xmlns=\"http://www.w3.org/1999/xhtml\">\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 1 1\">\
+         <use xmlns:xlink=\"http://www.w3.org/1999/xlink\" xlink:href=\"#a\"/>\
+         <foreignObject><p xmlns=\"http://www.w3.org/1999/xhtml\">x</p></foreignObject></svg>\
+         <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi>y</mi></math></div>"
+    );
+    assert!(result.errors.is_empty());
+}
+
+#[test]
+fn source_declarations_are_replaced() {
+    let result = xhtml(
+        "<html xmlns='http://www.w3.org/1999/xhtml'><svg xmlns='http://example.com'/></html>",
+    );
+    assert_eq!(
+        result.output,
+        "<html xmlns=\"http://www.w3.org/1999/xhtml\"><svg xmlns=\"http://www.w3.org/2000/svg\"/></html>"
+    );
+    assert_eq!(
+        kinds(&result),
+        [XhtmlErrorKind::NamespaceMismatch {
+            element: "svg".to_string(),
+            value: "http://example.com".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn escapes_text_and_attributes_for_xml() {
+    let result = xhtml("<p title='a \"b\" <c>\n&amp;'>1 &lt; 2 &gt; 0 &amp;&nbsp;3</p>");
+    assert_eq!(
+        result.output,
+        "<p xmlns=\"http://www.w3.org/1999/xhtml\" title=\"a &quot;b&quot; &lt;c>&#10;&amp;\">\
+         1 &lt; 2 &gt; 0 &amp;\u{a0}3</p>"
+    );
+}
+
+#[test]
+fn raw_text_goes_in_cdata_sections() {
+    let result = xhtml("<div><script>if (a < b && c) { s = \"]]>\"; }</script><style>p { color: red }</style></div>");
+    assert_eq!(
+        result.output,
+        "<div xmlns=\"http://www.w3.org/1999/xhtml\"><script><![CDATA[if (a < b && c) { s = \"]]]]><![CDATA[>\"; }]]></script>\
+         <style>p { color: red }</style></div>"
+    );
+}
+
+#[test]
+fn unrepresentable_attributes_are_reported_and_dropped() {
+    let result = xhtml("<p \"quoted\"=1 @click=go :class=x v-if=y o:p=z xml:lang=en>text</p>");
+    assert_eq!(
+        result.output,
+        "<p xmlns=\"http://www.w3.org/1999/xhtml\" v-if=\"y\" xml:lang=\"en\">text</p>"
+    );
+    let dropped: Vec<_> = kinds(&result)
+        .into_iter()
+        .map(|kind| match kind {
+            XhtmlErrorKind::InvalidAttributeName { attribute, .. } => attribute,
+            kind => panic!("unexpected {:?}", kind),
+        })
+        .collect();
+    assert_eq!(dropped, ["\"quoted\"", "@click", ":class", "o:p"]);
+}
+
+#[test]
+fn invalid_element_names_are_unwrapped() {
+    let mut document = Document::new();
+    let root = document.create_element("div".to_string(), Vec::new());
+    let invalid = document.create_element(
+        "o:p".to_string(),
+        vec![Attribute::new("a".to_string(), "b".to_string())],
+    );
+    let text = document.create_text("kept".to_string());
+    document.append_child(Document::ROOT, root).unwrap();
+    document.append_child(root, invalid).unwrap();
+    document.append_child(invalid, text).unwrap();
+
+    let result = serialize_xhtml(&document, &XhtmlOptions::default());
+    assert_eq!(
+        result.output,
+        "<div xmlns=\"http://www.w3.org/1999/xhtml\">kept</div>"
+    );
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].node, invalid);
+    assert_eq!(
+        result.errors[0].to_string(),
+        "`o:p` is not a valid element name"
+    );
+}
+
+#[test]
+fn comments_and_characters_are_made_valid() {
+    let result = xhtml("<p>a\u{c}b<!-- x -- y -->c</p><!--end-->");
+    assert_eq!(
+        result.output,
+        "<p xmlns=\"http://www.w3.org/1999/xhtml\">ab<!-- x - - y -->c</p><!--end-->"
+    );
+    assert_eq!(
+        kinds(&result),
+        [
+            XhtmlErrorKind::InvalidCharacter('\u{c}'),
+            XhtmlErrorKind::CommentText,
+        ]
+    );
+}
+
+#[test]
+fn documents_need_a_single_root() {
+    let result = xhtml("<p>one</p><p>two</p>");
+    assert_eq!(kinds(&result), [XhtmlErrorKind::RootElement]);
+    assert_eq!(result.errors[0].node, Document::ROOT);
+    assert_eq!(xhtml("text").errors.len(), 1);
+}
+
+#[test]
+fn writes_an_xml_declaration_and_doctype_identifiers() {
+    let document = parse_html("<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01//EN\"><html></html>");
+    let options = XhtmlOptions {
+        xml_declaration: true,
+    };
+    assert_eq!(
+        serialize_xhtml(&document, &options).output,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01//EN\" \"\">\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\"></html>"
+    );
+}
+
+#[test]
+fn html_serialization_keeps_foreign_content() {
+    let source = "<svg><style>rect { fill: red }</style><rect></rect><source></source></svg>";
+    assert_eq!(serialize(&parse_html(source)), source);
+}
+
+#[test]
+fn doctypes_are_validated() {
+    let result = xhtml("<!DOCTYPE html PUBLIC 'a\"b' 'c\"d'><html></html>");
+    assert_eq!(
+        result.output,
+        "<!DOCTYPE html SYSTEM 'c\"d'><html xmlns=\"http://www.w3.org/1999/xhtml\"></html>"
+    );
+    assert_eq!(
+        kinds(&result),
+        [XhtmlErrorKind::InvalidDoctypeIdentifier("a\"b".to_string())]
+    );
+
+    let result = xhtml("<!DOCTYPE html SYSTEM 'a\"b'><html></html>");
+    assert!(result
+        .output
+        .starts_with("<!DOCTYPE html SYSTEM 'a\"b'><html"));
+    assert!(result.errors.is_empty());
+
+    for (source, name) in [
+        ("<!DOCTYPE><html></html>", ""),
+        ("<!DOCTYPE a&b><html></html>", "a&b"),
+    ] {
+        let result = xhtml(source);
+        assert_eq!(
+            result.output, "<html xmlns=\"http://www.w3.org/1999/xhtml\"></html>",
+            "{}",
+            source
+        );
+        assert_eq!(
+            kinds(&result),
+            [XhtmlErrorKind::InvalidDoctypeName(name.to_string())]
+        );
+    }
+}
+
+#[test]
+fn documents_without_a_single_root_are_written_as_they_are() {
+    let result = xhtml("<p>one</p><p>two</p>");
+    assert_eq!(
+        result.output,
+        "<p xmlns=\"http://www.w3.org/1999/xhtml\">one</p><p xmlns=\"http://www.w3.org/1999/xhtml\">two</p>"
+    );
+}
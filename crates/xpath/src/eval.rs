@@ -0,0 +1,556 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::iter::successors;
+
+use ast::{Document, Namespace, Node, NodeId};
+
+use crate::{
+    Arithmetic, Axis, Comparison, Expr, Function, NodeTest, Path, Start, Step, Value, XPathNode,
+};
+
+/// Formats a number as the `string()` function does: integers without a
+/// fraction, and never with an exponent.
+pub(crate) fn number_to_string(number: f64) -> String {
+    if number.is_nan() {
+        "NaN".to_string()
+    } else if number.is_infinite() {
+        if number > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }
+        .to_string()
+    } else if number == 0.0 {
+        "0".to_string()
+    } else {
+        number.to_string()
+    }
+}
+
+/// Reads a number as the `number()` function does, which only accepts
+/// decimals, and gives NaN for anything else.
+pub(crate) fn string_to_number(string: &str) -> f64 {
+    let trimmed = string.trim_matches(|ch| matches!(ch, ' ' | '\t' | '\n' | '\r'));
+    let digits = trimmed.strip_prefix('-').unwrap_or(trimmed);
+    let valid = digits.chars().any(|ch| ch.is_ascii_digit())
+        && digits.chars().all(|ch| ch.is_ascii_digit() || ch == '.')
+        && digits.matches('.').count() <= 1;
+    match valid {
+        true => trimmed.parse().unwrap_or(f64::NAN),
+        false => f64::NAN,
+    }
+}
+
+/// Rounds to the closest integer, taking the one towards positive infinity
+/// for halves.
+fn round(number: f64) -> f64 {
+    if number.is_nan() || number.is_infinite() || number == 0.0 {
+        number
+    } else if (-0.5..0.0).contains(&number) {
+        -0.0
+    } else {
+        (number + 0.5).floor()
+    }
+}
+
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r')
+}
+
+/// The context an expression is evaluated in.
+#[derive(Clone, Copy)]
+struct Context<'a> {
+    node: XPathNode<'a>,
+    /// The 1-based position of the node in the nodes being filtered.
+    position: usize,
+    size: usize,
+}
+
+pub(crate) struct Evaluator<'a> {
+    document: &'a Document,
+    /// The position of every node in document order, worked out the first
+    /// time nodes have to be sorted.
+    order: OnceCell<HashMap<NodeId, usize>>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub(crate) fn new(document: &'a Document) -> Evaluator<'a> {
+        Evaluator {
+            document,
+            order: OnceCell::new(),
+        }
+    }
+
+    pub(crate) fn evaluate(&self, expr: &Expr, node: XPathNode<'a>) -> Value<'a> {
+        let context = Context {
+            node,
+            position: 1,
+            size: 1,
+        };
+        self.value(expr, context)
+    }
+
+    fn value(&self, expr: &Expr, context: Context<'a>) -> Value<'a> {
+        match expr {
+            Expr::Or(left, right) => Value::Boolean(
+                self.value(left, context).boolean() || self.value(right, context).boolean(),
+            ),
+            Expr::And(left, right) => Value::Boolean(
+                self.value(left, context).boolean() && self.value(right, context).boolean(),
+            ),
+            Expr::Compare(comparison, left, right) => Value::Boolean(compare(
+                *comparison,
+                &self.value(left, context),
+                &self.value(right, context),
+            )),
+            Expr::Arithmetic(operation, left, right) => {
+                let left = self.value(left, context).number();
+                let right = self.value(right, context).number();
+                Value::Number(match operation {
+                    Arithmetic::Add => left + right,
+                    Arithmetic::Subtract => left - right,
+                    Arithmetic::Multiply => left * right,
+                    Arithmetic::Divide => left / right,
+                    Arithmetic::Modulo => left % right,
+                })
+            }
+            Expr::Negate(expr) => Value::Number(-self.value(expr, context).number()),
+            Expr::Union(left, right) => {
+                let mut nodes = self.nodes(left, context);
+                nodes.extend(self.nodes(right, context));
+                self.sort(&mut nodes);
+                Value::NodeSet(nodes)
+            }
+            Expr::Filter(primary, predicates) => {
+                let nodes = self.nodes(primary, context);
+                Value::NodeSet(self.filter(nodes, predicates))
+            }
+            Expr::Path(path) => Value::NodeSet(self.path(path, context)),
+            Expr::Literal(literal) => Value::String(literal.clone()),
+            Expr::Number(number) => Value::Number(*number),
+            Expr::Call(function, arguments) => self.call(*function, arguments, context),
+        }
+    }
+
+    /// Evaluates an expression that the parser has checked gives a node-set.
+    fn nodes(&self, expr: &Expr, context: Context<'a>) -> Vec<XPathNode<'a>> {
+        match self.value(expr, context) {
+            Value::NodeSet(nodes) => nodes,
+            _ => unreachable!("the parser only allows node-sets here"),
+        }
+    }
+
+    fn path(&self, path: &Path, context: Context<'a>) -> Vec<XPathNode<'a>> {
+        let mut nodes = match &path.start {
+            Start::Root => {
+                let node = match context.node {
+                    XPathNode::Node(node) => node,
+                    XPathNode::Attribute { element, .. } => element.as_node(),
+                };
+                let root = successors(Some(node), Node::parent).last().unwrap();
+                vec![XPathNode::Node(root)]
+            }
+            Start::Context => vec![context.node],
+            Start::Expr(expr) => self.nodes(expr, context),
+        };
+        for step in &path.steps {
+            nodes = self.step(step, &nodes);
+        }
+        nodes
+    }
+
+    fn step(&self, step: &Step, nodes: &[XPathNode<'a>]) -> Vec<XPathNode<'a>> {
+        let mut selected = Vec::new();
+        for &node in nodes {
+            let candidates = axis(step.axis, node)
+                .into_iter()
+                .filter(|&candidate| matches_test(&step.test, step.axis, candidate))
+                .collect();
+            selected.extend(self.filter(candidates, &step.predicates));
+        }
+        if nodes.len() > 1 || is_reverse(step.axis) {
+            self.sort(&mut selected);
+        }
+        selected
+    }
+
+    /// Keeps the nodes that match every predicate in turn. A number matches
+    /// the node at that position, and any other value is converted to a
+    /// boolean.
+    fn filter(&self, mut nodes: Vec<XPathNode<'a>>, predicates: &[Expr]) -> Vec<XPathNode<'a>> {
+        for predicate in predicates {
+            let size = nodes.len();
+            let mut position = 0;
+            nodes.retain(|&node| {
+                position += 1;
+                let context = Context {
+                    node,
+                    position,
+                    size,
+                };
+                match self.value(predicate, context) {
+                    Value::Number(number) => number == position as f64,
+                    value => value.boolean(),
+                }
+            });
+        }
+        nodes
+    }
+
+    /// Puts nodes in document order, removing duplicates.
+    fn sort(&self, nodes: &mut Vec<XPathNode<'a>>) {
+        let order = self.order.get_or_init(|| {
+            self.document
+                .root()
+                .descendants()
+                .enumerate()
+                .map(|(index, node)| (node.id(), index + 1))
+                .collect()
+        });
+        let key = |node: &XPathNode| match node {
+            XPathNode::Node(node) => (order.get(&node.id()).copied().unwrap_or(0), 0),
+            XPathNode::Attribute { element, index } => (
+                order.get(&element.as_node().id()).copied().unwrap_or(0),
+                index + 1,
+            ),
+        };
+        nodes.sort_by_key(key);
+        nodes.dedup();
+    }
+
+    fn call(&self, function: Function, arguments: &[Expr], context: Context<'a>) -> Value<'a> {
+        let value = |index: usize| self.value(&arguments[index], context);
+        let string = |index: usize| match arguments.get(index) {
+            Some(argument) => self.value(argument, context).string(),
+            None => context.node.string_value(),
+        };
+        // The first node of the argument, or the context node without one.
+        let first = || match arguments.first() {
+            Some(argument) => self.nodes(argument, context).first().copied(),
+            None => Some(context.node),
+        };
+
+        match function {
+            Function::Last => Value::Number(context.size as f64),
+            Function::Position => Value::Number(context.position as f64),
+            Function::Count => Value::Number(self.nodes(&arguments[0], context).len() as f64),
+            Function::Id => {
+                let ids = match value(0) {
+                    Value::NodeSet(nodes) => nodes
+                        .iter()
+                        .map(XPathNode::string_value)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    value => value.string(),
+                };
+                let ids: Vec<&str> = ids.split(is_whitespace).collect();
+                let nodes = self
+                    .document
+                    .descendants()
+                    .filter(|node| {
+                        node.as_element()
+                            .and_then(|element| element.id())
+                            .is_some_and(|id| ids.contains(&id))
+                    })
+                    .map(XPathNode::Node)
+                    .collect();
+                Value::NodeSet(nodes)
+            }
+            Function::LocalName => {
+                let name = first().map_or("", |node| node.name());
+                let local = name.split_once(':').map_or(name, |(_, local)| local);
+                Value::String(local.to_string())
+            }
+            Function::NamespaceUri => Value::String(first().map_or("", namespace_uri).to_string()),
+            Function::Name => Value::String(first().map_or("", |node| node.name()).to_string()),
+            Function::String => Value::String(string(0)),
+            Function::Concat => Value::String((0..arguments.len()).map(string).collect()),
+            Function::StartsWith => Value::Boolean(string(0).starts_with(&string(1))),
+            Function::Contains => Value::Boolean(string(0).contains(&string(1))),
+            Function::SubstringBefore => {
+                let (string, pattern) = (string(0), string(1));
+                let before = string.find(&pattern).map_or("", |index| &string[..index]);
+                Value::String(before.to_string())
+            }
+            Function::SubstringAfter => {
+                let (string, pattern) = (string(0), string(1));
+                let after = string
+                    .find(&pattern)
+                    .map_or("", |index| &string[index + pattern.len()..]);
+                Value::String(after.to_string())
+            }
+            Function::Substring => {
+                let start = round(value(1).number());
+                let end = match arguments.len() {
+                    3 => start + round(value(2).number()),
+                    _ => f64::INFINITY,
+                };
+                let substring = string(0)
+                    .chars()
+                    .zip(1..)
+                    .filter(|&(_, position)| {
+                        let position = f64::from(position);
+                        position >= start && position < end
+                    })
+                    .map(|(ch, _)| ch)
+                    .collect();
+                Value::String(substring)
+            }
+            Function::StringLength => Value::Number(string(0).chars().count() as f64),
+            Function::NormalizeSpace => {
+                let string = string(0);
+                let words: Vec<&str> = string
+                    .split(is_whitespace)
+                    .filter(|word| !word.is_empty())
+                    .collect();
+                Value::String(words.join(" "))
+            }
+            Function::Translate => {
+                let from: Vec<char> = string(1).chars().collect();
+                let to: Vec<char> = string(2).chars().collect();
+                let translated = string(0)
+                    .chars()
+                    .filter_map(|ch| match from.iter().position(|&from| from == ch) {
+                        Some(index) => to.get(index).copied(),
+                        None => Some(ch),
+                    })
+                    .collect();
+                Value::String(translated)
+            }
+            Function::Boolean => Value::Boolean(value(0).boolean()),
+            Function::Not => Value::Boolean(!value(0).boolean()),
+            Function::True => Value::Boolean(true),
+            Function::False => Value::Boolean(false),
+            Function::Lang => Value::Boolean(lang(context.node, &string(0))),
+            Function::Number => Value::Number(match arguments.len() {
+                0 => string_to_number(&context.node.string_value()),
+                _ => value(0).number(),
+            }),
+            Function::Sum => Value::Number(
+                self.nodes(&arguments[0], context)
+                    .iter()
+                    .map(|node| string_to_number(&node.string_value()))
+                    .sum(),
+            ),
+            Function::Floor => Value::Number(value(0).number().floor()),
+            Function::Ceiling => Value::Number(value(0).number().ceil()),
+            Function::Round => Value::Number(round(value(0).number())),
+        }
+    }
+}
+
+fn is_reverse(axis: Axis) -> bool {
+    matches!(
+        axis,
+        Axis::Ancestor | Axis::AncestorOrSelf | Axis::Preceding | Axis::PrecedingSibling
+    )
+}
+
+/// The nodes on `axis` from `node`, in the axis' order: document order for
+/// forward axes, and reverse document order for reverse ones.
+fn axis(axis: Axis, node: XPathNode) -> Vec<XPathNode> {
+    let tree_node = match node {
+        XPathNode::Node(node) => node,
+        XPathNode::Attribute { element, .. } => element.as_node(),
+    };
+    let is_attribute = matches!(node, XPathNode::Attribute { .. });
+    let nodes: Vec<Node> = match axis {
+        Axis::Attribute | Axis::Namespace | Axis::Self_ => Vec::new(),
+        Axis::Child | Axis::Descendant | Axis::DescendantOrSelf if is_attribute => Vec::new(),
+        Axis::Child => tree_node.children().collect(),
+        Axis::Descendant => tree_node.descendants().collect(),
+        Axis::DescendantOrSelf => std::iter::once(tree_node)
+            .chain(tree_node.descendants())
+            .collect(),
+        Axis::Parent => node.parent().into_iter().collect(),
+        Axis::Ancestor => successors(node.parent(), Node::parent).collect(),
+        Axis::AncestorOrSelf if is_attribute => successors(node.parent(), Node::parent).collect(),
+        Axis::AncestorOrSelf => successors(Some(tree_node), Node::parent).collect(),
+        Axis::FollowingSibling | Axis::PrecedingSibling if is_attribute => Vec::new(),
+        Axis::FollowingSibling => {
+            successors(tree_node.next_sibling(), Node::next_sibling).collect()
+        }
+        Axis::PrecedingSibling => {
+            successors(tree_node.previous_sibling(), Node::previous_sibling).collect()
+        }
+        Axis::Following => {
+            // An attribute is followed by the contents of its element.
+            let mut nodes: Vec<Node> = match is_attribute {
+                true => tree_node.descendants().collect(),
+                false => Vec::new(),
+            };
+            for ancestor in successors(Some(tree_node), Node::parent) {
+                for sibling in successors(ancestor.next_sibling(), Node::next_sibling) {
+                    nodes.push(sibling);
+                    nodes.extend(sibling.descendants());
+                }
+            }
+            nodes
+        }
+        Axis::Preceding => {
+            let mut nodes = Vec::new();
+            for ancestor in successors(Some(tree_node), Node::parent) {
+                for sibling in successors(ancestor.previous_sibling(), Node::previous_sibling) {
+                    let mut subtree: Vec<Node> = sibling.descendants().collect();
+                    subtree.reverse();
+                    nodes.extend(subtree);
+                    nodes.push(sibling);
+                }
+            }
+            nodes
+        }
+    };
+
+    let mut nodes: Vec<XPathNode> = nodes
+        .into_iter()
+        .filter(|node| !node.is_doctype())
+        .map(XPathNode::Node)
+        .collect();
+    match axis {
+        Axis::Self_ => nodes.push(node),
+        Axis::AncestorOrSelf if is_attribute => nodes.insert(0, node),
+        Axis::Attribute if !is_attribute => {
+            if let Some(element) = tree_node.as_element() {
+                let attributes = element.attributes().iter().enumerate();
+                nodes.extend(
+                    attributes
+                        .filter(|(_, attribute)| {
+                            // Namespace declarations aren't attributes.
+                            attribute.name() != "xmlns" && !attribute.name().starts_with("xmlns:")
+                        })
+                        .map(|(index, _)| XPathNode::Attribute { element, index }),
+                );
+            }
+        }
+        _ => {}
+    }
+    nodes
+}
+
+fn matches_test(test: &NodeTest, axis: Axis, node: XPathNode) -> bool {
+    // Names are matched against attributes on the attribute axis, and
+    // elements on every other.
+    let principal = match (axis, node) {
+        (Axis::Attribute, XPathNode::Attribute { element, .. }) => Some(element),
+        (Axis::Attribute, _) => None,
+        (_, XPathNode::Node(node)) => node.as_element(),
+        (_, XPathNode::Attribute { .. }) => None,
+    };
+    let html = principal.is_some_and(|element| element.namespace() == Namespace::Html);
+    let same = |name: &str, test: &str| match html {
+        true => name.eq_ignore_ascii_case(test),
+        false => name == test,
+    };
+    match test {
+        NodeTest::Any => principal.is_some(),
+        NodeTest::Prefix(prefix) => {
+            principal.is_some()
+                && node
+                    .name()
+                    .split_once(':')
+                    .is_some_and(|(node_prefix, _)| same(node_prefix, prefix))
+        }
+        NodeTest::Name(name) => principal.is_some() && same(node.name(), name),
+        NodeTest::Node => true,
+        NodeTest::Text => node.as_node().is_some_and(|node| node.is_text()),
+        NodeTest::Comment => node.as_node().is_some_and(|node| node.is_comment()),
+        NodeTest::ProcessingInstruction => false,
+    }
+}
+
+fn namespace_uri<'a>(node: XPathNode<'a>) -> &'a str {
+    match node {
+        XPathNode::Node(node) => node
+            .as_element()
+            .map_or("", |element| element.namespace().uri()),
+        XPathNode::Attribute { .. } => match node.name().split_once(':') {
+            Some(("xlink", _)) => "http://www.w3.org/1999/xlink",
+            Some(("xml", _)) => "http://www.w3.org/XML/1998/namespace",
+            _ => "",
+        },
+    }
+}
+
+/// Whether the language of `node`, from the closest `xml:lang` or `lang`
+/// attribute, is `language` or a dialect of it.
+fn lang(node: XPathNode, language: &str) -> bool {
+    let start = match node {
+        XPathNode::Node(node) => Some(node),
+        XPathNode::Attribute { element, .. } => Some(element.as_node()),
+    };
+    let value = successors(start, Node::parent)
+        .filter_map(|node| node.as_element())
+        .find_map(|element| {
+            element
+                .get_attribute("xml:lang")
+                .or_else(|| element.get_attribute("lang"))
+        });
+    value.is_some_and(|value| {
+        value.len() >= language.len()
+            && value[..language.len()].eq_ignore_ascii_case(language)
+            && (value.len() == language.len() || value[language.len()..].starts_with('-'))
+    })
+}
+
+fn compare(comparison: Comparison, left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::NodeSet(left), Value::NodeSet(right)) => {
+            let right: Vec<String> = right.iter().map(XPathNode::string_value).collect();
+            left.iter().any(|node| {
+                let left = Value::String(node.string_value());
+                right
+                    .iter()
+                    .any(|right| compare_atoms(comparison, &left, &Value::String(right.clone())))
+            })
+        }
+        (Value::NodeSet(nodes), other) => compare_node_set(comparison, nodes, other),
+        (other, Value::NodeSet(nodes)) => compare_node_set(flip(comparison), nodes, other),
+        (left, right) => compare_atoms(comparison, left, right),
+    }
+}
+
+/// The comparison with its operands swapped.
+fn flip(comparison: Comparison) -> Comparison {
+    match comparison {
+        Comparison::Less => Comparison::Greater,
+        Comparison::LessOrEqual => Comparison::GreaterOrEqual,
+        Comparison::Greater => Comparison::Less,
+        Comparison::GreaterOrEqual => Comparison::LessOrEqual,
+        comparison => comparison,
+    }
+}
+
+/// Compares a node-set to another value, which is true when comparing the
+/// string value of any node in it is.
+fn compare_node_set(comparison: Comparison, nodes: &[XPathNode], other: &Value) -> bool {
+    if let Value::Boolean(_) = other {
+        return compare_atoms(comparison, &Value::Boolean(!nodes.is_empty()), other);
+    }
+    nodes.iter().any(|node| {
+        let string = node.string_value();
+        let value = match other {
+            Value::Number(_) => Value::Number(string_to_number(&string)),
+            _ => Value::String(string),
+        };
+        compare_atoms(comparison, &value, other)
+    })
+}
+
+fn compare_atoms(comparison: Comparison, left: &Value, right: &Value) -> bool {
+    match comparison {
+        Comparison::Equal | Comparison::NotEqual => {
+            let equal = match (left, right) {
+                (Value::Boolean(_), _) | (_, Value::Boolean(_)) => {
+                    left.boolean() == right.boolean()
+                }
+                (Value::Number(_), _) | (_, Value::Number(_)) => left.number() == right.number(),
+                _ => left.string() == right.string(),
+            };
+            equal == (comparison == Comparison::Equal)
+        }
+        Comparison::Less => left.number() < right.number(),
+        Comparison::LessOrEqual => left.number() <= right.number(),
+        Comparison::Greater => left.number() > right.number(),
+        Comparison::GreaterOrEqual => left.number() >= right.number(),
+    }
+}
@@ -0,0 +1,142 @@
+use crate::Type;
+
+/// The functions of the XPath 1.0 core function library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Function {
+    Last,
+    Position,
+    Count,
+    Id,
+    LocalName,
+    NamespaceUri,
+    Name,
+    String,
+    Concat,
+    StartsWith,
+    Contains,
+    SubstringBefore,
+    SubstringAfter,
+    Substring,
+    StringLength,
+    NormalizeSpace,
+    Translate,
+    Boolean,
+    Not,
+    True,
+    False,
+    Lang,
+    Number,
+    Sum,
+    Floor,
+    Ceiling,
+    Round,
+}
+
+impl Function {
+    pub(crate) fn from_name(name: &str) -> Option<Function> {
+        Some(match name {
+            "last" => Function::Last,
+            "position" => Function::Position,
+            "count" => Function::Count,
+            "id" => Function::Id,
+            "local-name" => Function::LocalName,
+            "namespace-uri" => Function::NamespaceUri,
+            "name" => Function::Name,
+            "string" => Function::String,
+            "concat" => Function::Concat,
+            "starts-with" => Function::StartsWith,
+            "contains" => Function::Contains,
+            "substring-before" => Function::SubstringBefore,
+            "substring-after" => Function::SubstringAfter,
+            "substring" => Function::Substring,
+            "string-length" => Function::StringLength,
+            "normalize-space" => Function::NormalizeSpace,
+            "translate" => Function::Translate,
+            "boolean" => Function::Boolean,
+            "not" => Function::Not,
+            "true" => Function::True,
+            "false" => Function::False,
+            "lang" => Function::Lang,
+            "number" => Function::Number,
+            "sum" => Function::Sum,
+            "floor" => Function::Floor,
+            "ceiling" => Function::Ceiling,
+            "round" => Function::Round,
+            _ => return None,
+        })
+    }
+
+    /// The smallest and largest number of arguments the function takes.
+    pub(crate) fn arity(self) -> (usize, usize) {
+        match self {
+            Function::Last | Function::Position | Function::True | Function::False => (0, 0),
+            Function::LocalName
+            | Function::NamespaceUri
+            | Function::Name
+            | Function::String
+            | Function::StringLength
+            | Function::NormalizeSpace
+            | Function::Number => (0, 1),
+            Function::Count
+            | Function::Id
+            | Function::Boolean
+            | Function::Not
+            | Function::Lang
+            | Function::Sum
+            | Function::Floor
+            | Function::Ceiling
+            | Function::Round => (1, 1),
+            Function::StartsWith
+            | Function::Contains
+            | Function::SubstringBefore
+            | Function::SubstringAfter => (2, 2),
+            Function::Substring => (2, 3),
+            Function::Translate => (3, 3),
+            Function::Concat => (2, usize::MAX),
+        }
+    }
+
+    /// Whether the function's arguments have to be node-sets.
+    pub(crate) fn takes_node_sets(self) -> bool {
+        matches!(
+            self,
+            Function::Count
+                | Function::LocalName
+                | Function::NamespaceUri
+                | Function::Name
+                | Function::Sum
+        )
+    }
+
+    pub(crate) fn returns(self) -> Type {
+        match self {
+            Function::Id => Type::NodeSet,
+            Function::LocalName
+            | Function::NamespaceUri
+            | Function::Name
+            | Function::String
+            | Function::Concat
+            | Function::SubstringBefore
+            | Function::SubstringAfter
+            | Function::Substring
+            | Function::NormalizeSpace
+            | Function::Translate => Type::String,
+            Function::StartsWith
+            | Function::Contains
+            | Function::Boolean
+            | Function::Not
+            | Function::True
+            | Function::False
+            | Function::Lang => Type::Boolean,
+            Function::Last
+            | Function::Position
+            | Function::Count
+            | Function::StringLength
+            | Function::Number
+            | Function::Sum
+            | Function::Floor
+            | Function::Ceiling
+            | Function::Round => Type::Number,
+        }
+    }
+}
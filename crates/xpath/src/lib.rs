@@ -0,0 +1,329 @@
+//! XPath 1.0 expressions over a document.
+//!
+//! ```
+//! use xpath::{Value, XPath};
+//!
+//! let document = parser::parse_html("<ul><li class=done>a<li>b</ul>");
+//! let xpath = XPath::parse("//li[not(@class)]").unwrap();
+//! let texts: Vec<_> = xpath
+//!     .select(document.root())
+//!     .iter()
+//!     .map(|node| node.string_value())
+//!     .collect();
+//! assert_eq!(texts, vec!["b"]);
+//!
+//! let count = XPath::parse("count(//li)").unwrap();
+//! assert_eq!(count.evaluate(document.root()), Value::Number(2.0));
+//! ```
+//!
+//! The whole of XPath 1.0 is supported apart from variables, and the
+//! namespace axis, which is always empty. Documents have no namespace
+//! declarations, so names with a prefix, such as `xlink:href`, are compared
+//! as they are written. Like in browsers, the names of HTML elements and
+//! their attributes are compared ignoring ASCII case. Doctypes aren't part of
+//! the XPath data model and are never selected.
+
+mod eval;
+mod functions;
+mod parse;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use ast::{Attribute, Element, Node};
+
+use eval::Evaluator;
+use functions::Function;
+
+/// A parsed XPath expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XPath {
+    expr: Expr,
+}
+
+impl XPath {
+    pub fn parse(source: &str) -> Result<XPath, XPathError> {
+        parse::parse(source).map(|expr| XPath { expr })
+    }
+
+    /// Evaluates the expression with `node` as the context node.
+    pub fn evaluate<'a>(&self, node: Node<'a>) -> Value<'a> {
+        let evaluator = Evaluator::new(node.document());
+        evaluator.evaluate(&self.expr, XPathNode::Node(node))
+    }
+
+    /// The nodes the expression selects with `node` as the context node, in
+    /// document order. Expressions that give a string, number or boolean
+    /// select nothing.
+    pub fn select<'a>(&self, node: Node<'a>) -> Vec<XPathNode<'a>> {
+        match self.evaluate(node) {
+            Value::NodeSet(nodes) => nodes,
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FromStr for XPath {
+    type Err = XPathError;
+
+    fn from_str(source: &str) -> Result<XPath, XPathError> {
+        XPath::parse(source)
+    }
+}
+
+/// The result of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    /// Nodes in document order, without duplicates.
+    NodeSet(Vec<XPathNode<'a>>),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+}
+
+impl Value<'_> {
+    /// Converts the value as the `string()` function does.
+    pub fn string(&self) -> String {
+        match self {
+            Value::NodeSet(nodes) => nodes
+                .first()
+                .map(XPathNode::string_value)
+                .unwrap_or_default(),
+            Value::String(string) => string.clone(),
+            Value::Number(number) => eval::number_to_string(*number),
+            Value::Boolean(boolean) => boolean.to_string(),
+        }
+    }
+
+    /// Converts the value as the `number()` function does.
+    pub fn number(&self) -> f64 {
+        match self {
+            Value::Number(number) => *number,
+            Value::Boolean(boolean) => f64::from(u8::from(*boolean)),
+            value => eval::string_to_number(&value.string()),
+        }
+    }
+
+    /// Converts the value as the `boolean()` function does.
+    pub fn boolean(&self) -> bool {
+        match self {
+            Value::NodeSet(nodes) => !nodes.is_empty(),
+            Value::String(string) => !string.is_empty(),
+            Value::Number(number) => *number != 0.0 && !number.is_nan(),
+            Value::Boolean(boolean) => *boolean,
+        }
+    }
+}
+
+/// A node as XPath sees it: a node of the document, or an attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XPathNode<'a> {
+    Node(Node<'a>),
+    /// The attribute at `index` on `element`.
+    Attribute {
+        element: Element<'a>,
+        index: usize,
+    },
+}
+
+impl<'a> XPathNode<'a> {
+    pub fn as_node(&self) -> Option<Node<'a>> {
+        match self {
+            XPathNode::Node(node) => Some(*node),
+            XPathNode::Attribute { .. } => None,
+        }
+    }
+
+    pub fn as_element(&self) -> Option<Element<'a>> {
+        self.as_node().and_then(|node| node.as_element())
+    }
+
+    pub fn as_attribute(&self) -> Option<&'a Attribute> {
+        match self {
+            XPathNode::Node(_) => None,
+            XPathNode::Attribute { element, index } => Some(&element.attributes()[*index]),
+        }
+    }
+
+    /// The parent of a node, or the element an attribute is on.
+    pub fn parent(&self) -> Option<Node<'a>> {
+        match self {
+            XPathNode::Node(node) => node.parent(),
+            XPathNode::Attribute { element, .. } => Some(element.as_node()),
+        }
+    }
+
+    /// The name of an element or attribute, and empty for other nodes.
+    pub fn name(&self) -> &'a str {
+        match self {
+            XPathNode::Node(node) => node.as_element().map_or("", |element| element.tag_name()),
+            XPathNode::Attribute { element, index } => element.attributes()[*index].name(),
+        }
+    }
+
+    /// The text of a node: the value of an attribute, the text in an
+    /// element or document, or the text of a text or comment node.
+    pub fn string_value(&self) -> String {
+        match self {
+            XPathNode::Node(node) => match node.as_comment() {
+                Some(comment) => comment.text().to_string(),
+                None if node.is_doctype() => String::new(),
+                None => node.text_content(),
+            },
+            XPathNode::Attribute { .. } => self.as_attribute().unwrap().value().to_string(),
+        }
+    }
+}
+
+/// Why an expression could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XPathError {
+    pub message: String,
+    /// The byte offset in the expression where the problem was found.
+    pub position: usize,
+}
+
+impl fmt::Display for XPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for XPathError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+    Arithmetic(Arithmetic, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Union(Box<Expr>, Box<Expr>),
+    /// A primary expression followed by predicates, e.g. `(//p)[1]`.
+    Filter(Box<Expr>, Vec<Expr>),
+    Path(Path),
+    Literal(String),
+    Number(f64),
+    Call(Function, Vec<Expr>),
+}
+
+/// The types of values, which are known for every expression before it is
+/// evaluated, since there are no variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    NodeSet,
+    String,
+    Number,
+    Boolean,
+}
+
+impl Expr {
+    fn value_type(&self) -> Type {
+        match self {
+            Expr::Or(..) | Expr::And(..) | Expr::Compare(..) => Type::Boolean,
+            Expr::Arithmetic(..) | Expr::Negate(_) | Expr::Number(_) => Type::Number,
+            Expr::Union(..) | Expr::Filter(..) | Expr::Path(_) => Type::NodeSet,
+            Expr::Literal(_) => Type::String,
+            Expr::Call(function, _) => function.returns(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arithmetic {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+}
+
+/// Steps taken from a starting point, e.g. `/html/body` or `$x//p`.
+#[derive(Debug, Clone, PartialEq)]
+struct Path {
+    start: Start,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Start {
+    /// The root of the tree holding the context node.
+    Root,
+    /// The context node, for relative paths.
+    Context,
+    /// The nodes of a filter expression.
+    Expr(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Ancestor,
+    AncestorOrSelf,
+    Attribute,
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Following,
+    FollowingSibling,
+    Namespace,
+    Parent,
+    Preceding,
+    PrecedingSibling,
+    Self_,
+}
+
+impl Axis {
+    fn from_name(name: &str) -> Option<Axis> {
+        Some(match name {
+            "ancestor" => Axis::Ancestor,
+            "ancestor-or-self" => Axis::AncestorOrSelf,
+            "attribute" => Axis::Attribute,
+            "child" => Axis::Child,
+            "descendant" => Axis::Descendant,
+            "descendant-or-self" => Axis::DescendantOrSelf,
+            "following" => Axis::Following,
+            "following-sibling" => Axis::FollowingSibling,
+            "namespace" => Axis::Namespace,
+            "parent" => Axis::Parent,
+            "preceding" => Axis::Preceding,
+            "preceding-sibling" => Axis::PrecedingSibling,
+            "self" => Axis::Self_,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeTest {
+    /// `*`, any element, or any attribute on the attribute axis.
+    Any,
+    /// `prefix:*`.
+    Prefix(String),
+    Name(String),
+    /// `node()`
+    Node,
+    /// `text()`
+    Text,
+    /// `comment()`
+    Comment,
+    /// `processing-instruction()`, which HTML documents don't have.
+    ProcessingInstruction,
+}
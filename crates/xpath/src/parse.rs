@@ -0,0 +1,631 @@
+use crate::{
+    Arithmetic, Axis, Comparison, Expr, Function, NodeTest, Path, Start, Step, Type, XPathError,
+};
+
+pub(crate) fn parse(source: &str) -> Result<Expr, XPathError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end: source.len(),
+    };
+    let expr = parser.expr()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected token"));
+    }
+    Ok(expr)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Dot,
+    DotDot,
+    At,
+    Comma,
+    ColonColon,
+    Slash,
+    DoubleSlash,
+    Pipe,
+    Plus,
+    Minus,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Multiply,
+    And,
+    Or,
+    Mod,
+    Div,
+    Literal(String),
+    Number(f64),
+    /// `*`, `prefix:*` or a name.
+    NameTest(String),
+    /// `node`, `text`, `comment` or `processing-instruction` before `(`.
+    NodeType(String),
+    FunctionName(String),
+    AxisName(String),
+    Variable(String),
+}
+
+impl Token {
+    /// Whether the token is an operator, after which `*` is a name test and
+    /// names aren't operators.
+    fn is_operator(&self) -> bool {
+        matches!(
+            self,
+            Token::And
+                | Token::Or
+                | Token::Mod
+                | Token::Div
+                | Token::Multiply
+                | Token::Slash
+                | Token::DoubleSlash
+                | Token::Pipe
+                | Token::Plus
+                | Token::Minus
+                | Token::Equal
+                | Token::NotEqual
+                | Token::Less
+                | Token::LessOrEqual
+                | Token::Greater
+                | Token::GreaterOrEqual
+        )
+    }
+}
+
+fn is_whitespace(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\n' | '\r')
+}
+
+fn is_name_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | '\u{b7}')
+}
+
+/// Splits an expression into tokens, telling apart the tokens that are
+/// written the same way as the specification's lexical rules describe.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, XPathError> {
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut position = 0;
+    let error = |message: &str, position| XPathError {
+        message: message.to_string(),
+        position,
+    };
+    let rest = |position: usize| &source[position..];
+
+    while let Some(ch) = rest(position).chars().next() {
+        if is_whitespace(ch) {
+            position += 1;
+            continue;
+        }
+        let start = position;
+        // After an operand, `*` multiplies and names are operators.
+        let after_operand = tokens.last().is_some_and(|(token, _)| {
+            !token.is_operator()
+                && !matches!(
+                    token,
+                    Token::At
+                        | Token::ColonColon
+                        | Token::LeftParen
+                        | Token::LeftBracket
+                        | Token::Comma
+                )
+        });
+        let next = rest(position).chars().nth(1);
+        let token = match ch {
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            ',' => Token::Comma,
+            '@' => Token::At,
+            '|' => Token::Pipe,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '=' => Token::Equal,
+            '!' if next == Some('=') => Token::NotEqual,
+            '<' if next == Some('=') => Token::LessOrEqual,
+            '<' => Token::Less,
+            '>' if next == Some('=') => Token::GreaterOrEqual,
+            '>' => Token::Greater,
+            '/' if next == Some('/') => Token::DoubleSlash,
+            '/' => Token::Slash,
+            ':' if next == Some(':') => Token::ColonColon,
+            '.' if next == Some('.') => Token::DotDot,
+            '.' if !next.is_some_and(|ch| ch.is_ascii_digit()) => Token::Dot,
+            '*' if after_operand => Token::Multiply,
+            '*' => Token::NameTest("*".to_string()),
+            '"' | '\'' => {
+                let length = rest(position + 1)
+                    .find(ch)
+                    .ok_or_else(|| error("unterminated string", start))?;
+                let literal = rest(position + 1)[..length].to_string();
+                position += length + 2;
+                tokens.push((Token::Literal(literal), start));
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let length = rest(position)
+                    .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+                    .unwrap_or(rest(position).len());
+                let number = &rest(position)[..length];
+                let number = number.parse().map_err(|_| error("invalid number", start))?;
+                position += length;
+                tokens.push((Token::Number(number), start));
+                continue;
+            }
+            '$' => {
+                let length = name_length(rest(position + 1), true);
+                if length == 0 {
+                    return Err(error("expected a variable name", start + 1));
+                }
+                let name = rest(position + 1)[..length].to_string();
+                position += length + 1;
+                tokens.push((Token::Variable(name), start));
+                continue;
+            }
+            ch if is_name_start(ch) => {
+                let length = name_length(rest(position), false);
+                let name = &rest(position)[..length];
+                position += length;
+                let following = rest(position).trim_start_matches(is_whitespace);
+                let token = if after_operand {
+                    match name {
+                        "and" => Token::And,
+                        "or" => Token::Or,
+                        "mod" => Token::Mod,
+                        "div" => Token::Div,
+                        _ => return Err(error("expected an operator", start)),
+                    }
+                } else if following.starts_with("::") {
+                    Token::AxisName(name.to_string())
+                } else {
+                    // Take the rest of a qualified name or a `prefix:*` test.
+                    let name = if following.len() == rest(position).len()
+                        && rest(position).starts_with(':')
+                        && !rest(position).starts_with("::")
+                    {
+                        let local = rest(position + 1);
+                        let length = if local.starts_with('*') {
+                            1
+                        } else {
+                            name_length(local, false)
+                        };
+                        if length == 0 {
+                            return Err(error("expected a name", position + 1));
+                        }
+                        position += length + 1;
+                        &source[start..position]
+                    } else {
+                        name
+                    };
+                    let following = rest(position).trim_start_matches(is_whitespace);
+                    match name {
+                        _ if !following.starts_with('(') => Token::NameTest(name.to_string()),
+                        "node" | "text" | "comment" | "processing-instruction" => {
+                            Token::NodeType(name.to_string())
+                        }
+                        _ => Token::FunctionName(name.to_string()),
+                    }
+                };
+                tokens.push((token, start));
+                continue;
+            }
+            ch => return Err(error(&format!("unexpected `{ch}`"), start)),
+        };
+        position += match token {
+            Token::NotEqual
+            | Token::LessOrEqual
+            | Token::GreaterOrEqual
+            | Token::DoubleSlash
+            | Token::ColonColon
+            | Token::DotDot => 2,
+            _ => 1,
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// The length of the name at the start of `source`, which may have a prefix
+/// if `qualified`.
+fn name_length(source: &str, qualified: bool) -> usize {
+    let local = |source: &str| match source.chars().next() {
+        Some(ch) if is_name_start(ch) => source
+            .find(|ch: char| !is_name_char(ch))
+            .unwrap_or(source.len()),
+        _ => 0,
+    };
+    let length = local(source);
+    match source[length..].strip_prefix(':') {
+        Some(rest) if qualified && length > 0 && local(rest) > 0 => length + 1 + local(rest),
+        _ => length,
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    /// The length of the source, where errors at its end are reported.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|(token, _)| token.clone());
+        self.index += 1;
+        token
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), XPathError> {
+        if self.eat(&expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {description}")))
+        }
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end, |&(_, position)| position)
+    }
+
+    fn error(&self, message: &str) -> XPathError {
+        XPathError {
+            message: message.to_string(),
+            position: self.position(),
+        }
+    }
+
+    fn node_set(&self, expr: Expr, position: usize) -> Result<Expr, XPathError> {
+        if expr.value_type() == Type::NodeSet {
+            Ok(expr)
+        } else {
+            Err(XPathError {
+                message: "expected a node-set".to_string(),
+                position,
+            })
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.and_expr()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.equality_expr()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.equality_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn equality_expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.relational_expr()?;
+        loop {
+            let comparison = match self.peek() {
+                Some(Token::Equal) => Comparison::Equal,
+                Some(Token::NotEqual) => Comparison::NotEqual,
+                _ => return Ok(expr),
+            };
+            self.bump();
+            let right = self.relational_expr()?;
+            expr = Expr::Compare(comparison, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn relational_expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.additive_expr()?;
+        loop {
+            let comparison = match self.peek() {
+                Some(Token::Less) => Comparison::Less,
+                Some(Token::LessOrEqual) => Comparison::LessOrEqual,
+                Some(Token::Greater) => Comparison::Greater,
+                Some(Token::GreaterOrEqual) => Comparison::GreaterOrEqual,
+                _ => return Ok(expr),
+            };
+            self.bump();
+            let right = self.additive_expr()?;
+            expr = Expr::Compare(comparison, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn additive_expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.multiplicative_expr()?;
+        loop {
+            let operation = match self.peek() {
+                Some(Token::Plus) => Arithmetic::Add,
+                Some(Token::Minus) => Arithmetic::Subtract,
+                _ => return Ok(expr),
+            };
+            self.bump();
+            let right = self.multiplicative_expr()?;
+            expr = Expr::Arithmetic(operation, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn multiplicative_expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.unary_expr()?;
+        loop {
+            let operation = match self.peek() {
+                Some(Token::Multiply) => Arithmetic::Multiply,
+                Some(Token::Div) => Arithmetic::Divide,
+                Some(Token::Mod) => Arithmetic::Modulo,
+                _ => return Ok(expr),
+            };
+            self.bump();
+            let right = self.unary_expr()?;
+            expr = Expr::Arithmetic(operation, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn unary_expr(&mut self) -> Result<Expr, XPathError> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Negate(Box::new(self.unary_expr()?)));
+        }
+        self.union_expr()
+    }
+
+    fn union_expr(&mut self) -> Result<Expr, XPathError> {
+        let position = self.position();
+        let mut expr = self.path_expr()?;
+        while self.eat(&Token::Pipe) {
+            let left = self.node_set(expr, position)?;
+            let position = self.position();
+            let right = self.path_expr()?;
+            let right = self.node_set(right, position)?;
+            expr = Expr::Union(Box::new(left), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn path_expr(&mut self) -> Result<Expr, XPathError> {
+        let position = self.position();
+        let start = match self.peek() {
+            Some(
+                Token::LeftParen
+                | Token::Literal(_)
+                | Token::Number(_)
+                | Token::FunctionName(_)
+                | Token::Variable(_),
+            ) => self.filter_expr()?,
+            Some(Token::Slash) => {
+                self.bump();
+                let steps = match self.starts_step() {
+                    true => self.relative_path()?,
+                    false => Vec::new(),
+                };
+                return Ok(Expr::Path(Path {
+                    start: Start::Root,
+                    steps,
+                }));
+            }
+            Some(Token::DoubleSlash) => {
+                self.bump();
+                let mut steps = vec![descendant_or_self()];
+                steps.extend(self.relative_path()?);
+                return Ok(Expr::Path(Path {
+                    start: Start::Root,
+                    steps,
+                }));
+            }
+            _ => {
+                return Ok(Expr::Path(Path {
+                    start: Start::Context,
+                    steps: self.relative_path()?,
+                }));
+            }
+        };
+
+        let mut steps = Vec::new();
+        loop {
+            if self.eat(&Token::DoubleSlash) {
+                steps.push(descendant_or_self());
+            } else if !self.eat(&Token::Slash) {
+                break;
+            }
+            steps.push(self.step()?);
+        }
+        if steps.is_empty() {
+            return Ok(start);
+        }
+        Ok(Expr::Path(Path {
+            start: Start::Expr(Box::new(self.node_set(start, position)?)),
+            steps,
+        }))
+    }
+
+    fn filter_expr(&mut self) -> Result<Expr, XPathError> {
+        let position = self.position();
+        let primary = match self.bump() {
+            Some(Token::LeftParen) => {
+                let expr = self.expr()?;
+                self.expect(Token::RightParen, "`)`")?;
+                expr
+            }
+            Some(Token::Literal(literal)) => Expr::Literal(literal),
+            Some(Token::Number(number)) => Expr::Number(number),
+            Some(Token::FunctionName(name)) => self.function_call(&name, position)?,
+            Some(Token::Variable(_)) => {
+                return Err(XPathError {
+                    message: "variables are not supported".to_string(),
+                    position,
+                })
+            }
+            _ => unreachable!("only called at the start of a primary expression"),
+        };
+        let predicates = self.predicates()?;
+        if predicates.is_empty() {
+            return Ok(primary);
+        }
+        Ok(Expr::Filter(
+            Box::new(self.node_set(primary, position)?),
+            predicates,
+        ))
+    }
+
+    fn function_call(&mut self, name: &str, position: usize) -> Result<Expr, XPathError> {
+        let function = Function::from_name(name).ok_or_else(|| XPathError {
+            message: format!("unknown function `{name}`"),
+            position,
+        })?;
+        self.expect(Token::LeftParen, "`(`")?;
+        let mut arguments = Vec::new();
+        if !self.eat(&Token::RightParen) {
+            loop {
+                let position = self.position();
+                let argument = self.expr()?;
+                arguments.push(match function.takes_node_sets() {
+                    true => self.node_set(argument, position)?,
+                    false => argument,
+                });
+                if self.eat(&Token::RightParen) {
+                    break;
+                }
+                self.expect(Token::Comma, "`,` or `)`")?;
+            }
+        }
+        let (min, max) = function.arity();
+        if arguments.len() < min || arguments.len() > max {
+            return Err(XPathError {
+                message: format!("wrong number of arguments to `{name}`"),
+                position,
+            });
+        }
+        Ok(Expr::Call(function, arguments))
+    }
+
+    fn starts_step(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(
+                Token::Dot
+                    | Token::DotDot
+                    | Token::At
+                    | Token::AxisName(_)
+                    | Token::NameTest(_)
+                    | Token::NodeType(_)
+            )
+        )
+    }
+
+    fn relative_path(&mut self) -> Result<Vec<Step>, XPathError> {
+        let mut steps = vec![self.step()?];
+        loop {
+            if self.eat(&Token::DoubleSlash) {
+                steps.push(descendant_or_self());
+            } else if !self.eat(&Token::Slash) {
+                return Ok(steps);
+            }
+            steps.push(self.step()?);
+        }
+    }
+
+    fn step(&mut self) -> Result<Step, XPathError> {
+        let axis = match self.peek() {
+            Some(Token::Dot) | Some(Token::DotDot) => {
+                let axis = match self.bump() {
+                    Some(Token::Dot) => Axis::Self_,
+                    _ => Axis::Parent,
+                };
+                return Ok(Step {
+                    axis,
+                    test: NodeTest::Node,
+                    predicates: Vec::new(),
+                });
+            }
+            Some(Token::At) => {
+                self.bump();
+                Axis::Attribute
+            }
+            Some(Token::AxisName(name)) => {
+                let axis = Axis::from_name(name)
+                    .ok_or_else(|| self.error(&format!("unknown axis `{name}`")))?;
+                self.bump();
+                self.expect(Token::ColonColon, "`::`")?;
+                axis
+            }
+            _ => Axis::Child,
+        };
+
+        let test = match self.bump() {
+            Some(Token::NameTest(name)) => match name.strip_suffix(":*") {
+                _ if name == "*" => NodeTest::Any,
+                Some(prefix) => NodeTest::Prefix(prefix.to_string()),
+                None => NodeTest::Name(name),
+            },
+            Some(Token::NodeType(name)) => {
+                self.expect(Token::LeftParen, "`(`")?;
+                let test = match name.as_str() {
+                    "node" => NodeTest::Node,
+                    "text" => NodeTest::Text,
+                    "comment" => NodeTest::Comment,
+                    _ => {
+                        // The name of the processing instruction to match.
+                        if let Some(Token::Literal(_)) = self.peek() {
+                            self.bump();
+                        }
+                        NodeTest::ProcessingInstruction
+                    }
+                };
+                self.expect(Token::RightParen, "`)`")?;
+                test
+            }
+            _ => {
+                self.index -= 1;
+                return Err(self.error("expected a step"));
+            }
+        };
+        Ok(Step {
+            axis,
+            test,
+            predicates: self.predicates()?,
+        })
+    }
+
+    fn predicates(&mut self) -> Result<Vec<Expr>, XPathError> {
+        let mut predicates = Vec::new();
+        while self.eat(&Token::LeftBracket) {
+            predicates.push(self.expr()?);
+            self.expect(Token::RightBracket, "`]`")?;
+        }
+        Ok(predicates)
+    }
+}
+
+/// The step `//` stands for.
+fn descendant_or_self() -> Step {
+    Step {
+        axis: Axis::DescendantOrSelf,
+        test: NodeTest::Node,
+        predicates: Vec::new(),
+    }
+}
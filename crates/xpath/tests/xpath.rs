@@ -0,0 +1,253 @@
+use parser::parse_html;
+use xpath::{Value, XPath};
+
+const PAGE: &str = "<!DOCTYPE html>\
+    <html lang=en-US><head><title>Page</title></head><body>\
+    <ul id=menu>\
+    <li class=\"item first\"><a href=/>Home</a></li>\
+    <li class=item><a href=/docs data-kind=Guide>Docs</a></li>\
+    <li class=\"item last\"><a>Blog</a></li>\
+    </ul>\
+    <main><h1>Title</h1><p>One</p><p>Two <em>three</em></p><!-- note --><p id=four>  Four \n items </p></main>\
+    <table><tr><td>1</td><td>2.5</td><td>x</td></tr></table>\
+    </body></html>";
+
+/// The string values of the nodes `expression` selects.
+fn select(expression: &str) -> Vec<String> {
+    let document = parse_html(PAGE);
+    let xpath = XPath::parse(expression).unwrap();
+    xpath
+        .select(document.root())
+        .iter()
+        .map(|node| node.string_value())
+        .collect()
+}
+
+fn evaluate(expression: &str) -> String {
+    let document = parse_html(PAGE);
+    XPath::parse(expression)
+        .unwrap()
+        .evaluate(document.root())
+        .string()
+}
+
+#[test]
+fn location_paths() {
+    assert_eq!(select("/html/head/title"), vec!["Page"]);
+    assert_eq!(select("//li/a"), vec!["Home", "Docs", "Blog"]);
+    assert_eq!(select("//main/p[2]/em"), vec!["three"]);
+    assert_eq!(
+        select("//em/../.."),
+        vec!["TitleOneTwo three  Four \n items "]
+    );
+    assert_eq!(select("//UL/LI[@CLASS='item']"), vec!["Docs"]);
+    assert_eq!(select("/"), vec![select("/html")[0].clone()]);
+    assert!(select("//p/nonexistent").is_empty());
+}
+
+#[test]
+fn axes() {
+    assert_eq!(
+        select("//li[1]/following-sibling::li"),
+        vec!["Docs", "Blog"]
+    );
+    assert_eq!(select("//li[3]/preceding-sibling::li[1]"), vec!["Docs"]);
+    assert_eq!(select("//em/ancestor::*[2]/h1"), vec!["Title"]);
+    assert_eq!(select("//em/ancestor-or-self::em"), vec!["three"]);
+    assert_eq!(
+        select("//h1/following::p"),
+        vec!["One", "Two three", "  Four \n items "]
+    );
+    assert_eq!(select("//h1/following::text()[1]"), vec!["One"]);
+    assert_eq!(select("//h1/preceding::a[1]"), vec!["Blog"]);
+    assert_eq!(evaluate("count(//h1/preceding::*)"), "9");
+    assert_eq!(select("//main/descendant::em"), vec!["three"]);
+    assert_eq!(
+        select("//li[2]/descendant-or-self::*/@*"),
+        vec!["item", "/docs", "Guide"]
+    );
+    assert_eq!(select("//a[@data-kind]/parent::li/@class"), vec!["item"]);
+    assert_eq!(select("//a/@href/parent::a"), vec!["Home", "Docs"]);
+    assert_eq!(select("//title/self::title"), vec!["Page"]);
+    assert_eq!(select("//main/comment()"), vec![" note "]);
+    assert!(select("//main/namespace::*").is_empty());
+}
+
+#[test]
+fn predicates() {
+    assert_eq!(select("//li[last()]"), vec!["Blog"]);
+    assert_eq!(select("//li[position() > 1][1]"), vec!["Docs"]);
+    assert_eq!(select("(//a)[2]"), vec!["Docs"]);
+    assert_eq!(select("//li[a[not(@href)]]"), vec!["Blog"]);
+    assert_eq!(select("//li[contains(@class, 'first')]"), vec!["Home"]);
+    assert_eq!(select("//p[em]"), vec!["Two three"]);
+    assert_eq!(select("//*[@id='menu']/li[.='Docs']"), vec!["Docs"]);
+    assert_eq!(select("//a | //h1"), vec!["Home", "Docs", "Blog", "Title"]);
+}
+
+#[test]
+fn core_functions() {
+    assert_eq!(evaluate("count(//li)"), "3");
+    assert_eq!(evaluate("normalize-space(//p[3])"), "Four items");
+    assert_eq!(evaluate("string(//a/@href)"), "/");
+    assert_eq!(evaluate("string(//li[2])"), "Docs");
+    assert_eq!(evaluate("name(//*[@id='menu'])"), "ul");
+    assert_eq!(evaluate("local-name(//a/@href)"), "href");
+    assert_eq!(
+        evaluate("namespace-uri(//p)"),
+        "http://www.w3.org/1999/xhtml"
+    );
+    assert_eq!(evaluate("concat('a', 1, true())"), "a1true");
+    assert_eq!(evaluate("substring('12345', 1.5, 2.6)"), "234");
+    assert_eq!(evaluate("substring('12345', 0, 3)"), "12");
+    assert_eq!(evaluate("substring-before('1999/04/01', '/')"), "1999");
+    assert_eq!(evaluate("substring-after('1999/04/01', '/')"), "04/01");
+    assert_eq!(evaluate("translate('--aaa--', 'abc-', 'ABC')"), "AAA");
+    assert_eq!(evaluate("string-length('日本語')"), "3");
+    assert_eq!(evaluate("starts-with(//title, 'Pa')"), "true");
+    assert_eq!(evaluate("id('four menu')/@id"), "menu");
+    assert_eq!(evaluate("count(id('four menu'))"), "2");
+    assert_eq!(evaluate("boolean(//h1[lang('EN')])"), "true");
+    assert_eq!(evaluate("count(//h1[lang('en')])"), "1");
+    assert_eq!(evaluate("count(//h1[lang('fr')])"), "0");
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(evaluate("1 + 2 * 3"), "7");
+    assert_eq!(evaluate("7 div 2"), "3.5");
+    assert_eq!(evaluate("-7 mod 3"), "-1");
+    assert_eq!(evaluate("1 div 0"), "Infinity");
+    assert_eq!(evaluate("0 div 0"), "NaN");
+    assert_eq!(evaluate("-0"), "0");
+    assert_eq!(evaluate("number('  12.5 ')"), "12.5");
+    assert_eq!(evaluate("number('1e3')"), "NaN");
+    assert_eq!(evaluate("sum(//td[position() < 3])"), "3.5");
+    assert_eq!(evaluate("sum(//td)"), "NaN");
+    assert_eq!(evaluate("round(2.5)"), "3");
+    assert_eq!(evaluate("round(-2.5)"), "-2");
+    assert_eq!(evaluate("floor(-1.5)"), "-2");
+    assert_eq!(evaluate("ceiling(1.2)"), "2");
+    assert_eq!(evaluate("1000000 * 1000000"), "1000000000000");
+    assert_eq!(evaluate("count(//td)*2"), "6");
+}
+
+#[test]
+fn comparisons() {
+    assert_eq!(evaluate("//td = 2.5"), "true");
+    assert_eq!(evaluate("//td != 2.5"), "true");
+    assert_eq!(evaluate("//td > 2"), "true");
+    assert_eq!(evaluate("3 < //td"), "false");
+    assert_eq!(evaluate("//td = 'x'"), "true");
+    assert_eq!(evaluate("//li = //a"), "true");
+    assert_eq!(evaluate("//nothing = //nothing"), "false");
+    assert_eq!(evaluate("//nothing = false()"), "true");
+    assert_eq!(evaluate("'1.0' = 1"), "true");
+    assert_eq!(evaluate("'abc' = 'abc' and not(1 = 2) or false()"), "true");
+}
+
+#[test]
+fn values_have_types() {
+    let document = parse_html(PAGE);
+    let root = document.root();
+    let value = |expression: &str| XPath::parse(expression).unwrap().evaluate(root);
+    assert_eq!(value("count(//p)"), Value::Number(3.0));
+    assert_eq!(value("'text'"), Value::String("text".to_string()));
+    assert_eq!(value("//p and //h1"), Value::Boolean(true));
+    match value("//a/@href") {
+        Value::NodeSet(nodes) => {
+            assert_eq!(nodes.len(), 2);
+            assert_eq!(nodes[0].name(), "href");
+            assert_eq!(nodes[1].parent().unwrap().text_content(), "Docs");
+            assert_eq!(nodes[0].as_attribute().unwrap().value(), "/");
+        }
+        value => panic!("expected nodes, found {:?}", value),
+    }
+    assert!(XPath::parse("count(//p)").unwrap().select(root).is_empty());
+}
+
+#[test]
+fn relative_to_a_context_node() {
+    let document = parse_html(PAGE);
+    let list = XPath::parse("//ul").unwrap().select(document.root())[0]
+        .as_node()
+        .unwrap();
+    let texts = |expression: &str| -> Vec<String> {
+        XPath::parse(expression)
+            .unwrap()
+            .select(list)
+            .iter()
+            .map(|node| node.string_value())
+            .collect()
+    };
+    assert_eq!(texts("li[2]"), vec!["Docs"]);
+    assert_eq!(texts(".//a[. = 'Blog']"), vec!["Blog"]);
+    assert_eq!(texts("/html/head/title"), vec!["Page"]);
+    assert_eq!(texts("../main/h1"), vec!["Title"]);
+}
+
+#[test]
+fn errors_have_positions() {
+    let error = |expression: &str| {
+        let error = XPath::parse(expression).unwrap_err();
+        (error.message, error.position)
+    };
+    assert_eq!(error("//p[1"), ("expected `]`".to_string(), 5));
+    assert_eq!(
+        error("frobnicate(1)"),
+        ("unknown function `frobnicate`".to_string(), 0)
+    );
+    assert_eq!(error("count(1)"), ("expected a node-set".to_string(), 6));
+    assert_eq!(
+        error("concat('a')"),
+        ("wrong number of arguments to `concat`".to_string(), 0)
+    );
+    assert_eq!(
+        error("sideways::p"),
+        ("unknown axis `sideways`".to_string(), 0)
+    );
+    assert_eq!(error("'open"), ("unterminated string".to_string(), 0));
+    assert_eq!(error("$x"), ("variables are not supported".to_string(), 0));
+    assert_eq!(error("1 + "), ("expected a step".to_string(), 4));
+    assert_eq!(error("p q"), ("expected an operator".to_string(), 2));
+    assert_eq!(
+        XPath::parse("//p[").unwrap_err().to_string(),
+        "expected a step at position 4"
+    );
+}
+
+#[test]
+fn star_and_names_depend_on_context() {
+    // `*` multiplies after an operand, and operator names are names
+    // elsewhere.
+    assert_eq!(evaluate("count(//li) * 2"), "6");
+    let document = parse_html("<div><and>a</and><div>b</div></div>");
+    let xpath = XPath::parse("//div/and | //div/*/div").unwrap();
+    let texts: Vec<_> = xpath
+        .select(document.root())
+        .iter()
+        .map(|node| node.string_value())
+        .collect();
+    assert_eq!(texts, vec!["a"]);
+    assert_eq!(
+        XPath::parse("count(//div) div 2")
+            .unwrap()
+            .evaluate(document.root()),
+        Value::Number(1.0)
+    );
+}
+
+#[test]
+fn svg_names_keep_their_case() {
+    let document =
+        parse_html("<svg><clipPath id=c /><foreignObject><DIV>x</DIV></foreignObject></svg>");
+    let count = |expression: &str| {
+        XPath::parse(expression)
+            .unwrap()
+            .evaluate(document.root())
+            .number()
+    };
+    assert_eq!(count("count(//clipPath)"), 1.0);
+    assert_eq!(count("count(//clippath)"), 0.0);
+    assert_eq!(count("count(//foreignObject/div)"), 1.0);
+}